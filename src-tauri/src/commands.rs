@@ -3,15 +3,24 @@ use serde_json::Value;
 use std::path::PathBuf;
 
 use crate::comparison::{
-    ComparisonPagination, ComparisonSegment, ComparisonSegmentPage, ComparisonSnapshot,
+    BoundingBox, ComparisonDiff, ComparisonPagination, ComparisonSegment, ComparisonSegmentPage,
+    ComparisonSnapshot, DuplicateCluster, FuzzyMatchOptions, LooseOnlyReport, MultiListComparison,
+    NearestPlace, OverlapPairing, PendingRows, PlaceComparisonRow, SegmentCursor, SegmentExtent,
+    SegmentFilter, TypeFacet,
 };
 use crate::config::PublicAppConfig;
-use crate::google::{DeviceFlowState, DriveFileMetadata, GoogleIdentity, LoopbackFlowState};
-use crate::ingestion::{ImportSummary, ListSlot};
+use crate::errors::{AppError, CommandError};
+use crate::google::{
+    DeviceFlowState, DriveFileMetadata, GoogleAccount, GoogleIdentity, LoopbackFlowState,
+};
+use crate::ingestion::{
+    CsvImportOptions, ImportHistoryEntry, ImportSummary, ListSlot, RawRowPage, SlotFreshness,
+};
 use crate::places::NormalizationStats;
-use crate::projects::ComparisonProjectRecord;
+use crate::projects::{ComparisonProjectRecord, ComparisonRunRecord};
 use crate::settings::{RuntimeSettings, UpdateRuntimeSettingsPayload};
-use crate::{AppState, ExportSummary, MapStyleDescriptor};
+use crate::telemetry::TelemetryEvent;
+use crate::{AppState, ExportSummary, MapStyleDescriptor, SchemaRepairReport, WalCheckpoint};
 
 #[derive(Debug, Serialize)]
 pub struct FoundationHealth {
@@ -52,8 +61,8 @@ impl FoundationHealth {
 #[tauri::command]
 pub async fn foundation_health(
     state: tauri::State<'_, AppState>,
-) -> Result<FoundationHealth, String> {
-    state.foundation_health().map_err(|err| err.to_string())
+) -> Result<FoundationHealth, CommandError> {
+    state.foundation_health().map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -62,40 +71,57 @@ pub async fn record_telemetry_event(
     name: String,
     payload: Value,
     flush: Option<bool>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     state
         .record_telemetry_event(name, payload, flush.unwrap_or(false))
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn read_telemetry_events(
+    state: tauri::State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<Vec<TelemetryEvent>, CommandError> {
+    state
+        .read_telemetry_events(limit.unwrap_or(50))
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn update_runtime_settings(
     state: tauri::State<'_, AppState>,
     payload: UpdateRuntimeSettingsPayload,
-) -> Result<RuntimeSettings, String> {
+) -> Result<RuntimeSettings, CommandError> {
     state
         .update_runtime_settings(payload)
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn google_start_device_flow(
+pub async fn reset_settings(
     state: tauri::State<'_, AppState>,
-) -> Result<DeviceFlowState, String> {
+    regenerate_salt: Option<bool>,
+) -> Result<RuntimeSettings, CommandError> {
     state
-        .start_device_flow()
-        .await
-        .map_err(|err| err.to_string())
+        .reset_settings(regenerate_salt.unwrap_or(false))
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn google_start_device_flow(
+    state: tauri::State<'_, AppState>,
+) -> Result<DeviceFlowState, CommandError> {
+    state.start_device_flow().await.map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn google_start_loopback_flow(
     state: tauri::State<'_, AppState>,
-) -> Result<LoopbackFlowState, String> {
+) -> Result<LoopbackFlowState, CommandError> {
     state
         .start_loopback_flow()
         .await
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -103,63 +129,81 @@ pub async fn google_complete_sign_in(
     state: tauri::State<'_, AppState>,
     device_code: String,
     interval_secs: Option<u64>,
-) -> Result<GoogleIdentity, String> {
+) -> Result<GoogleIdentity, CommandError> {
     state
         .complete_device_flow(device_code, interval_secs.unwrap_or(5))
         .await
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn google_complete_loopback_sign_in(
     state: tauri::State<'_, AppState>,
     timeout_secs: Option<u64>,
-) -> Result<GoogleIdentity, String> {
+) -> Result<GoogleIdentity, CommandError> {
     state
         .complete_loopback_sign_in(timeout_secs)
         .await
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn google_current_identity(
     state: tauri::State<'_, AppState>,
-) -> Result<GoogleIdentity, String> {
+    force: Option<bool>,
+) -> Result<GoogleIdentity, CommandError> {
     state
-        .current_identity()
+        .current_identity(force.unwrap_or(false))
         .await
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn google_keepalive(state: tauri::State<'_, AppState>) -> Result<GoogleIdentity, String> {
-    state
-        .keepalive_google()
-        .await
-        .map_err(|err| err.to_string())
+pub async fn google_keepalive(
+    state: tauri::State<'_, AppState>,
+) -> Result<GoogleIdentity, CommandError> {
+    state.keepalive_google().await.map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn google_refresh_status(
     state: tauri::State<'_, AppState>,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CommandError> {
     Ok(state.refresh_status_google())
 }
 
 #[tauri::command]
-pub async fn google_sign_out(state: tauri::State<'_, AppState>) -> Result<(), String> {
-    state.sign_out_google().map_err(|err| err.to_string())
+pub async fn google_sign_out(state: tauri::State<'_, AppState>) -> Result<(), CommandError> {
+    state.sign_out_google().await.map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn google_list_accounts(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<GoogleAccount>, CommandError> {
+    state.list_google_accounts().map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn google_switch_account(
+    state: tauri::State<'_, AppState>,
+    email: String,
+) -> Result<GoogleIdentity, CommandError> {
+    state
+        .switch_google_account(email)
+        .await
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn drive_list_kml_files(
     state: tauri::State<'_, AppState>,
     limit: Option<usize>,
-) -> Result<Vec<DriveFileMetadata>, String> {
+) -> Result<Vec<DriveFileMetadata>, CommandError> {
     state
         .list_drive_files(limit)
         .await
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -173,8 +217,8 @@ pub async fn drive_import_kml(
     modified_time: Option<String>,
     size: Option<u64>,
     md5_checksum: Option<String>,
-) -> Result<ImportSummary, String> {
-    let parsed_slot = ListSlot::parse(&slot).map_err(|err| err.to_string())?;
+) -> Result<ImportSummary, CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
     state
         .import_drive_file(
             project_id,
@@ -187,7 +231,74 @@ pub async fn drive_import_kml(
             md5_checksum,
         )
         .await
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn import_both_slots(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    list_a: DriveFileMetadata,
+    list_b: DriveFileMetadata,
+) -> Result<Vec<ImportSummary>, CommandError> {
+    state
+        .import_both_slots(project_id, list_a, list_b)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn check_drive_freshness(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<Vec<SlotFreshness>, CommandError> {
+    state
+        .check_drive_freshness(project_id)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn refresh_drive_metadata(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: String,
+) -> Result<DriveFileMetadata, CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
+    state
+        .refresh_drive_metadata(project_id, parsed_slot)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn import_local_file(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: String,
+    path: String,
+    csv_options: Option<CsvImportOptions>,
+) -> Result<ImportSummary, CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
+    state
+        .import_local_file(project_id, parsed_slot, PathBuf::from(path), csv_options)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn import_kml_string(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: String,
+    name: String,
+    kml: String,
+) -> Result<ImportSummary, CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
+    state
+        .import_kml_string(project_id, parsed_slot, name, kml)
+        .await
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -196,11 +307,79 @@ pub async fn drive_save_selection(
     project_id: Option<i64>,
     slot: String,
     file: Option<DriveFileMetadata>,
-) -> Result<(), String> {
-    let parsed_slot = ListSlot::parse(&slot).map_err(|err| err.to_string())?;
+) -> Result<(), CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
     state
         .save_drive_selection(project_id, parsed_slot, file)
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn rename_list(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: String,
+    display_name: Option<String>,
+) -> Result<String, CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
+    state
+        .rename_list(project_id, parsed_slot, display_name)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn clear_list_slot(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: String,
+) -> Result<(), CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
+    state
+        .clear_list_slot(project_id, parsed_slot)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn swap_list_slots(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<(), CommandError> {
+    state
+        .swap_list_slots(project_id)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn list_import_history(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: Option<String>,
+) -> Result<Vec<ImportHistoryEntry>, CommandError> {
+    let parsed_slot = match slot {
+        Some(value) => Some(ListSlot::parse(&value)?),
+        None => None,
+    };
+    state
+        .list_import_history(project_id, parsed_slot)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn get_raw_rows(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: String,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> Result<RawRowPage, CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
+    state
+        .raw_rows(
+            project_id,
+            parsed_slot,
+            ComparisonPagination::new(page, page_size),
+        )
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -209,20 +388,91 @@ pub async fn refresh_place_details(
     project_id: Option<i64>,
     slot: Option<String>,
     request_id: Option<String>,
-) -> Result<Vec<NormalizationStats>, String> {
+) -> Result<Vec<NormalizationStats>, CommandError> {
     let parsed = match slot {
-        Some(value) => Some(vec![ListSlot::parse(&value).map_err(|err| err.to_string())?]),
+        Some(value) => Some(vec![ListSlot::parse(&value)?]),
         None => None,
     };
     state
         .refresh_place_details(project_id, parsed, request_id)
         .await
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
-pub async fn cancel_refresh_queue(state: tauri::State<'_, AppState>) -> Result<(), String> {
-    state.cancel_refresh_queue().map_err(|err| err.to_string())
+pub async fn retry_pending_rows(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: String,
+    request_id: Option<String>,
+) -> Result<NormalizationStats, CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
+    state
+        .retry_pending_rows(project_id, parsed_slot, request_id)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn normalize_slot_dry_run(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: String,
+) -> Result<NormalizationStats, CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
+    state
+        .normalize_slot_dry_run(project_id, parsed_slot)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn cancel_refresh_queue(state: tauri::State<'_, AppState>) -> Result<(), CommandError> {
+    state.cancel_refresh_queue().map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn cancel_import(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: Option<String>,
+) -> Result<bool, CommandError> {
+    let parsed_slot = match slot {
+        Some(value) => Some(ListSlot::parse(&value)?),
+        None => None,
+    };
+    state
+        .cancel_import(project_id, parsed_slot)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn purge_stale_cache(state: tauri::State<'_, AppState>) -> Result<usize, CommandError> {
+    state.purge_stale_cache().map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn repair_schema(
+    state: tauri::State<'_, AppState>,
+) -> Result<SchemaRepairReport, CommandError> {
+    state.repair_schema().await.map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn checkpoint_wal(
+    state: tauri::State<'_, AppState>,
+) -> Result<WalCheckpoint, CommandError> {
+    state.checkpoint_wal().await.map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn clear_normalization_cache(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<usize, CommandError> {
+    state
+        .clear_normalization_cache(project_id)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -231,10 +481,20 @@ pub async fn compare_lists(
     project_id: Option<i64>,
     page: Option<usize>,
     page_size: Option<usize>,
-) -> Result<ComparisonSnapshot, String> {
+    fuzzy: Option<bool>,
+    fuzzy_options: Option<FuzzyMatchOptions>,
+) -> Result<ComparisonSnapshot, CommandError> {
+    let fuzzy = fuzzy
+        .unwrap_or(false)
+        .then(|| fuzzy_options.unwrap_or_default());
     state
-        .comparison_snapshot(project_id, Some(ComparisonPagination::new(page, page_size)))
-        .map_err(|err| err.to_string())
+        .comparison_snapshot(
+            project_id,
+            Some(ComparisonPagination::new(page, page_size)),
+            fuzzy,
+        )
+        .await
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -244,25 +504,204 @@ pub async fn comparison_segment_page(
     segment: String,
     page: Option<usize>,
     page_size: Option<usize>,
-) -> Result<ComparisonSegmentPage, String> {
+    query: Option<String>,
+    type_filter: Option<String>,
+    after: Option<SegmentCursor>,
+) -> Result<ComparisonSegmentPage, CommandError> {
     let parsed_segment = ComparisonSegment::parse(&segment)
-        .ok_or_else(|| format!("unsupported comparison segment: {segment}"))?;
+        .ok_or_else(|| AppError::Config(format!("unsupported comparison segment: {segment}")))?;
     state
         .comparison_segment_page(
             project_id,
             parsed_segment,
             ComparisonPagination::new(page, page_size),
+            after,
+            SegmentFilter { query, type_filter },
         )
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn segment_in_bounds(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    segment: String,
+    bounds: BoundingBox,
+) -> Result<Vec<PlaceComparisonRow>, CommandError> {
+    let parsed_segment = ComparisonSegment::parse(&segment)
+        .ok_or_else(|| AppError::Config(format!("unsupported comparison segment: {segment}")))?;
+    state
+        .segment_in_bounds(project_id, parsed_segment, bounds)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn overlap_pairing(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<Vec<OverlapPairing>, CommandError> {
+    state
+        .overlap_pairing(project_id)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn segment_bounds(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    segment: Option<String>,
+) -> Result<Option<SegmentExtent>, CommandError> {
+    let parsed_segment = segment
+        .map(|value| {
+            ComparisonSegment::parse(&value)
+                .ok_or_else(|| AppError::Config(format!("unsupported comparison segment: {value}")))
+        })
+        .transpose()?;
+    state
+        .segment_bounds(project_id, parsed_segment)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn segment_nearest(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    segment: String,
+    lat: f64,
+    lng: f64,
+    limit: Option<usize>,
+) -> Result<Vec<NearestPlace>, CommandError> {
+    let parsed_segment = ComparisonSegment::parse(&segment)
+        .ok_or_else(|| AppError::Config(format!("unsupported comparison segment: {segment}")))?;
+    state
+        .segment_nearest(project_id, parsed_segment, lat, lng, limit)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn loose_only_sets(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    radius_m: Option<f64>,
+) -> Result<LooseOnlyReport, CommandError> {
+    state
+        .loose_only_sets(project_id, radius_m)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn place_photo_url(
+    state: tauri::State<'_, AppState>,
+    place_id: String,
+    max_width: Option<u32>,
+) -> Result<Option<String>, CommandError> {
+    state
+        .place_photo_url(place_id, max_width)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn segment_geojson(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    segment: Option<String>,
+    bounds: Option<BoundingBox>,
+) -> Result<Value, CommandError> {
+    let parsed_segment = segment
+        .map(|value| {
+            ComparisonSegment::parse(&value)
+                .ok_or_else(|| AppError::Config(format!("unsupported comparison segment: {value}")))
+        })
+        .transpose()?;
+    state
+        .segment_geojson(project_id, parsed_segment, bounds)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn segment_type_facets(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    segment: String,
+    query: Option<String>,
+) -> Result<Vec<TypeFacet>, CommandError> {
+    let parsed_segment = ComparisonSegment::parse(&segment)
+        .ok_or_else(|| AppError::Config(format!("unsupported comparison segment: {segment}")))?;
+    state
+        .segment_type_facets(project_id, parsed_segment, query)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn comparison_diff(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: String,
+) -> Result<ComparisonDiff, CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
+    state
+        .comparison_diff(project_id, parsed_slot)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn compare_slots(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<MultiListComparison, CommandError> {
+    state
+        .compare_slots(project_id)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn list_pending_rows(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+) -> Result<Vec<PendingRows>, CommandError> {
+    state
+        .list_pending_rows(project_id)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn find_duplicates(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    slot: String,
+    options: Option<FuzzyMatchOptions>,
+) -> Result<Vec<DuplicateCluster>, CommandError> {
+    let parsed_slot = ListSlot::parse(&slot)?;
+    state
+        .find_duplicates(project_id, parsed_slot, options.unwrap_or_default())
+        .await
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn list_comparison_projects(
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<ComparisonProjectRecord>, String> {
+) -> Result<Vec<ComparisonProjectRecord>, CommandError> {
+    state.list_comparison_projects().map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn comparison_run_history(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<ComparisonRunRecord>, CommandError> {
     state
-        .list_comparison_projects()
-        .map_err(|err| err.to_string())
+        .comparison_run_history(project_id, limit)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -270,10 +709,10 @@ pub async fn create_comparison_project(
     state: tauri::State<'_, AppState>,
     name: String,
     activate: Option<bool>,
-) -> Result<ComparisonProjectRecord, String> {
+) -> Result<ComparisonProjectRecord, CommandError> {
     state
         .create_comparison_project(name, activate.unwrap_or(true))
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
@@ -281,27 +720,91 @@ pub async fn rename_comparison_project(
     state: tauri::State<'_, AppState>,
     project_id: i64,
     name: String,
-) -> Result<ComparisonProjectRecord, String> {
+) -> Result<ComparisonProjectRecord, CommandError> {
     state
         .rename_comparison_project(project_id, name)
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn set_project_rate_limit(
+    state: tauri::State<'_, AppState>,
+    project_id: i64,
+    qps: Option<u32>,
+) -> Result<ComparisonProjectRecord, CommandError> {
+    state
+        .set_project_rate_limit(project_id, qps)
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn set_active_comparison_project(
     state: tauri::State<'_, AppState>,
     project_id: i64,
-) -> Result<ComparisonProjectRecord, String> {
+) -> Result<ComparisonProjectRecord, CommandError> {
     state
         .set_active_comparison_project(project_id)
-        .map_err(|err| err.to_string())
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn ensure_single_active_project(
+    state: tauri::State<'_, AppState>,
+) -> Result<ComparisonProjectRecord, CommandError> {
+    state
+        .ensure_single_active_project()
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn delete_comparison_project(
+    state: tauri::State<'_, AppState>,
+    project_id: i64,
+) -> Result<ComparisonProjectRecord, CommandError> {
+    state
+        .delete_comparison_project(project_id)
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn duplicate_comparison_project(
+    state: tauri::State<'_, AppState>,
+    project_id: i64,
+) -> Result<ComparisonProjectRecord, CommandError> {
+    state
+        .duplicate_comparison_project(project_id)
+        .await
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn export_project(
+    state: tauri::State<'_, AppState>,
+    project_id: Option<i64>,
+    destination: String,
+) -> Result<(), CommandError> {
+    state
+        .export_project(project_id, PathBuf::from(destination))
+        .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub async fn import_project(
+    state: tauri::State<'_, AppState>,
+    source: String,
+) -> Result<ComparisonProjectRecord, CommandError> {
+    state
+        .import_project(PathBuf::from(source))
+        .await
+        .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub async fn map_style_descriptor(
     state: tauri::State<'_, AppState>,
-) -> Result<MapStyleDescriptor, String> {
-    Ok(state.map_style_descriptor())
+    style: Option<String>,
+) -> Result<MapStyleDescriptor, CommandError> {
+    Ok(state.map_style_descriptor(style.as_deref()))
 }
 
 #[tauri::command]
@@ -312,11 +815,20 @@ pub async fn export_comparison_segment(
     format: String,
     destination: String,
     place_ids: Option<Vec<String>>,
-) -> Result<ExportSummary, String> {
+    neutralize_formulas: Option<bool>,
+) -> Result<ExportSummary, CommandError> {
     let parsed_segment = ComparisonSegment::parse(&segment)
-        .ok_or_else(|| format!("unsupported comparison segment: {segment}"))?;
+        .ok_or_else(|| AppError::Config(format!("unsupported comparison segment: {segment}")))?;
     let path = PathBuf::from(destination);
     state
-        .export_comparison_segment(project_id, parsed_segment, &format, place_ids, path)
-        .map_err(|err| err.to_string())
+        .export_comparison_segment(
+            project_id,
+            parsed_segment,
+            &format,
+            place_ids,
+            path,
+            neutralize_formulas.unwrap_or(true),
+        )
+        .await
+        .map_err(CommandError::from)
 }
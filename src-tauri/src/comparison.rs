@@ -1,13 +1,36 @@
-use std::cmp;
+use std::cmp::{self, Ordering};
+use std::collections::BTreeMap;
 
-use rusqlite::{Connection, OptionalExtension, Row};
-use serde::Serialize;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
 
 use crate::errors::{AppError, AppResult};
-use crate::ingestion::ListSlot;
+use crate::ingestion::{list_label, ListSlot};
+use crate::places::{PlaceEnrichment, PLACE_ENRICHMENT_COLUMNS};
 
 const DEFAULT_PAGE_SIZE: usize = 200;
 const MAX_PAGE_SIZE: usize = 1000;
+const DEFAULT_NEAREST_LIMIT: usize = 25;
+const MAX_NEAREST_LIMIT: usize = 200;
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+const METERS_PER_DEGREE_LAT: f64 = 111_195.0;
+const DEFAULT_LOOSE_RADIUS_M: f64 = 50.0;
+const SEGMENT_FILTER: &str = "WHERE project_id = ?1
+    AND (?2 IS NULL
+        OR name LIKE ?2 ESCAPE '\\'
+        OR formatted_address LIKE ?2 ESCAPE '\\')
+    AND (?3 IS NULL OR EXISTS (
+        SELECT 1 FROM json_each(CASE WHEN json_valid(types) THEN types END)
+        WHERE json_each.value = ?3
+    ))";
+
+/// Keyset condition for rows strictly after a cursor, in the segment sort order.
+const AFTER_CURSOR: &str = "AND (name COLLATE UNICODE_NOCASE > ?4
+        OR (name COLLATE UNICODE_NOCASE = ?4 AND place_id > ?5))";
 
 #[derive(Debug, Serialize, Clone)]
 pub struct ComparisonSnapshot {
@@ -25,12 +48,70 @@ pub struct ComparisonSegmentPage {
     pub total: usize,
     pub page: usize,
     pub page_size: usize,
+    /// At least 1, so an empty segment still reports a single (empty) page.
+    pub total_pages: usize,
+    pub has_next: bool,
+    pub has_prev: bool,
+    /// Key of the last returned row when more rows follow; pass back as `after`.
+    pub next_cursor: Option<SegmentCursor>,
+}
+
+/// Position in a segment's `name`, `place_id` ordering, used for keyset paging.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SegmentCursor {
+    pub name: String,
+    pub place_id: String,
+}
+
+impl SegmentCursor {
+    fn from_row(row: &PlaceComparisonRow) -> Self {
+        Self {
+            name: row.name.clone(),
+            place_id: row.place_id.clone(),
+        }
+    }
+}
+
+impl ComparisonSegmentPage {
+    fn new(rows: Vec<PlaceComparisonRow>, total: usize, page: usize, page_size: usize) -> Self {
+        let total_pages = total_pages(total, page_size);
+        Self {
+            rows,
+            total,
+            page,
+            page_size,
+            total_pages,
+            has_next: page < total_pages,
+            has_prev: page > 1,
+            next_cursor: None,
+        }
+    }
+
+    fn with_next_cursor(mut self) -> Self {
+        if self.has_next {
+            self.next_cursor = self.rows.last().map(SegmentCursor::from_row);
+        }
+        self
+    }
 }
 
 #[derive(Debug, Serialize, Clone)]
 pub struct ComparisonLists {
     pub list_a_id: Option<i64>,
     pub list_b_id: Option<i64>,
+    /// User-facing labels; the slot defaults unless the lists were renamed.
+    pub list_a_name: String,
+    pub list_b_name: String,
+}
+
+impl ComparisonLists {
+    pub fn label(&self, slot: ListSlot) -> &str {
+        match slot {
+            ListSlot::A => &self.list_a_name,
+            ListSlot::B => &self.list_b_name,
+            other => other.display_name(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -50,6 +131,21 @@ pub struct ComparisonStats {
     pub pending_b: usize,
 }
 
+/// An imported row that never resolved to a place, as the source file described it.
+#[derive(Debug, Serialize, Clone)]
+pub struct PendingRow {
+    pub source_row_hash: String,
+    pub title: Option<String>,
+    pub lat: Option<f64>,
+    pub lng: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PendingRows {
+    pub slot: ListSlot,
+    pub rows: Vec<PendingRow>,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct PlaceComparisonRow {
     pub place_id: String,
@@ -59,6 +155,184 @@ pub struct PlaceComparisonRow {
     pub lng: f64,
     pub types: Vec<String>,
     pub lists: Vec<ListSlot>,
+    /// Place id of the list B entry this row was fuzzily matched with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fuzzy_match: Option<String>,
+    /// When Places details were last fetched for this place; the newer of the pair for fuzzy
+    /// overlap matches.
+    pub last_checked_at: Option<String>,
+    /// How the row's place was resolved: `api`, `cache`, `places_table`, `provided` or
+    /// `synthetic`. `None` for assignments written before this was tracked.
+    pub resolution_source: Option<String>,
+    #[serde(flatten)]
+    pub enrichment: PlaceEnrichment,
+}
+
+/// Thresholds for treating two places without a shared `place_id` as the same place.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct FuzzyMatchOptions {
+    /// Minimum Jaro-Winkler similarity of the normalized names, between 0 and 1.
+    pub name_similarity: f64,
+    pub max_distance_m: f64,
+}
+
+impl Default for FuzzyMatchOptions {
+    fn default() -> Self {
+        Self {
+            name_similarity: 0.9,
+            max_distance_m: 100.0,
+        }
+    }
+}
+
+impl FuzzyMatchOptions {
+    pub fn matches(&self, a: &PlaceComparisonRow, b: &PlaceComparisonRow) -> Option<f64> {
+        if distance_m(a.lat, a.lng, b.lat, b.lng) > self.max_distance_m {
+            return None;
+        }
+        let similarity = name_similarity(&a.name, &b.name);
+        (similarity >= self.name_similarity).then_some(similarity)
+    }
+}
+
+/// Strict only-A/only-B places that also have no pin from the other list within `radius_m`,
+/// whatever its `place_id`.
+#[derive(Debug, Serialize, Clone)]
+pub struct LooseOnlyReport {
+    pub radius_m: f64,
+    pub loose_only_a: Vec<PlaceComparisonRow>,
+    pub loose_only_b: Vec<PlaceComparisonRow>,
+}
+
+/// Places within one list that look like the same place pinned more than once.
+#[derive(Debug, Serialize, Clone)]
+pub struct DuplicateCluster {
+    pub places: Vec<PlaceComparisonRow>,
+}
+
+/// Optional narrowing applied to a segment before it is counted and paginated.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SegmentFilter {
+    /// Case-insensitive substring matched against the name or formatted address.
+    pub query: Option<String>,
+    /// Exact entry that must be present in the place's `types` array.
+    pub type_filter: Option<String>,
+}
+
+impl SegmentFilter {
+    fn type_filter(&self) -> Option<&str> {
+        self.type_filter
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// Map viewport in degrees. `min_lng > max_lng` describes a box crossing the antimeridian.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub min_lat: f64,
+    pub min_lng: f64,
+    pub max_lat: f64,
+    pub max_lng: f64,
+}
+
+impl BoundingBox {
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.min_lng > self.max_lng
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        let finite = [self.min_lat, self.min_lng, self.max_lat, self.max_lng]
+            .iter()
+            .all(|value| value.is_finite());
+        if !finite || self.min_lat > self.max_lat {
+            return Err(AppError::Config(format!("invalid bounding box: {self:?}")));
+        }
+        Ok(())
+    }
+
+    fn lng_clause(&self) -> &'static str {
+        if self.crosses_antimeridian() {
+            "(lng >= ?4 OR lng <= ?5)"
+        } else {
+            "lng BETWEEN ?4 AND ?5"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SegmentExtent {
+    #[serde(flatten)]
+    pub bounds: BoundingBox,
+    pub center_lat: f64,
+    pub center_lng: f64,
+}
+
+/// Where each list's own row placed an overlap place, and how far apart those pins are.
+#[derive(Debug, Serialize, Clone)]
+pub struct OverlapPairing {
+    pub place_id: String,
+    pub name: String,
+    pub a_lat: f64,
+    pub a_lng: f64,
+    pub b_lat: f64,
+    pub b_lng: f64,
+    pub distance_m: f64,
+}
+
+/// A segment row with its great-circle distance from the caller's reference point.
+#[derive(Debug, Serialize, Clone)]
+pub struct NearestPlace {
+    #[serde(flatten)]
+    pub place: PlaceComparisonRow,
+    pub distance_m: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TypeFacet {
+    #[serde(rename = "type")]
+    pub place_type: String,
+    pub count: usize,
+}
+
+/// Places that belong to exactly `slots` and to none of the project's other lists.
+#[derive(Debug, Serialize, Clone)]
+pub struct SlotSet {
+    pub slots: Vec<ListSlot>,
+    pub rows: Vec<PlaceComparisonRow>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MultiListComparison {
+    pub project: ComparisonProjectInfo,
+    pub slots: Vec<ListSlot>,
+    pub sets: Vec<SlotSet>,
+}
+
+impl MultiListComparison {
+    pub fn rows_in_exactly(&self, slots: &[ListSlot]) -> &[PlaceComparisonRow] {
+        let mut wanted = slots.to_vec();
+        wanted.sort();
+        wanted.dedup();
+        self.sets
+            .iter()
+            .find(|set| set.slots == wanted)
+            .map(|set| set.rows.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ComparisonDiff {
+    pub slot: ListSlot,
+    /// Comparison run the current assignments were diffed against, if any run exists.
+    pub baseline_run_id: Option<i64>,
+    pub baseline_completed_at: Option<String>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -69,6 +343,12 @@ pub enum ComparisonSegment {
 }
 
 impl ComparisonSegment {
+    pub const ALL: [ComparisonSegment; 3] = [
+        ComparisonSegment::Overlap,
+        ComparisonSegment::OnlyA,
+        ComparisonSegment::OnlyB,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             ComparisonSegment::Overlap => "overlap",
@@ -112,19 +392,22 @@ impl ComparisonPagination {
                 page_size: self.page_size,
             };
         }
-        let pages = (total + self.page_size - 1) / self.page_size;
-        let capped_page = cmp::min(self.page, pages);
+        let capped_page = cmp::min(self.page, total_pages(total, self.page_size));
         Self {
             page: capped_page.max(1),
             page_size: self.page_size,
         }
     }
 
-    fn offset(&self) -> i64 {
+    pub(crate) fn offset(&self) -> i64 {
         self.page.saturating_sub(1).saturating_mul(self.page_size) as i64
     }
 }
 
+fn total_pages(total: usize, page_size: usize) -> usize {
+    total.div_ceil(page_size.max(1)).max(1)
+}
+
 impl Default for ComparisonPagination {
     fn default() -> Self {
         Self {
@@ -142,6 +425,9 @@ struct PlaceEntry {
     lat: f64,
     lng: f64,
     types: Vec<String>,
+    last_checked_at: Option<String>,
+    resolution_source: Option<String>,
+    enrichment: PlaceEnrichment,
 }
 
 impl PlaceEntry {
@@ -154,6 +440,10 @@ impl PlaceEntry {
             lng: self.lng,
             types: self.types,
             lists,
+            fuzzy_match: None,
+            last_checked_at: self.last_checked_at,
+            resolution_source: self.resolution_source,
+            enrichment: self.enrichment,
         }
     }
 }
@@ -163,15 +453,30 @@ pub fn compute_snapshot(
     project_id: i64,
     pagination: Option<ComparisonPagination>,
 ) -> AppResult<ComparisonSnapshot> {
+    compute_snapshot_with(conn, project_id, pagination, None)
+}
+
+/// Like [`compute_snapshot`], but with `fuzzy` set, only-A/only-B places whose names and
+/// positions are close enough are promoted to the overlap segment.
+pub fn compute_snapshot_with(
+    conn: &Connection,
+    project_id: i64,
+    pagination: Option<ComparisonPagination>,
+    fuzzy: Option<FuzzyMatchOptions>,
+) -> AppResult<ComparisonSnapshot> {
+    if let Some(options) = fuzzy {
+        return compute_fuzzy_snapshot(conn, project_id, pagination, options);
+    }
+    let unfiltered = SegmentFilter::default();
     let project = project_info(conn, project_id)?;
     let list_a = list_id(conn, project_id, ListSlot::A)?;
     let list_b = list_id(conn, project_id, ListSlot::B)?;
     let stats = ComparisonStats {
         list_a_count: count_places(conn, list_a)?,
         list_b_count: count_places(conn, list_b)?,
-        overlap_count: count_segment(conn, project_id, ComparisonSegment::Overlap)?,
-        only_a_count: count_segment(conn, project_id, ComparisonSegment::OnlyA)?,
-        only_b_count: count_segment(conn, project_id, ComparisonSegment::OnlyB)?,
+        overlap_count: count_segment(conn, project_id, ComparisonSegment::Overlap, &unfiltered)?,
+        only_a_count: count_segment(conn, project_id, ComparisonSegment::OnlyA, &unfiltered)?,
+        only_b_count: count_segment(conn, project_id, ComparisonSegment::OnlyB, &unfiltered)?,
         pending_a: pending_count(conn, list_a)?,
         pending_b: pending_count(conn, list_b)?,
     };
@@ -179,9 +484,27 @@ pub fn compute_snapshot(
     let overlap_page = pagination.map(|p| p.with_total(stats.overlap_count));
     let only_a_page = pagination.map(|p| p.with_total(stats.only_a_count));
     let only_b_page = pagination.map(|p| p.with_total(stats.only_b_count));
-    let overlap = load_segment(conn, project_id, ComparisonSegment::Overlap, overlap_page)?;
-    let only_a = load_segment(conn, project_id, ComparisonSegment::OnlyA, only_a_page)?;
-    let only_b = load_segment(conn, project_id, ComparisonSegment::OnlyB, only_b_page)?;
+    let overlap = load_segment(
+        conn,
+        project_id,
+        ComparisonSegment::Overlap,
+        overlap_page,
+        &unfiltered,
+    )?;
+    let only_a = load_segment(
+        conn,
+        project_id,
+        ComparisonSegment::OnlyA,
+        only_a_page,
+        &unfiltered,
+    )?;
+    let only_b = load_segment(
+        conn,
+        project_id,
+        ComparisonSegment::OnlyB,
+        only_b_page,
+        &unfiltered,
+    )?;
 
     Ok(ComparisonSnapshot {
         project,
@@ -189,6 +512,8 @@ pub fn compute_snapshot(
         lists: ComparisonLists {
             list_a_id: list_a,
             list_b_id: list_b,
+            list_a_name: list_label(conn, project_id, ListSlot::A)?,
+            list_b_name: list_label(conn, project_id, ListSlot::B)?,
         },
         overlap,
         only_a,
@@ -196,13 +521,696 @@ pub fn compute_snapshot(
     })
 }
 
+fn compute_fuzzy_snapshot(
+    conn: &Connection,
+    project_id: i64,
+    pagination: Option<ComparisonPagination>,
+    options: FuzzyMatchOptions,
+) -> AppResult<ComparisonSnapshot> {
+    let strict = compute_snapshot(conn, project_id, None)?;
+    let mut overlap = strict.overlap.rows;
+    let only_b = strict.only_b.rows;
+    let mut matched_b = vec![false; only_b.len()];
+    let mut only_a = Vec::new();
+    for mut row in strict.only_a.rows {
+        let best = only_b
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !matched_b[*index])
+            .filter_map(|(index, candidate)| {
+                options.matches(&row, candidate).map(|score| (index, score))
+            })
+            .max_by(|left, right| left.1.total_cmp(&right.1));
+        match best {
+            Some((index, _)) => {
+                matched_b[index] = true;
+                row.lists = segment_lists(ComparisonSegment::Overlap);
+                row.fuzzy_match = Some(only_b[index].place_id.clone());
+                row.last_checked_at = row
+                    .last_checked_at
+                    .take()
+                    .max(only_b[index].last_checked_at.clone());
+                overlap.push(row);
+            }
+            None => only_a.push(row),
+        }
+    }
+    let only_b: Vec<_> = only_b
+        .into_iter()
+        .zip(matched_b)
+        .filter_map(|(row, matched)| (!matched).then_some(row))
+        .collect();
+    overlap.sort_by(|left, right| compare_names(&left.name, &right.name));
+
+    let mut stats = strict.stats;
+    stats.overlap_count = overlap.len();
+    stats.only_a_count = only_a.len();
+    stats.only_b_count = only_b.len();
+    Ok(ComparisonSnapshot {
+        project: strict.project,
+        stats,
+        lists: strict.lists,
+        overlap: paginate_rows(overlap, pagination),
+        only_a: paginate_rows(only_a, pagination),
+        only_b: paginate_rows(only_b, pagination),
+    })
+}
+
+fn paginate_rows(
+    rows: Vec<PlaceComparisonRow>,
+    pagination: Option<ComparisonPagination>,
+) -> ComparisonSegmentPage {
+    let total = rows.len();
+    match pagination.map(|p| p.with_total(total)) {
+        Some(paging) => ComparisonSegmentPage::new(
+            rows.into_iter()
+                .skip(paging.offset() as usize)
+                .take(paging.page_size)
+                .collect(),
+            total,
+            paging.page,
+            paging.page_size,
+        ),
+        None => ComparisonSegmentPage::new(rows, total, 1, cmp::max(total, 1)),
+    }
+}
+
+pub(crate) fn name_similarity(left: &str, right: &str) -> f64 {
+    let normalize = |value: &str| {
+        value
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    strsim::jaro_winkler(&normalize(left), &normalize(right))
+}
+
+pub(crate) fn distance_m(lat_a: f64, lng_a: f64, lat_b: f64, lng_b: f64) -> f64 {
+    let (phi_a, phi_b) = (lat_a.to_radians(), lat_b.to_radians());
+    let d_phi = (lat_b - lat_a).to_radians();
+    let d_lambda = (lng_b - lng_a).to_radians();
+    let h =
+        (d_phi / 2.0).sin().powi(2) + phi_a.cos() * phi_b.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * h.sqrt().asin()
+}
+
+/// Case- and accent-insensitive sort key: "Zürich" and "zurich" both fold to "zurich".
+fn name_sort_key(value: &str) -> String {
+    value
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Orders by folded name, falling back to the raw text so distinct names never compare equal.
+pub(crate) fn compare_names(left: &str, right: &str) -> Ordering {
+    name_sort_key(left)
+        .cmp(&name_sort_key(right))
+        .then_with(|| left.cmp(right))
+}
+
+/// Registers `haversine(lat_a, lng_a, lat_b, lng_b)`, the great-circle distance in metres, so
+/// queries can order and filter by distance, and the `UNICODE_NOCASE` collation used to
+/// sort segments by name.
+pub fn register_sql_functions(conn: &Connection) -> AppResult<()> {
+    conn.create_collation("UNICODE_NOCASE", compare_names)?;
+    conn.create_scalar_function(
+        "haversine",
+        4,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            Ok(distance_m(
+                ctx.get(0)?,
+                ctx.get(1)?,
+                ctx.get(2)?,
+                ctx.get(3)?,
+            ))
+        },
+    )?;
+    Ok(())
+}
+
+/// Returns up to `limit` rows of a segment, nearest to (`lat`, `lng`) first.
+pub fn segment_nearest(
+    conn: &Connection,
+    project_id: i64,
+    segment: ComparisonSegment,
+    lat: f64,
+    lng: f64,
+    limit: Option<usize>,
+) -> AppResult<Vec<NearestPlace>> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+        return Err(AppError::Config(format!(
+            "invalid reference point: {lat}, {lng}"
+        )));
+    }
+    let limit = limit
+        .unwrap_or(DEFAULT_NEAREST_LIMIT)
+        .clamp(1, MAX_NEAREST_LIMIT);
+    let lists = segment_lists(segment);
+    let table = segment_table(segment);
+    let sql = format!(
+        "SELECT place_id, name, formatted_address, lat, lng, types, last_checked_at,
+            resolution_source, haversine(lat, lng, ?2, ?3) AS distance_m,
+            {PLACE_ENRICHMENT_COLUMNS}
+        FROM {table}
+        WHERE project_id = ?1
+        ORDER BY distance_m, name COLLATE UNICODE_NOCASE
+        LIMIT ?4"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt
+        .query_map(params![project_id, lat, lng, limit as i64], |row| {
+            Ok(NearestPlace {
+                place: parse_place_entry(row)?.into_row(lists.clone()),
+                distance_m: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Narrows the id-based only segments to places with no coordinate-near counterpart in the
+/// other list, so the same spot saved under different ids is not reported as unique.
+pub fn loose_only_sets(
+    conn: &Connection,
+    project_id: i64,
+    radius_m: Option<f64>,
+) -> AppResult<LooseOnlyReport> {
+    let radius_m = radius_m.unwrap_or(DEFAULT_LOOSE_RADIUS_M);
+    if !radius_m.is_finite() || radius_m < 0.0 {
+        return Err(AppError::Config(format!("invalid radius: {radius_m}")));
+    }
+    Ok(LooseOnlyReport {
+        radius_m,
+        loose_only_a: load_loose_only(conn, project_id, ComparisonSegment::OnlyA, radius_m)?,
+        loose_only_b: load_loose_only(conn, project_id, ComparisonSegment::OnlyB, radius_m)?,
+    })
+}
+
+fn load_loose_only(
+    conn: &Connection,
+    project_id: i64,
+    segment: ComparisonSegment,
+    radius_m: f64,
+) -> AppResult<Vec<PlaceComparisonRow>> {
+    let other = match segment {
+        ComparisonSegment::OnlyB => ListSlot::A,
+        _ => ListSlot::B,
+    };
+    let table = segment_table(segment);
+    // The latitude band lets SQLite discard most candidates before computing distances.
+    let mut stmt = conn.prepare(&format!(
+        "SELECT place_id, name, formatted_address, lat, lng, types, last_checked_at,
+            resolution_source, {PLACE_ENRICHMENT_COLUMNS}
+        FROM {table} s
+        WHERE s.project_id = ?1
+            AND NOT EXISTS (
+                SELECT 1
+                FROM lists l
+                JOIN list_places lp ON lp.list_id = l.id
+                JOIN places p ON p.place_id = lp.place_id
+                WHERE l.project_id = ?1 AND l.slot = ?2
+                    AND p.lat BETWEEN s.lat - ?4 AND s.lat + ?4
+                    AND haversine(s.lat, s.lng, p.lat, p.lng) <= ?3
+            )
+        ORDER BY name COLLATE UNICODE_NOCASE, place_id"
+    ))?;
+    let iter = stmt.query_map(
+        params![
+            project_id,
+            other.as_tag(),
+            radius_m,
+            radius_m / METERS_PER_DEGREE_LAT
+        ],
+        parse_place_entry,
+    )?;
+    parse_segment_rows(iter, segment_lists(segment))
+}
+
+pub fn find_duplicates(
+    conn: &Connection,
+    project_id: i64,
+    slot: ListSlot,
+    options: FuzzyMatchOptions,
+) -> AppResult<Vec<DuplicateCluster>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT p.place_id, p.name, p.formatted_address, p.lat, p.lng, p.types, p.last_checked_at,
+            lp.resolution_source, {PLACE_ENRICHMENT_COLUMNS}
+        FROM list_places lp
+        JOIN lists l ON l.id = lp.list_id
+        JOIN places p ON p.place_id = lp.place_id
+        WHERE l.project_id = ?1 AND l.slot = ?2
+        ORDER BY p.lat, p.place_id"
+    ))?;
+    let iter = stmt.query_map(params![project_id, slot.as_tag()], parse_place_entry)?;
+    let rows = parse_segment_rows(iter, vec![slot])?;
+
+    // Rows are sorted by latitude, so the inner scan can stop once the latitude gap alone
+    // exceeds the distance threshold.
+    let max_lat_delta = options.max_distance_m / METERS_PER_DEGREE_LAT;
+    let mut parent: Vec<usize> = (0..rows.len()).collect();
+    for i in 0..rows.len() {
+        for j in i + 1..rows.len() {
+            if rows[j].lat - rows[i].lat > max_lat_delta {
+                break;
+            }
+            if options.matches(&rows[i], &rows[j]).is_some() {
+                let (root_i, root_j) = (find_root(&mut parent, i), find_root(&mut parent, j));
+                parent[root_j] = root_i;
+            }
+        }
+    }
+
+    let mut clusters: Vec<(usize, Vec<PlaceComparisonRow>)> = Vec::new();
+    for (index, row) in rows.into_iter().enumerate() {
+        let root = find_root(&mut parent, index);
+        match clusters.iter_mut().find(|(existing, _)| *existing == root) {
+            Some((_, places)) => places.push(row),
+            None => clusters.push((root, vec![row])),
+        }
+    }
+    let mut clusters: Vec<DuplicateCluster> = clusters
+        .into_iter()
+        .filter(|(_, places)| places.len() > 1)
+        .map(|(_, mut places)| {
+            places.sort_by(|left, right| compare_names(&left.name, &right.name));
+            DuplicateCluster { places }
+        })
+        .collect();
+    clusters.sort_by(|left, right| compare_names(&left.places[0].name, &right.places[0].name));
+    Ok(clusters)
+}
+
+fn find_root(parent: &mut [usize], mut index: usize) -> usize {
+    while parent[index] != index {
+        parent[index] = parent[parent[index]];
+        index = parent[index];
+    }
+    index
+}
+
+/// Partitions every place in the project's lists by the exact set of slots containing it.
+/// Sets are ordered from the widest membership down, so the all-lists overlap comes first.
+pub fn compute_slot_sets(conn: &Connection, project_id: i64) -> AppResult<MultiListComparison> {
+    let project = project_info(conn, project_id)?;
+    let slots = {
+        let mut stmt =
+            conn.prepare("SELECT slot FROM lists WHERE project_id = ?1 ORDER BY slot")?;
+        let tags = stmt
+            .query_map([project_id], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        tags.iter()
+            .map(|tag| ListSlot::parse(tag))
+            .collect::<AppResult<Vec<_>>>()?
+    };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT p.place_id, p.name, p.formatted_address, p.lat, p.lng, p.types, p.last_checked_at,
+            lp.resolution_source, l.slot, {PLACE_ENRICHMENT_COLUMNS}
+        FROM lists l
+        JOIN list_places lp ON lp.list_id = l.id
+        JOIN places p ON p.place_id = lp.place_id
+        WHERE l.project_id = ?1
+        ORDER BY p.place_id, l.slot"
+    ))?;
+    let memberships = stmt
+        .query_map([project_id], |row| {
+            Ok((parse_place_entry(row)?, row.get::<_, String>(8)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut places: Vec<(PlaceEntry, Vec<ListSlot>)> = Vec::new();
+    for (entry, tag) in memberships {
+        let slot = ListSlot::parse(&tag)?;
+        match places.last_mut() {
+            Some((current, member_of)) if current.place_id == entry.place_id => {
+                member_of.push(slot)
+            }
+            _ => places.push((entry, vec![slot])),
+        }
+    }
+
+    let mut grouped: BTreeMap<Vec<ListSlot>, Vec<PlaceComparisonRow>> = BTreeMap::new();
+    for (entry, member_of) in places {
+        grouped
+            .entry(member_of.clone())
+            .or_default()
+            .push(entry.into_row(member_of));
+    }
+    let mut sets: Vec<SlotSet> = grouped
+        .into_iter()
+        .map(|(slots, mut rows)| {
+            rows.sort_by(|left, right| compare_names(&left.name, &right.name));
+            SlotSet { slots, rows }
+        })
+        .collect();
+    sets.sort_by(|left, right| {
+        right
+            .slots
+            .len()
+            .cmp(&left.slots.len())
+            .then_with(|| left.slots.cmp(&right.slots))
+    });
+
+    Ok(MultiListComparison {
+        project,
+        slots,
+        sets,
+    })
+}
+
+/// Compares a slot's current place assignments with the membership snapshot stored by the
+/// project's comparison runs. Runs that saw unchanged lists share the previous snapshot.
+pub fn compute_diff(
+    conn: &Connection,
+    project_id: i64,
+    slot: ListSlot,
+) -> AppResult<ComparisonDiff> {
+    let baseline: Option<(i64, String)> = conn
+        .query_row(
+            "SELECT id, completed_at FROM comparison_runs
+            WHERE project_id = ?1
+            ORDER BY id DESC
+            LIMIT 1",
+            [project_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let baseline_run_id = baseline.as_ref().map(|(id, _)| *id);
+    let snapshot_run_id = match baseline_run_id {
+        Some(_) => crate::projects::latest_member_snapshot(conn, project_id)?,
+        None => None,
+    };
+    let query_ids = |sql: &str| -> AppResult<Vec<String>> {
+        let mut stmt = conn.prepare(sql)?;
+        let ids = stmt
+            .query_map(params![project_id, slot.as_tag(), snapshot_run_id], |row| {
+                row.get(0)
+            })?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(ids)
+    };
+    let added = query_ids(
+        "SELECT lp.place_id
+        FROM list_places lp
+        JOIN lists l ON l.id = lp.list_id
+        WHERE l.project_id = ?1 AND l.slot = ?2
+            AND NOT EXISTS (
+                SELECT 1 FROM comparison_run_members m
+                WHERE m.run_id = ?3 AND m.slot = ?2 AND m.place_id = lp.place_id
+            )
+        ORDER BY lp.place_id",
+    )?;
+    let removed = query_ids(
+        "SELECT m.place_id
+        FROM comparison_run_members m
+        WHERE m.run_id = ?3 AND m.slot = ?2
+            AND NOT EXISTS (
+                SELECT 1 FROM list_places lp
+                JOIN lists l ON l.id = lp.list_id
+                WHERE l.project_id = ?1 AND l.slot = ?2 AND lp.place_id = m.place_id
+            )
+        ORDER BY m.place_id",
+    )?;
+    Ok(ComparisonDiff {
+        slot,
+        baseline_run_id,
+        baseline_completed_at: baseline.map(|(_, completed_at)| completed_at),
+        added,
+        removed,
+    })
+}
+
 pub fn load_segment_page(
     conn: &Connection,
     project_id: i64,
     segment: ComparisonSegment,
     pagination: ComparisonPagination,
+    filter: &SegmentFilter,
 ) -> AppResult<ComparisonSegmentPage> {
-    load_segment(conn, project_id, segment, Some(pagination))
+    load_segment(conn, project_id, segment, Some(pagination), filter)
+}
+
+/// Keyset variant of [`load_segment_page`]: returns up to `page_size` rows strictly after
+/// `after`, so rows inserted or removed between fetches never shift later pages.
+pub fn load_segment_after(
+    conn: &Connection,
+    project_id: i64,
+    segment: ComparisonSegment,
+    after: &SegmentCursor,
+    page_size: usize,
+    filter: &SegmentFilter,
+) -> AppResult<ComparisonSegmentPage> {
+    let page_size = ComparisonPagination::new(None, Some(page_size)).page_size;
+    let total = count_segment(conn, project_id, segment, filter)?;
+    let pattern = like_pattern(filter.query.as_deref());
+    let type_filter = filter.type_filter();
+    let table = segment_table(segment);
+    let remaining: usize = conn.query_row(
+        &format!("SELECT COUNT(*) FROM {table} {SEGMENT_FILTER} {AFTER_CURSOR}"),
+        params![project_id, pattern, type_filter, after.name, after.place_id],
+        |row| row.get::<_, i64>(0).map(|value| value as usize),
+    )?;
+    let sql = format!(
+        "SELECT place_id, name, formatted_address, lat, lng, types, last_checked_at,
+            resolution_source, {PLACE_ENRICHMENT_COLUMNS}
+        FROM {table}
+        {SEGMENT_FILTER} {AFTER_CURSOR}
+        ORDER BY name COLLATE UNICODE_NOCASE, place_id
+        LIMIT ?6"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let iter = stmt.query_map(
+        params![
+            project_id,
+            pattern,
+            type_filter,
+            after.name,
+            after.place_id,
+            page_size as i64
+        ],
+        parse_place_entry,
+    )?;
+    let rows = parse_segment_rows(iter, segment_lists(segment))?;
+
+    let preceding = total.saturating_sub(remaining);
+    let mut page = ComparisonSegmentPage::new(rows, total, preceding / page_size + 1, page_size);
+    page.has_next = remaining > page.rows.len();
+    page.has_prev = preceding > 0;
+    Ok(page.with_next_cursor())
+}
+
+pub fn load_segment_in_bounds(
+    conn: &Connection,
+    project_id: i64,
+    segment: ComparisonSegment,
+    bounds: BoundingBox,
+) -> AppResult<Vec<PlaceComparisonRow>> {
+    bounds.validate()?;
+    let table = segment_table(segment);
+    let sql = format!(
+        "SELECT place_id, name, formatted_address, lat, lng, types, last_checked_at,
+            resolution_source, {PLACE_ENRICHMENT_COLUMNS}
+        FROM {table}
+        WHERE project_id = ?1 AND lat BETWEEN ?2 AND ?3 AND {}
+        ORDER BY name COLLATE UNICODE_NOCASE",
+        bounds.lng_clause()
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let iter = stmt.query_map(
+        params![
+            project_id,
+            bounds.min_lat,
+            bounds.max_lat,
+            bounds.min_lng,
+            bounds.max_lng
+        ],
+        parse_place_entry,
+    )?;
+    parse_segment_rows(iter, segment_lists(segment))
+}
+
+/// Pairs the source rows behind each overlap place, loosest matches first. Assignments
+/// without a recorded source row fall back to the place's stored coordinates.
+pub fn overlap_pairings(conn: &Connection, project_id: i64) -> AppResult<Vec<OverlapPairing>> {
+    let coordinate = |alias: &str, field: &str, fallback: &str| {
+        format!(
+            "COALESCE(
+                CASE WHEN json_valid({alias}.raw_json) THEN COALESCE(
+                    json_extract({alias}.raw_json, '$.normalized.{field}'),
+                    json_extract({alias}.raw_json, '$.{field}')
+                ) END,
+                o.{fallback}
+            )"
+        )
+    };
+    let sql = format!(
+        "SELECT o.place_id, o.name, {}, {}, {}, {}
+        FROM comparison_overlap o
+        JOIN lists la ON la.project_id = o.project_id AND la.slot = 'A'
+        JOIN list_places lpa ON lpa.list_id = la.id AND lpa.place_id = o.place_id
+        LEFT JOIN raw_items ra ON ra.list_id = la.id AND ra.source_row_hash = lpa.source_row_hash
+        JOIN lists lb ON lb.project_id = o.project_id AND lb.slot = 'B'
+        JOIN list_places lpb ON lpb.list_id = lb.id AND lpb.place_id = o.place_id
+        LEFT JOIN raw_items rb ON rb.list_id = lb.id AND rb.source_row_hash = lpb.source_row_hash
+        WHERE o.project_id = ?1",
+        coordinate("ra", "latitude", "lat"),
+        coordinate("ra", "longitude", "lng"),
+        coordinate("rb", "latitude", "lat"),
+        coordinate("rb", "longitude", "lng"),
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut pairings = stmt
+        .query_map([project_id], |row| {
+            let (a_lat, a_lng, b_lat, b_lng) = (row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?);
+            Ok(OverlapPairing {
+                place_id: row.get(0)?,
+                name: row.get(1)?,
+                a_lat,
+                a_lng,
+                b_lat,
+                b_lng,
+                distance_m: distance_m(a_lat, a_lng, b_lat, b_lng),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    pairings.sort_by(|left, right| {
+        right
+            .distance_m
+            .total_cmp(&left.distance_m)
+            .then_with(|| compare_names(&left.name, &right.name))
+    });
+    Ok(pairings)
+}
+
+/// Returns the extent of a segment, or of every list in the project when `segment` is
+/// `None`. Empty selections yield `None`.
+pub fn segment_extent(
+    conn: &Connection,
+    project_id: i64,
+    segment: Option<ComparisonSegment>,
+) -> AppResult<Option<SegmentExtent>> {
+    let source = match segment {
+        Some(segment) => format!(
+            "SELECT lat, lng FROM {} WHERE project_id = ?1",
+            segment_table(segment)
+        ),
+        None => "SELECT p.lat, p.lng
+            FROM lists l
+            JOIN list_places lp ON lp.list_id = l.id
+            JOIN places p ON p.place_id = lp.place_id
+            WHERE l.project_id = ?1"
+            .to_string(),
+    };
+    let sql = format!("SELECT MIN(lat), MIN(lng), MAX(lat), MAX(lng) FROM ({source})");
+    let extent = conn.query_row(&sql, [project_id], |row| {
+        Ok((
+            row.get::<_, Option<f64>>(0)?,
+            row.get::<_, Option<f64>>(1)?,
+            row.get::<_, Option<f64>>(2)?,
+            row.get::<_, Option<f64>>(3)?,
+        ))
+    })?;
+    let (Some(min_lat), Some(min_lng), Some(max_lat), Some(max_lng)) = extent else {
+        return Ok(None);
+    };
+    Ok(Some(SegmentExtent {
+        bounds: BoundingBox {
+            min_lat,
+            min_lng,
+            max_lat,
+            max_lng,
+        },
+        center_lat: (min_lat + max_lat) / 2.0,
+        center_lng: (min_lng + max_lng) / 2.0,
+    }))
+}
+
+/// Builds a GeoJSON `FeatureCollection` of point features tagged with their segment. With no
+/// `segment`, every segment of the project is included.
+pub fn segment_feature_collection(
+    conn: &Connection,
+    project_id: i64,
+    segment: Option<ComparisonSegment>,
+    bounds: Option<BoundingBox>,
+) -> AppResult<Value> {
+    let segments = segment.map_or_else(|| ComparisonSegment::ALL.to_vec(), |value| vec![value]);
+    let mut features = Vec::new();
+    for segment in segments {
+        let rows = match bounds {
+            Some(bounds) => load_segment_in_bounds(conn, project_id, segment, bounds)?,
+            None => load_segment(conn, project_id, segment, None, &SegmentFilter::default())?.rows,
+        };
+        features.extend(rows.iter().map(|row| place_feature(row, segment)));
+    }
+    Ok(feature_collection(features))
+}
+
+pub fn place_feature(row: &PlaceComparisonRow, segment: ComparisonSegment) -> Value {
+    json!({
+        "type": "Feature",
+        "id": row.place_id,
+        "geometry": {
+            "type": "Point",
+            "coordinates": [row.lng, row.lat],
+        },
+        "properties": {
+            "place_id": row.place_id,
+            "name": row.name,
+            "formatted_address": row.formatted_address,
+            "types": row.types,
+            "lists": row.lists.iter().map(|slot| slot.as_tag()).collect::<Vec<_>>(),
+            "last_checked_at": row.last_checked_at,
+            "resolution_source": row.resolution_source,
+            "rating": row.enrichment.rating,
+            "user_rating_count": row.enrichment.user_rating_count,
+            "phone_number": row.enrichment.phone_number,
+            "website_uri": row.enrichment.website_uri,
+            "opening_hours": row.enrichment.opening_hours,
+            "segment": segment.as_str(),
+        },
+    })
+}
+
+pub fn feature_collection(features: Vec<Value>) -> Value {
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+/// Counts places per `types` entry within a segment, honouring the filter's text query.
+pub fn segment_type_facets(
+    conn: &Connection,
+    project_id: i64,
+    segment: ComparisonSegment,
+    query: Option<&str>,
+) -> AppResult<Vec<TypeFacet>> {
+    let table = segment_table(segment);
+    let sql = format!(
+        "SELECT facet.value, COUNT(DISTINCT place_id) AS total
+        FROM {table}, json_each(CASE WHEN json_valid(types) THEN types END) AS facet
+        {SEGMENT_FILTER}
+        GROUP BY facet.value
+        ORDER BY total DESC, facet.value"
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let facets = stmt
+        .query_map(
+            params![project_id, like_pattern(query), Option::<String>::None],
+            |row| {
+                Ok(TypeFacet {
+                    place_type: row.get(0)?,
+                    count: row.get::<_, i64>(1)? as usize,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(facets)
 }
 
 fn project_info(conn: &Connection, project_id: i64) -> AppResult<ComparisonProjectInfo> {
@@ -245,6 +1253,59 @@ fn pending_count(conn: &Connection, list_id: Option<i64>) -> AppResult<usize> {
     .map_err(AppError::from)
 }
 
+/// Lists the rows counted by `pending_a`/`pending_b` (and their multi-list equivalents) for
+/// every list in the project, in import order.
+pub fn list_pending_rows(conn: &Connection, project_id: i64) -> AppResult<Vec<PendingRows>> {
+    project_info(conn, project_id)?;
+    let lists = {
+        let mut stmt =
+            conn.prepare("SELECT id, slot FROM lists WHERE project_id = ?1 ORDER BY slot")?;
+        let lists = stmt
+            .query_map([project_id], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        lists
+    };
+    let field = |name: &str| {
+        format!(
+            "CASE WHEN json_valid(ri.raw_json) THEN COALESCE(
+                json_extract(ri.raw_json, '$.normalized.{name}'),
+                json_extract(ri.raw_json, '$.{name}')
+            ) END"
+        )
+    };
+    let sql = format!(
+        "SELECT ri.source_row_hash, {}, {}, {}
+        FROM raw_items ri
+        LEFT JOIN normalization_cache nc ON nc.source_row_hash = ri.source_row_hash
+        WHERE ri.list_id = ?1 AND nc.place_id IS NULL
+        ORDER BY ri.id",
+        field("title"),
+        field("latitude"),
+        field("longitude"),
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut pending = Vec::with_capacity(lists.len());
+    for (list_id, tag) in lists {
+        let rows = stmt
+            .query_map([list_id], |row| {
+                Ok(PendingRow {
+                    source_row_hash: row.get(0)?,
+                    title: row.get(1)?,
+                    lat: row.get(2)?,
+                    lng: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        pending.push(PendingRows {
+            slot: ListSlot::parse(&tag)?,
+            rows,
+        });
+    }
+    Ok(pending)
+}
+
 fn decode_types(value: Option<String>) -> Vec<String> {
     value
         .and_then(|text| serde_json::from_str::<Vec<String>>(&text).ok())
@@ -268,10 +1329,16 @@ fn count_segment(
     conn: &Connection,
     project_id: i64,
     segment: ComparisonSegment,
+    filter: &SegmentFilter,
 ) -> AppResult<usize> {
     let table = segment_table(segment);
-    let sql = format!("SELECT COUNT(*) FROM {table} WHERE project_id = ?1");
-    conn.query_row(&sql, [project_id], |row| row.get::<_, i64>(0))
+    let sql = format!("SELECT COUNT(*) FROM {table} {SEGMENT_FILTER}");
+    let args = params![
+        project_id,
+        like_pattern(filter.query.as_deref()),
+        filter.type_filter()
+    ];
+    conn.query_row(&sql, args, |row| row.get::<_, i64>(0))
         .map(|value| value as usize)
         .map_err(AppError::from)
 }
@@ -281,30 +1348,40 @@ fn load_segment(
     project_id: i64,
     segment: ComparisonSegment,
     pagination: Option<ComparisonPagination>,
+    filter: &SegmentFilter,
 ) -> AppResult<ComparisonSegmentPage> {
-    let total = count_segment(conn, project_id, segment)?;
+    let total = count_segment(conn, project_id, segment, filter)?;
+    let pattern = like_pattern(filter.query.as_deref());
+    let type_filter = filter.type_filter();
     let lists = segment_lists(segment);
     let effective_pagination = pagination.map(|p| p.with_total(total));
     let table = segment_table(segment);
     let base_sql = format!(
-        "SELECT place_id, name, formatted_address, lat, lng, types
+        "SELECT place_id, name, formatted_address, lat, lng, types, last_checked_at,
+            resolution_source, {PLACE_ENRICHMENT_COLUMNS}
         FROM {table}
-        WHERE project_id = ?1
-        ORDER BY name COLLATE NOCASE"
+        {SEGMENT_FILTER}
+        ORDER BY name COLLATE UNICODE_NOCASE, place_id"
     );
 
     let mapper = |row: &Row<'_>| parse_place_entry(row);
     let rows = if let Some(paging) = effective_pagination {
-        let limited = format!("{base_sql} LIMIT ?2 OFFSET ?3");
+        let limited = format!("{base_sql} LIMIT ?4 OFFSET ?5");
         let mut stmt = conn.prepare(&limited)?;
         let iter = stmt.query_map(
-            (project_id, paging.page_size as i64, paging.offset()),
+            params![
+                project_id,
+                pattern,
+                type_filter,
+                paging.page_size as i64,
+                paging.offset()
+            ],
             mapper,
         )?;
         parse_segment_rows(iter, lists)
     } else {
         let mut stmt = conn.prepare(&base_sql)?;
-        let iter = stmt.query_map([project_id], mapper)?;
+        let iter = stmt.query_map(params![project_id, pattern, type_filter], mapper)?;
         parse_segment_rows(iter, lists)
     }?;
 
@@ -312,12 +1389,17 @@ fn load_segment(
         .map(|p| (p.page, p.page_size))
         .unwrap_or_else(|| (1, cmp::max(total, 1)));
 
-    Ok(ComparisonSegmentPage {
-        rows,
-        total,
-        page,
-        page_size,
-    })
+    Ok(ComparisonSegmentPage::new(rows, total, page, page_size).with_next_cursor())
+}
+
+/// Case-insensitive substring match on name or address; LIKE only folds ASCII case.
+fn like_pattern(query: Option<&str>) -> Option<String> {
+    let term = query.map(str::trim).filter(|term| !term.is_empty())?;
+    let escaped = term
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    Some(format!("%{escaped}%"))
 }
 
 fn parse_segment_rows(
@@ -355,6 +1437,9 @@ fn parse_place_entry(row: &Row<'_>) -> rusqlite::Result<PlaceEntry> {
         lat: row.get(3)?,
         lng: row.get(4)?,
         types: decode_types(row.get(5)?),
+        last_checked_at: row.get(6)?,
+        resolution_source: row.get(7)?,
+        enrichment: PlaceEnrichment::from_row(row)?,
     })
 }
 
@@ -372,7 +1457,7 @@ impl ComparisonSnapshot {
 mod tests {
     use std::sync::Arc;
 
-    use tempfile::tempdir;
+    use tempfile::{tempdir, TempDir};
 
     use crate::db::bootstrap;
     use crate::secrets::SecretVault;
@@ -461,4 +1546,802 @@ mod tests {
         assert_eq!(snapshot.only_a.rows[0].place_id, "place_1");
         assert_eq!(snapshot.only_b.rows[0].place_id, "place_3");
     }
+
+    /// Bootstraps a fresh database and returns it with the default active project's id.
+    fn active_project_conn(name: &str) -> (TempDir, Connection, i64) {
+        let dir = tempdir().unwrap();
+        let conn = bootstrap(dir.path(), name, &SecretVault::in_memory())
+            .unwrap()
+            .context
+            .connection;
+        let project_id = conn
+            .query_row(
+                "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        (dir, conn, project_id)
+    }
+
+    fn insert_list(conn: &Connection, project_id: i64, slot: ListSlot) -> i64 {
+        conn.execute(
+            "INSERT INTO lists (project_id, slot, name, source) VALUES (?1, ?2, ?3, 'test')",
+            params![project_id, slot.as_tag(), slot.display_name()],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    /// Assigns places to a list, creating any that are not stored yet.
+    fn seed_places(conn: &Connection, list_id: i64, places: &[(&str, &str, f64, f64)]) {
+        for (place_id, name, lat, lng) in places {
+            conn.execute(
+                "INSERT OR IGNORE INTO places (place_id, name, lat, lng) VALUES (?1, ?2, ?3, ?4)",
+                params![place_id, name, lat, lng],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO list_places (list_id, place_id) VALUES (?1, ?2)",
+                params![list_id, place_id],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn swapping_slots_flips_only_segments() {
+        let (_dir, mut conn, project_id) = active_project_conn("swap.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        let list_b = insert_list(&conn, project_id, ListSlot::B);
+        seed_places(&conn, list_a, &[("only_a", "Alpha", 1.0, 1.0)]);
+        seed_places(&conn, list_b, &[("only_b", "Bravo", 2.0, 2.0)]);
+
+        crate::ingestion::swap_list_slots(&mut conn, project_id).unwrap();
+
+        let snapshot = compute_snapshot(&conn, project_id, None).unwrap();
+        assert_eq!(snapshot.lists.list_a_id, Some(list_b));
+        assert_eq!(snapshot.lists.list_b_id, Some(list_a));
+        assert_eq!(snapshot.only_a.rows[0].place_id, "only_b");
+        assert_eq!(snapshot.only_b.rows[0].place_id, "only_a");
+        let name: String = conn
+            .query_row("SELECT name FROM lists WHERE id = ?1", [list_b], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(name, "List A");
+    }
+
+    #[test]
+    fn snapshot_pages_each_segment() {
+        let (_dir, conn, project_id) = active_project_conn("paged.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        let list_b = insert_list(&conn, project_id, ListSlot::B);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("place_0", "place_0", 1.0, 1.0),
+                ("place_1", "place_1", 1.0, 1.0),
+                ("place_2", "place_2", 1.0, 1.0),
+                ("place_3", "place_3", 1.0, 1.0),
+                ("place_4", "place_4", 1.0, 1.0),
+            ],
+        );
+        seed_places(
+            &conn,
+            list_b,
+            &[
+                ("place_3", "place_3", 1.0, 1.0),
+                ("place_4", "place_4", 1.0, 1.0),
+                ("place_5", "place_5", 1.0, 1.0),
+            ],
+        );
+
+        let pagination = ComparisonPagination::new(Some(1), Some(2));
+        let snapshot = compute_snapshot(&conn, project_id, Some(pagination)).unwrap();
+        assert_eq!(snapshot.stats.only_a_count, 3);
+        assert_eq!(snapshot.only_a.rows.len(), 2);
+        assert_eq!(snapshot.only_a.page_size, 2);
+        assert_eq!(snapshot.overlap.rows.len(), 2);
+        assert_eq!(snapshot.only_b.rows.len(), 1);
+
+        let unpaged = compute_snapshot(&conn, project_id, None).unwrap();
+        assert_eq!(unpaged.only_a.rows.len(), 3);
+        assert_eq!(unpaged.only_a.total_pages, 1);
+        assert!(!unpaged.only_a.has_next);
+
+        let first = &snapshot.only_a;
+        assert_eq!(first.total_pages, 2);
+        assert!(first.has_next);
+        assert!(!first.has_prev);
+
+        let second = load_segment_page(
+            &conn,
+            project_id,
+            ComparisonSegment::OnlyA,
+            ComparisonPagination::new(Some(2), Some(2)),
+            &SegmentFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(second.rows.len(), 1);
+        assert_eq!(second.total_pages, 2);
+        assert!(!second.has_next);
+        assert!(second.has_prev);
+    }
+
+    #[test]
+    fn diff_reports_places_removed_since_last_run() {
+        let (_dir, conn, project_id) = active_project_conn("diff.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        seed_places(
+            &conn,
+            list_a,
+            &[("keep", "Keep", 1.0, 1.0), ("drop", "Drop", 2.0, 2.0)],
+        );
+
+        let before_run = compute_diff(&conn, project_id, ListSlot::A).unwrap();
+        assert!(before_run.baseline_run_id.is_none());
+        assert_eq!(before_run.added, vec!["drop", "keep"]);
+
+        let snapshot = compute_snapshot(&conn, project_id, None).unwrap();
+        crate::projects::record_comparison_run(
+            &conn,
+            project_id,
+            snapshot.lists.list_a_id,
+            snapshot.lists.list_b_id,
+            &snapshot.stats,
+            crate::db::now_timestamp(),
+            1,
+        )
+        .unwrap();
+        conn.execute(
+            "DELETE FROM list_places WHERE list_id = ?1 AND place_id = 'drop'",
+            [list_a],
+        )
+        .unwrap();
+
+        let diff = compute_diff(&conn, project_id, ListSlot::A).unwrap();
+        assert!(diff.baseline_run_id.is_some());
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed, vec!["drop"]);
+    }
+
+    #[test]
+    fn fuzzy_mode_merges_near_duplicate_places() {
+        let (_dir, conn, project_id) = active_project_conn("fuzzy.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        let list_b = insert_list(&conn, project_id, ListSlot::B);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("kml:a1", "Blue Bottle Coffee", 37.77620, -122.42330),
+                ("kml:a2", "Tartine Bakery", 37.76140, -122.42410),
+            ],
+        );
+        seed_places(
+            &conn,
+            list_b,
+            &[
+                ("kml:b1", "Blue Bottle Coffee Co.", 37.77625, -122.42338),
+                ("kml:b2", "Tartine Bakery", 37.80000, -122.40000),
+            ],
+        );
+
+        let strict = compute_snapshot(&conn, project_id, None).unwrap();
+        assert_eq!(strict.stats.overlap_count, 0);
+        assert_eq!(strict.stats.only_a_count, 2);
+
+        let fuzzy =
+            compute_snapshot_with(&conn, project_id, None, Some(FuzzyMatchOptions::default()))
+                .unwrap();
+        assert_eq!(fuzzy.stats.overlap_count, 1);
+        assert_eq!(fuzzy.overlap.rows[0].place_id, "kml:a1");
+        assert_eq!(fuzzy.overlap.rows[0].fuzzy_match.as_deref(), Some("kml:b1"));
+        // Same name, but kilometres apart.
+        assert_eq!(fuzzy.only_a.rows[0].place_id, "kml:a2");
+        assert_eq!(fuzzy.only_b.rows[0].place_id, "kml:b2");
+    }
+
+    #[test]
+    fn find_duplicates_clusters_near_identical_places() {
+        let (_dir, conn, project_id) = active_project_conn("duplicates.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("kml:d1", "Golden Gate Park", 37.76940, -122.48620),
+                ("kml:d2", "Golden Gate Park ", 37.76945, -122.48630),
+                ("kml:d3", "Dolores Park", 37.75960, -122.42690),
+            ],
+        );
+
+        let clusters =
+            find_duplicates(&conn, project_id, ListSlot::A, FuzzyMatchOptions::default()).unwrap();
+        assert_eq!(clusters.len(), 1);
+        let ids: Vec<_> = clusters[0]
+            .places
+            .iter()
+            .map(|row| row.place_id.as_str())
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&"kml:d1") && ids.contains(&"kml:d2"));
+        assert!(
+            find_duplicates(&conn, project_id, ListSlot::B, FuzzyMatchOptions::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn segment_query_filters_before_pagination() {
+        let (_dir, conn, project_id) = active_project_conn("query.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("q1", "Cafe Luna", 1.0, 1.0),
+                ("q2", "Bakery", 2.0, 2.0),
+                ("q3", "Library", 3.0, 3.0),
+                ("q4", "Museum", 4.0, 4.0),
+                ("q5", "100% Juice", 5.0, 5.0),
+            ],
+        );
+        conn.execute_batch(
+            "UPDATE places SET formatted_address = '1 Main St' WHERE place_id = 'q1';
+            UPDATE places SET formatted_address = '2 Luna Ave' WHERE place_id = 'q2';
+            UPDATE places SET formatted_address = '3 Oak St' WHERE place_id = 'q3';
+            UPDATE places SET formatted_address = '5 Pine St' WHERE place_id = 'q5';",
+        )
+        .unwrap();
+
+        let page = load_segment_page(
+            &conn,
+            project_id,
+            ComparisonSegment::OnlyA,
+            ComparisonPagination::new(Some(1), Some(10)),
+            &SegmentFilter {
+                query: Some("LUNA".into()),
+                ..SegmentFilter::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(page.total, 2);
+        let names: Vec<_> = page.rows.iter().map(|row| row.name.as_str()).collect();
+        assert_eq!(names, vec!["Bakery", "Cafe Luna"]);
+
+        let literal = load_segment_page(
+            &conn,
+            project_id,
+            ComparisonSegment::OnlyA,
+            ComparisonPagination::default(),
+            &SegmentFilter {
+                query: Some("0%".into()),
+                ..SegmentFilter::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(literal.total, 1);
+    }
+
+    #[test]
+    fn cursor_pages_stay_stable_across_inserts() {
+        let (_dir, conn, project_id) = active_project_conn("cursor.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("c1", "Alpha", 1.0, 1.0),
+                ("c2", "Bravo", 2.0, 2.0),
+                ("c3", "bravo", 3.0, 3.0),
+                ("c4", "Delta", 4.0, 4.0),
+            ],
+        );
+
+        let first = load_segment_page(
+            &conn,
+            project_id,
+            ComparisonSegment::OnlyA,
+            ComparisonPagination::new(Some(1), Some(2)),
+            &SegmentFilter::default(),
+        )
+        .unwrap();
+        let cursor = first.next_cursor.clone().expect("more rows follow");
+        assert_eq!(cursor.place_id, "c2");
+
+        // A background import lands one row before the cursor and one after it.
+        seed_places(
+            &conn,
+            list_a,
+            &[("c5", "Aardvark", 5.0, 5.0), ("c6", "Charlie", 6.0, 6.0)],
+        );
+
+        let mut seen: Vec<String> = first.rows.iter().map(|row| row.place_id.clone()).collect();
+        let mut after = Some(cursor);
+        while let Some(cursor) = after.take() {
+            let page = load_segment_after(
+                &conn,
+                project_id,
+                ComparisonSegment::OnlyA,
+                &cursor,
+                2,
+                &SegmentFilter::default(),
+            )
+            .unwrap();
+            assert!(page.has_prev);
+            seen.extend(page.rows.iter().map(|row| row.place_id.clone()));
+            after = page.next_cursor;
+        }
+
+        assert_eq!(seen, vec!["c1", "c2", "c3", "c6", "c4"]);
+    }
+
+    #[test]
+    fn loose_only_sets_drop_coordinate_near_pairs() {
+        let (_dir, conn, project_id) = active_project_conn("loose.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        let list_b = insert_list(&conn, project_id, ListSlot::B);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("shared", "Shared", 0.0, 0.0),
+                ("a_cafe", "Corner Cafe", 10.0, 10.0),
+                ("a_far", "Far Away", 20.0, 20.0),
+            ],
+        );
+        seed_places(
+            &conn,
+            list_b,
+            &[
+                ("shared", "Shared", 0.0, 0.0),
+                ("b_cafe", "Corner Café", 10.0001, 10.0),
+            ],
+        );
+
+        let strict = compute_snapshot(&conn, project_id, None).unwrap();
+        let ids = |rows: &[PlaceComparisonRow]| -> Vec<String> {
+            rows.iter().map(|row| row.place_id.clone()).collect()
+        };
+        assert_eq!(ids(&strict.only_a.rows), vec!["a_cafe", "a_far"]);
+        assert_eq!(ids(&strict.only_b.rows), vec!["b_cafe"]);
+
+        let loose = loose_only_sets(&conn, project_id, Some(25.0)).unwrap();
+        assert_eq!(ids(&loose.loose_only_a), vec!["a_far"]);
+        assert!(loose.loose_only_b.is_empty());
+
+        let tight = loose_only_sets(&conn, project_id, Some(5.0)).unwrap();
+        assert_eq!(ids(&tight.loose_only_a), vec!["a_cafe", "a_far"]);
+        assert_eq!(ids(&tight.loose_only_b), vec!["b_cafe"]);
+
+        assert!(loose_only_sets(&conn, project_id, Some(-1.0)).is_err());
+    }
+
+    #[test]
+    fn segments_sort_accented_names_with_their_base_letters() {
+        let (_dir, conn, project_id) = active_project_conn("collation.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("n1", "Zürich", 1.0, 1.0),
+                ("n2", "zebra", 2.0, 2.0),
+                ("n3", "Église", 3.0, 3.0),
+                ("n4", "Zurich", 4.0, 4.0),
+                ("n5", "Ostrich", 5.0, 5.0),
+                ("n6", "Álvarez", 6.0, 6.0),
+            ],
+        );
+
+        let page = load_segment_page(
+            &conn,
+            project_id,
+            ComparisonSegment::OnlyA,
+            ComparisonPagination::default(),
+            &SegmentFilter::default(),
+        )
+        .unwrap();
+        let names: Vec<_> = page.rows.iter().map(|row| row.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Álvarez", "Église", "Ostrich", "zebra", "Zurich", "Zürich"]
+        );
+    }
+
+    #[test]
+    fn duplicate_clusters_sort_accented_names_with_their_base_letters() {
+        let (_dir, conn, project_id) = active_project_conn("duplicate-order.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("z1", "Zoo Cafe", 37.76940, -122.48620),
+                ("z2", "Zoo Cafe ", 37.76945, -122.48630),
+                ("a1", "Álamo Square", 37.77640, -122.43450),
+                ("a2", "Álamo Square ", 37.77645, -122.43460),
+            ],
+        );
+
+        let clusters =
+            find_duplicates(&conn, project_id, ListSlot::A, FuzzyMatchOptions::default()).unwrap();
+        let leads: Vec<_> = clusters
+            .iter()
+            .map(|cluster| cluster.places[0].name.trim())
+            .collect();
+        assert_eq!(leads, vec!["Álamo Square", "Zoo Cafe"]);
+    }
+
+    #[test]
+    fn segment_type_filter_and_facets() {
+        let (_dir, conn, project_id) = active_project_conn("types.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("t1", "Noodle Bar", 1.0, 1.0),
+                ("t2", "Taqueria", 2.0, 2.0),
+                ("t3", "Rose Garden", 3.0, 3.0),
+                ("t4", "Restaurant Supply", 4.0, 4.0),
+            ],
+        );
+        conn.execute_batch(
+            r#"UPDATE places SET types = '["restaurant"]' WHERE place_id IN ('t1', 't2');
+            UPDATE places SET types = '["park"]' WHERE place_id = 't3';
+            UPDATE places SET types = '["store"]' WHERE place_id = 't4';"#,
+        )
+        .unwrap();
+
+        let page = load_segment_page(
+            &conn,
+            project_id,
+            ComparisonSegment::OnlyA,
+            ComparisonPagination::default(),
+            &SegmentFilter {
+                type_filter: Some("restaurant".into()),
+                ..SegmentFilter::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(page.total, 2);
+        let names: Vec<_> = page.rows.iter().map(|row| row.name.as_str()).collect();
+        assert_eq!(names, vec!["Noodle Bar", "Taqueria"]);
+
+        let facets =
+            segment_type_facets(&conn, project_id, ComparisonSegment::OnlyA, None).unwrap();
+        assert_eq!(facets[0].place_type, "restaurant");
+        assert_eq!(facets[0].count, 2);
+        let total = count_segment(
+            &conn,
+            project_id,
+            ComparisonSegment::OnlyA,
+            &SegmentFilter::default(),
+        )
+        .unwrap();
+        assert_eq!(facets.iter().map(|facet| facet.count).sum::<usize>(), total);
+    }
+
+    #[test]
+    fn three_list_project_partitions_places_by_exact_membership() {
+        let (_dir, conn, project_id) = active_project_conn("three.db");
+        let everyone = ("everyone", "Everyone", 1.0, 1.0);
+        let pair = ("ab", "Pair", 2.0, 2.0);
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        let list_b = insert_list(&conn, project_id, ListSlot::B);
+        let list_c = insert_list(&conn, project_id, ListSlot::C);
+        seed_places(
+            &conn,
+            list_a,
+            &[everyone, pair, ("only-a", "Alpha", 3.0, 3.0)],
+        );
+        seed_places(
+            &conn,
+            list_b,
+            &[everyone, pair, ("only-b", "Bravo", 4.0, 4.0)],
+        );
+        seed_places(&conn, list_c, &[everyone, ("only-c", "Charlie", 5.0, 5.0)]);
+
+        let comparison = compute_slot_sets(&conn, project_id).unwrap();
+        assert_eq!(
+            comparison.slots,
+            vec![ListSlot::A, ListSlot::B, ListSlot::C]
+        );
+        assert_eq!(
+            comparison.sets[0].slots,
+            vec![ListSlot::A, ListSlot::B, ListSlot::C]
+        );
+        let ids = |slots: &[ListSlot]| -> Vec<String> {
+            comparison
+                .rows_in_exactly(slots)
+                .iter()
+                .map(|row| row.place_id.clone())
+                .collect()
+        };
+        assert_eq!(
+            ids(&[ListSlot::C, ListSlot::A, ListSlot::B]),
+            vec!["everyone"]
+        );
+        assert_eq!(ids(&[ListSlot::A]), vec!["only-a"]);
+        assert_eq!(ids(&[ListSlot::B]), vec!["only-b"]);
+        assert_eq!(ids(&[ListSlot::C]), vec!["only-c"]);
+        assert_eq!(ids(&[ListSlot::A, ListSlot::B]), vec!["ab"]);
+
+        // The pairwise A/B snapshot keeps its existing semantics.
+        let snapshot = compute_snapshot(&conn, project_id, None).unwrap();
+        assert_eq!(snapshot.stats.overlap_count, 2);
+        assert_eq!(snapshot.stats.only_a_count, 1);
+    }
+
+    #[test]
+    fn segment_in_bounds_filters_by_viewport() {
+        let (_dir, conn, project_id) = active_project_conn("bounds.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("inside", "Ferry Building", 37.7955, -122.3937),
+                ("outside", "Space Needle", 47.6205, -122.3493),
+                ("fiji", "Suva", -18.1416, 178.4419),
+                ("samoa", "Apia", -13.8333, -171.7667),
+            ],
+        );
+
+        let bay_area = BoundingBox {
+            min_lat: 37.6,
+            min_lng: -122.6,
+            max_lat: 37.9,
+            max_lng: -122.2,
+        };
+        let rows =
+            load_segment_in_bounds(&conn, project_id, ComparisonSegment::OnlyA, bay_area).unwrap();
+        let ids: Vec<_> = rows.iter().map(|row| row.place_id.as_str()).collect();
+        assert_eq!(ids, vec!["inside"]);
+
+        let pacific = BoundingBox {
+            min_lat: -25.0,
+            min_lng: 170.0,
+            max_lat: -10.0,
+            max_lng: -170.0,
+        };
+        let rows =
+            load_segment_in_bounds(&conn, project_id, ComparisonSegment::OnlyA, pacific).unwrap();
+        let ids: Vec<_> = rows.iter().map(|row| row.place_id.as_str()).collect();
+        assert_eq!(ids, vec!["samoa", "fiji"]);
+    }
+
+    #[test]
+    fn segment_feature_collection_tags_each_feature() {
+        let (_dir, conn, project_id) = active_project_conn("geojson.db");
+        let both = ("both", "Shared", 10.0, 20.0);
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        let list_b = insert_list(&conn, project_id, ListSlot::B);
+        seed_places(&conn, list_a, &[both, ("a-only", "Alpha", 11.0, 21.0)]);
+        seed_places(&conn, list_b, &[both, ("b-only", "Bravo", 50.0, 60.0)]);
+
+        let collection = segment_feature_collection(&conn, project_id, None, None).unwrap();
+        assert_eq!(collection["type"], "FeatureCollection");
+        let features = collection["features"].as_array().unwrap();
+        assert_eq!(features.len(), 3);
+        let tagged: Vec<_> = features
+            .iter()
+            .map(|feature| {
+                (
+                    feature["properties"]["place_id"].as_str().unwrap(),
+                    feature["properties"]["segment"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            tagged,
+            vec![
+                ("both", "overlap"),
+                ("a-only", "only_a"),
+                ("b-only", "only_b")
+            ]
+        );
+        assert_eq!(features[0]["geometry"]["coordinates"], json!([20.0, 10.0]));
+
+        let bounded = segment_feature_collection(
+            &conn,
+            project_id,
+            None,
+            Some(BoundingBox {
+                min_lat: 0.0,
+                min_lng: 0.0,
+                max_lat: 20.0,
+                max_lng: 30.0,
+            }),
+        )
+        .unwrap();
+        assert_eq!(bounded["features"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn segment_extent_encloses_places_and_centers_on_midpoint() {
+        let (_dir, conn, project_id) = active_project_conn("extent.db");
+        assert!(segment_extent(&conn, project_id, None).unwrap().is_none());
+
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("west", "West", 10.0, -40.0),
+                ("east", "East", 30.0, 20.0),
+                ("south", "South", -10.0, 0.0),
+            ],
+        );
+
+        let extent = segment_extent(&conn, project_id, Some(ComparisonSegment::OnlyA))
+            .unwrap()
+            .expect("extent");
+        assert_eq!(extent.bounds.min_lat, -10.0);
+        assert_eq!(extent.bounds.max_lat, 30.0);
+        assert_eq!(extent.bounds.min_lng, -40.0);
+        assert_eq!(extent.bounds.max_lng, 20.0);
+        assert_eq!((extent.center_lat, extent.center_lng), (10.0, -10.0));
+        assert!(
+            segment_extent(&conn, project_id, Some(ComparisonSegment::Overlap))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn overlap_pairings_report_distance_between_source_rows() {
+        let (_dir, conn, project_id) = active_project_conn("pairing.db");
+        let places = [
+            ("shared", "Shared Spot", 40.0000, -73.0000),
+            ("exact", "Exact Spot", 41.0, -74.0),
+        ];
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        let list_b = insert_list(&conn, project_id, ListSlot::B);
+        seed_places(&conn, list_a, &places);
+        seed_places(&conn, list_b, &places);
+        for (list_id, hash, raw_json) in [
+            (
+                list_a,
+                "row-a",
+                r#"{"normalized":{"latitude":40.0000,"longitude":-73.0000}}"#,
+            ),
+            (
+                list_b,
+                "row-b",
+                r#"{"latitude":40.0010,"longitude":-73.0000}"#,
+            ),
+        ] {
+            conn.execute(
+                "INSERT INTO raw_items (list_id, source_row_hash, raw_json) VALUES (?1, ?2, ?3)",
+                params![list_id, hash, raw_json],
+            )
+            .unwrap();
+            conn.execute(
+                "UPDATE list_places SET source_row_hash = ?2
+                 WHERE list_id = ?1 AND place_id = 'shared'",
+                params![list_id, hash],
+            )
+            .unwrap();
+        }
+
+        let pairings = overlap_pairings(&conn, project_id).unwrap();
+        assert_eq!(pairings.len(), 2);
+        let shared = &pairings[0];
+        assert_eq!(shared.place_id, "shared");
+        assert_eq!((shared.a_lat, shared.b_lat), (40.0, 40.001));
+        assert!(
+            (shared.distance_m - 111.2).abs() < 1.0,
+            "{}",
+            shared.distance_m
+        );
+        assert_eq!(pairings[1].place_id, "exact");
+        assert_eq!(pairings[1].distance_m, 0.0);
+    }
+
+    #[test]
+    fn segment_nearest_orders_by_great_circle_distance() {
+        let (_dir, conn, project_id) = active_project_conn("nearest.db");
+        let list_a = insert_list(&conn, project_id, ListSlot::A);
+        seed_places(
+            &conn,
+            list_a,
+            &[
+                ("needle", "Space Needle", 47.6205, -122.3493),
+                ("oakland", "Lake Merritt", 37.8044, -122.2712),
+                ("ferry", "Ferry Building", 37.7955, -122.3937),
+                ("fiji", "Suva", -18.1416, 178.4419),
+                ("coit", "Coit Tower", 37.8024, -122.4058),
+            ],
+        );
+
+        let nearest = segment_nearest(
+            &conn,
+            project_id,
+            ComparisonSegment::OnlyA,
+            37.7956,
+            -122.3935,
+            None,
+        )
+        .unwrap();
+        let ids: Vec<_> = nearest
+            .iter()
+            .map(|entry| entry.place.place_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["ferry", "coit", "oakland", "needle", "fiji"]);
+        assert!(nearest[0].distance_m < 50.0);
+        assert!(nearest
+            .windows(2)
+            .all(|pair| pair[0].distance_m <= pair[1].distance_m));
+        assert_eq!(nearest[0].place.lists, vec![ListSlot::A]);
+
+        let capped = segment_nearest(
+            &conn,
+            project_id,
+            ComparisonSegment::OnlyA,
+            37.7956,
+            -122.3935,
+            Some(2),
+        )
+        .unwrap();
+        assert_eq!(capped.len(), 2);
+
+        let within_5km: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM places WHERE haversine(lat, lng, 37.7956, -122.3935) < 5000",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(within_5km, 2);
+
+        assert!(matches!(
+            segment_nearest(&conn, project_id, ComparisonSegment::OnlyA, 91.0, 0.0, None),
+            Err(AppError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn pending_rows_lists_only_unresolved_imports() {
+        let (_dir, mut conn, project_id) = active_project_conn("pending.db");
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <kml xmlns="http://www.opengis.net/kml/2.2"><Document>
+          <Placemark><name>Matched</name><Point><coordinates>1.5,2.5</coordinates></Point></Placemark>
+          <Placemark><name>Orphan</name><Point><coordinates>-3.25,4.75</coordinates></Point></Placemark>
+        </Document></kml>"#;
+        let parsed = crate::ingestion::parse_kml(kml.as_bytes()).unwrap();
+        let (drive_file, _) = crate::ingestion::pasted_kml("List A", kml);
+        let summary = crate::ingestion::persist_rows(
+            &mut conn,
+            project_id,
+            ListSlot::A,
+            &drive_file,
+            &parsed.rows,
+        )
+        .unwrap();
+        seed_places(&conn, summary.list_id, &[("matched", "Matched", 2.5, 1.5)]);
+        conn.execute(
+            "INSERT INTO normalization_cache (source_row_hash, place_id) VALUES (?1, 'matched')",
+            [&parsed.rows[0].source_row_hash],
+        )
+        .unwrap();
+
+        let pending = list_pending_rows(&conn, project_id).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].slot, ListSlot::A);
+        assert_eq!(pending[0].rows.len(), 1);
+        let orphan = &pending[0].rows[0];
+        assert_eq!(orphan.source_row_hash, parsed.rows[1].source_row_hash);
+        assert_eq!(orphan.title.as_deref(), Some("Orphan"));
+        assert_eq!(orphan.lat, Some(4.75));
+        assert_eq!(orphan.lng, Some(-3.25));
+
+        let stats = compute_snapshot(&conn, project_id, None).unwrap().stats;
+        assert_eq!(stats.pending_a, 1);
+    }
 }
@@ -1,17 +1,47 @@
 use std::{env, io};
 
-use secrecy::SecretString;
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+use secrecy::{ExposeSecret, SecretString};
 use serde::Serialize;
 use tracing::debug;
 
+use crate::db::CipherSettings;
+use crate::errors::{AppError, AppResult};
+
 const DEFAULT_TELEMETRY_BUFFER_MAX_BYTES: u64 = 5 * 1024 * 1024;
 const DEFAULT_TELEMETRY_BUFFER_MAX_FILES: usize = 5;
+const DEFAULT_TELEMETRY_BUFFER_MAX_AGE_DAYS: u64 = 30;
 const DEFAULT_DEVICE_CODE_ENDPOINT: &str = "https://oauth2.googleapis.com/device/code";
 const DEFAULT_AUTH_ENDPOINT: &str = "https://accounts.google.com/o/oauth2/v2/auth";
 const DEFAULT_TOKEN_ENDPOINT: &str = "https://oauth2.googleapis.com/token";
 const DEFAULT_USERINFO_ENDPOINT: &str = "https://openidconnect.googleapis.com/v1/userinfo";
+const DEFAULT_REVOKE_ENDPOINT: &str = "https://oauth2.googleapis.com/revoke";
 const DEFAULT_DRIVE_API_BASE: &str = "https://www.googleapis.com/drive/v3";
 const DEFAULT_DRIVE_PICKER_PAGE_SIZE: usize = 25;
+pub(crate) const DEFAULT_MAX_IMPORT_BYTES: u64 = 50 * 1024 * 1024;
+const DEFAULT_MAX_IMPORT_ROWS: usize = 50_000;
+const DEFAULT_REFRESH_PROGRESS_EVERY_ROWS: usize = 50;
+const DEFAULT_REFRESH_PROGRESS_INTERVAL_MS: u64 = 250;
+pub(crate) const DEFAULT_PLACES_RETRY_MAX_ATTEMPTS: u32 = 5;
+pub(crate) const DEFAULT_PLACES_RETRY_BASE_BACKOFF_MS: u64 = 250;
+pub(crate) const DEFAULT_PLACES_RETRY_MAX_BACKOFF_MS: u64 = 16_000;
+/// KML `<Data>` names and GeoJSON property keys read as a Place ID unless
+/// `PLACE_ID_FIELDS` overrides them.
+pub const DEFAULT_PLACE_ID_FIELDS: &[&str] = &[
+    "PlaceID",
+    "placeId",
+    "place_id",
+    "gx_id",
+    "google_maps_place_id",
+];
+const DEFAULT_TELEMETRY_REDACTED_KEYS: &[&str] = &["email", "file_name"];
+const DEFAULT_GOOGLE_SCOPES: &[&str] = &[
+    "https://www.googleapis.com/auth/drive.readonly",
+    "https://www.googleapis.com/auth/drive.metadata.readonly",
+    "openid",
+    "email",
+    "profile",
+];
 
 #[derive(Clone, Debug)]
 pub struct AppConfig {
@@ -21,7 +51,15 @@ pub struct AppConfig {
     pub telemetry_batch_size: usize,
     pub telemetry_buffer_max_bytes: u64,
     pub telemetry_buffer_max_files: usize,
+    pub telemetry_buffer_max_age_days: u64,
     pub places_rate_limit_qps: u32,
+    /// Substitute synthetic places when a Places HTTP lookup fails instead of surfacing the error.
+    pub allow_synthetic_fallback: bool,
+    /// Total tries per Places lookup, including the first.
+    pub places_retry_max_attempts: u32,
+    pub places_retry_base_backoff_ms: u64,
+    /// Upper bound on the exponential part of a retry delay; jitter is added on top.
+    pub places_retry_max_backoff_ms: u64,
     pub normalization_cache_ttl_hours: u64,
     pub database_file_name: String,
     pub google_places_api_key: Option<SecretString>,
@@ -32,8 +70,64 @@ pub struct AppConfig {
     pub google_auth_endpoint: String,
     pub google_token_endpoint: String,
     pub google_userinfo_endpoint: String,
+    pub google_revoke_endpoint: String,
     pub google_drive_api_base: String,
     pub google_drive_picker_page_size: usize,
+    pub google_oauth_scopes: Vec<String>,
+    pub max_import_bytes: u64,
+    pub max_import_rows: usize,
+    /// Keep the first `max_import_rows` rows of a larger import instead of rejecting it.
+    pub truncate_oversized_imports: bool,
+    pub refresh_progress_every_rows: usize,
+    pub refresh_progress_interval_ms: u64,
+    pub place_id_fields: Vec<String>,
+    pub telemetry_redacted_keys: Vec<String>,
+    /// Proxy every Google and Places request goes through; `None` connects directly.
+    pub http_proxy: Option<ProxySettings>,
+    /// SQLCipher parameters for a newly created database; existing files keep their own.
+    pub cipher: CipherSettings,
+}
+
+/// Outbound proxy shared by the Google and Places HTTP clients. Hosts listed in `NO_PROXY`
+/// still bypass it.
+#[derive(Clone, Debug)]
+pub struct ProxySettings {
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<SecretString>,
+}
+
+impl ProxySettings {
+    fn from_env() -> Option<Self> {
+        let url = env::var("HTTP_PROXY")
+            .ok()
+            .filter(|v| !v.trim().is_empty())?;
+        Some(Self {
+            url: url.trim().to_string(),
+            username: env::var("HTTP_PROXY_USERNAME")
+                .ok()
+                .filter(|v| !v.trim().is_empty()),
+            password: env::var("HTTP_PROXY_PASSWORD")
+                .ok()
+                .filter(|v| !v.is_empty())
+                .map(|value| SecretString::new(value.into())),
+        })
+    }
+
+    pub fn apply(&self, builder: ClientBuilder) -> AppResult<ClientBuilder> {
+        let mut proxy = Proxy::all(self.url.as_str())
+            .map_err(|err| AppError::Config(format!("invalid HTTP_PROXY: {err}")))?
+            .no_proxy(NoProxy::from_env());
+        if let Some(username) = &self.username {
+            let password = self
+                .password
+                .as_ref()
+                .map(|value| value.expose_secret().to_string())
+                .unwrap_or_default();
+            proxy = proxy.basic_auth(username, &password);
+        }
+        Ok(builder.proxy(proxy))
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -44,6 +138,7 @@ pub struct PublicAppConfig {
     pub telemetry_batch_size: usize,
     pub telemetry_buffer_max_bytes: u64,
     pub telemetry_buffer_max_files: usize,
+    pub telemetry_buffer_max_age_days: u64,
     pub places_rate_limit_qps: u32,
     pub normalization_cache_ttl_hours: u64,
     pub database_file_name: String,
@@ -51,6 +146,7 @@ pub struct PublicAppConfig {
     pub has_maptiler_key: bool,
     pub drive_import_enabled: bool,
     pub drive_picker_page_size: usize,
+    pub max_import_bytes: u64,
 }
 
 impl AppConfig {
@@ -70,7 +166,25 @@ impl AppConfig {
                 DEFAULT_TELEMETRY_BUFFER_MAX_FILES,
             )
             .max(1),
+            telemetry_buffer_max_age_days: parse_u64(
+                "TELEMETRY_BUFFER_MAX_AGE_DAYS",
+                DEFAULT_TELEMETRY_BUFFER_MAX_AGE_DAYS,
+            ),
             places_rate_limit_qps: parse_u32("PLACES_RATE_LIMIT_QPS", 3),
+            allow_synthetic_fallback: parse_bool("PLACES_ALLOW_SYNTHETIC_FALLBACK", false),
+            places_retry_max_attempts: parse_u32(
+                "PLACES_RETRY_MAX_ATTEMPTS",
+                DEFAULT_PLACES_RETRY_MAX_ATTEMPTS,
+            )
+            .max(1),
+            places_retry_base_backoff_ms: parse_u64(
+                "PLACES_RETRY_BASE_BACKOFF_MS",
+                DEFAULT_PLACES_RETRY_BASE_BACKOFF_MS,
+            ),
+            places_retry_max_backoff_ms: parse_u64(
+                "PLACES_RETRY_MAX_BACKOFF_MS",
+                DEFAULT_PLACES_RETRY_MAX_BACKOFF_MS,
+            ),
             normalization_cache_ttl_hours: parse_u64("NORMALIZATION_CACHE_TTL_HOURS", 72),
             database_file_name: env::var("DATABASE_FILE_NAME")
                 .unwrap_or_else(|_| "maps-list-comparator.db".to_string()),
@@ -96,12 +210,46 @@ impl AppConfig {
                 .unwrap_or_else(|_| DEFAULT_TOKEN_ENDPOINT.to_string()),
             google_userinfo_endpoint: env::var("GOOGLE_USERINFO_ENDPOINT")
                 .unwrap_or_else(|_| DEFAULT_USERINFO_ENDPOINT.to_string()),
+            google_revoke_endpoint: env::var("GOOGLE_REVOKE_ENDPOINT")
+                .unwrap_or_else(|_| DEFAULT_REVOKE_ENDPOINT.to_string()),
             google_drive_api_base: env::var("GOOGLE_DRIVE_API_BASE")
                 .unwrap_or_else(|_| DEFAULT_DRIVE_API_BASE.to_string()),
             google_drive_picker_page_size: parse_usize(
                 "GOOGLE_DRIVE_PICKER_PAGE_SIZE",
                 DEFAULT_DRIVE_PICKER_PAGE_SIZE,
             ),
+            google_oauth_scopes: parse_list("GOOGLE_OAUTH_SCOPES", DEFAULT_GOOGLE_SCOPES),
+            max_import_bytes: parse_u64("MAX_IMPORT_BYTES", DEFAULT_MAX_IMPORT_BYTES).max(1),
+            max_import_rows: parse_usize("MAX_IMPORT_ROWS", DEFAULT_MAX_IMPORT_ROWS).max(1),
+            truncate_oversized_imports: parse_bool("TRUNCATE_OVERSIZED_IMPORTS", false),
+            refresh_progress_every_rows: parse_usize(
+                "REFRESH_PROGRESS_EVERY_ROWS",
+                DEFAULT_REFRESH_PROGRESS_EVERY_ROWS,
+            )
+            .max(1),
+            refresh_progress_interval_ms: parse_u64(
+                "REFRESH_PROGRESS_INTERVAL_MS",
+                DEFAULT_REFRESH_PROGRESS_INTERVAL_MS,
+            ),
+            place_id_fields: parse_list("PLACE_ID_FIELDS", DEFAULT_PLACE_ID_FIELDS),
+            telemetry_redacted_keys: parse_list(
+                "TELEMETRY_REDACTED_KEYS",
+                DEFAULT_TELEMETRY_REDACTED_KEYS,
+            ),
+            http_proxy: ProxySettings::from_env(),
+            cipher: CipherSettings {
+                kdf_iter: parse_u32("SQLCIPHER_KDF_ITER", CipherSettings::default().kdf_iter),
+                page_size: parse_u32("SQLCIPHER_PAGE_SIZE", CipherSettings::default().page_size),
+            },
+        }
+    }
+
+    /// Starts an HTTP client builder with the configured proxy applied.
+    pub fn http_client_builder(&self) -> AppResult<ClientBuilder> {
+        let builder = reqwest::Client::builder();
+        match &self.http_proxy {
+            Some(proxy) => proxy.apply(builder),
+            None => Ok(builder),
         }
     }
 
@@ -113,6 +261,7 @@ impl AppConfig {
             telemetry_batch_size: self.telemetry_batch_size,
             telemetry_buffer_max_bytes: self.telemetry_buffer_max_bytes,
             telemetry_buffer_max_files: self.telemetry_buffer_max_files,
+            telemetry_buffer_max_age_days: self.telemetry_buffer_max_age_days,
             places_rate_limit_qps: self.places_rate_limit_qps,
             normalization_cache_ttl_hours: self.normalization_cache_ttl_hours,
             database_file_name: self.database_file_name.clone(),
@@ -121,6 +270,7 @@ impl AppConfig {
             drive_import_enabled: self.google_oauth_client_id.is_some()
                 && self.google_oauth_client_secret.is_some(),
             drive_picker_page_size: self.google_drive_picker_page_size,
+            max_import_bytes: self.max_import_bytes,
         }
     }
 }
@@ -163,6 +313,20 @@ fn parse_usize(key: &str, default: usize) -> usize {
         .unwrap_or(default)
 }
 
+fn parse_list(key: &str, default: &[&str]) -> Vec<String> {
+    let values: Vec<String> = env::var(key)
+        .unwrap_or_default()
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect();
+    if values.is_empty() {
+        default.iter().map(|value| value.to_string()).collect()
+    } else {
+        values
+    }
+}
+
 fn parse_u32(key: &str, default: u32) -> u32 {
     env::var(key)
         .ok()
@@ -203,5 +367,9 @@ mod tests {
             public.telemetry_buffer_max_files,
             DEFAULT_TELEMETRY_BUFFER_MAX_FILES
         );
+        assert_eq!(
+            public.telemetry_buffer_max_age_days,
+            DEFAULT_TELEMETRY_BUFFER_MAX_AGE_DAYS
+        );
     }
 }
@@ -1,11 +1,15 @@
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use chrono::Utc;
+use parking_lot::Mutex;
 use rusqlite::ffi::ErrorCode;
 use rusqlite::{Connection, Error as SqliteError, OpenFlags, OptionalExtension};
 use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
 use crate::errors::{AppError, AppResult};
@@ -24,18 +28,109 @@ pub struct DatabaseBootstrap {
     pub recovered: bool,
 }
 
+/// SQLCipher key derivation parameters. They are baked into the file when it is created, so
+/// an existing database keeps the ones recorded next to it in `<db>-cipher.json`; new values
+/// only take effect for a freshly created (or recovered) database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherSettings {
+    pub kdf_iter: u32,
+    pub page_size: u32,
+}
+
+impl Default for CipherSettings {
+    fn default() -> Self {
+        Self {
+            kdf_iter: 64_000,
+            page_size: 4096,
+        }
+    }
+}
+
+impl CipherSettings {
+    fn validate(&self) -> AppResult<()> {
+        if self.kdf_iter == 0 {
+            return Err(AppError::Config(
+                "SQLCipher KDF iterations must be positive".into(),
+            ));
+        }
+        if !self.page_size.is_power_of_two() || !(512..=65_536).contains(&self.page_size) {
+            return Err(AppError::Config(format!(
+                "SQLCipher page size must be a power of two between 512 and 65536, got {}",
+                self.page_size
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A table, view, index or column (as `table.column`) that a schema repair had to create.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct SchemaObject {
+    pub kind: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaRepairReport {
+    pub created: Vec<SchemaObject>,
+}
+
+/// Result row of `PRAGMA wal_checkpoint`: whether a reader or writer blocked it, the WAL size
+/// in frames afterwards, and how many frames were copied back into the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct WalCheckpoint {
+    pub busy: bool,
+    pub log_frames: i64,
+    pub checkpointed_frames: i64,
+}
+
+/// Runs `job` against the shared connection on Tokio's blocking pool so long queries don't
+/// stall the async workers that serve other commands.
+pub async fn run_blocking<T, F>(db: &Arc<Mutex<Connection>>, job: F) -> AppResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Connection) -> AppResult<T> + Send + 'static,
+{
+    let db = db.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut conn = db.lock();
+        job(&mut conn)
+    })
+    .await
+    .map_err(|err| AppError::Config(format!("database task failed: {err}")))?
+}
+
 pub fn bootstrap<P: AsRef<Path>>(
     data_dir: P,
     database_file: &str,
     vault: &SecretVault,
 ) -> AppResult<DatabaseBootstrap> {
+    bootstrap_with_cipher(data_dir, database_file, vault, CipherSettings::default())
+}
+
+pub fn bootstrap_with_cipher<P: AsRef<Path>>(
+    data_dir: P,
+    database_file: &str,
+    vault: &SecretVault,
+    requested: CipherSettings,
+) -> AppResult<DatabaseBootstrap> {
+    requested.validate()?;
     let data_dir = data_dir.as_ref();
     std::fs::create_dir_all(data_dir)?;
     let db_path = data_dir.join(database_file);
     let mut key_material = vault.ensure(DB_KEY_ALIAS)?;
+    let cipher = existing_cipher_settings(&db_path)?.unwrap_or(requested);
+    if cipher != requested {
+        warn!(
+            target: "database_bootstrap",
+            path = %db_path.display(),
+            "configured SQLCipher parameters only apply to a new database; keeping the existing ones"
+        );
+    }
 
-    match establish_context(&db_path, key_material.secret()) {
+    match establish_context(&db_path, key_material.secret(), cipher) {
         Ok(context) => {
+            persist_cipher_settings(&db_path, cipher)?;
             info!(
                 target: "database_bootstrap",
                 path = %db_path.display(),
@@ -60,7 +155,8 @@ pub fn bootstrap<P: AsRef<Path>>(
             if key_material.lifecycle() == SecretLifecycle::Retrieved {
                 key_material = vault.rotate(DB_KEY_ALIAS)?;
             }
-            let context = establish_context(&db_path, key_material.secret())?;
+            let context = establish_context(&db_path, key_material.secret(), requested)?;
+            persist_cipher_settings(&db_path, requested)?;
             Ok(DatabaseBootstrap {
                 context,
                 key_lifecycle: key_material.lifecycle(),
@@ -71,8 +167,12 @@ pub fn bootstrap<P: AsRef<Path>>(
     }
 }
 
-fn establish_context(db_path: &Path, passphrase: &SecretString) -> AppResult<DatabaseContext> {
-    match establish_context_with_mode(db_path, passphrase, true) {
+fn establish_context(
+    db_path: &Path,
+    passphrase: &SecretString,
+    cipher: CipherSettings,
+) -> AppResult<DatabaseContext> {
+    match establish_context_with_mode(db_path, passphrase, cipher, true) {
         Ok(context) => Ok(context),
         Err(err) if is_memory_security_error(&err) => {
             warn!(
@@ -80,7 +180,7 @@ fn establish_context(db_path: &Path, passphrase: &SecretString) -> AppResult<Dat
                 path = %db_path.display(),
                 "cipher_memory_security unsupported; continuing without locked pages"
             );
-            establish_context_with_mode(db_path, passphrase, false)
+            establish_context_with_mode(db_path, passphrase, cipher, false)
         }
         Err(err) => Err(err),
     }
@@ -89,12 +189,14 @@ fn establish_context(db_path: &Path, passphrase: &SecretString) -> AppResult<Dat
 fn establish_context_with_mode(
     db_path: &Path,
     passphrase: &SecretString,
+    cipher: CipherSettings,
     enforce_memory_security: bool,
 ) -> AppResult<DatabaseContext> {
     let flags = OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE;
     let connection = Connection::open_with_flags(db_path, flags)?;
-    apply_pragmas(&connection, passphrase)?;
+    apply_pragmas(&connection, passphrase, cipher)?;
     configure_cipher(&connection, enforce_memory_security)?;
+    crate::comparison::register_sql_functions(&connection)?;
     run_migrations(&connection)?;
     assert_encrypted(db_path)?;
 
@@ -104,12 +206,20 @@ fn establish_context_with_mode(
     })
 }
 
-fn apply_pragmas(connection: &Connection, passphrase: &SecretString) -> AppResult<()> {
+fn apply_pragmas(
+    connection: &Connection,
+    passphrase: &SecretString,
+    cipher: CipherSettings,
+) -> AppResult<()> {
     connection
-        .pragma_update(None, "cipher_default_page_size", 4096_i64)
+        .pragma_update(
+            None,
+            "cipher_default_page_size",
+            i64::from(cipher.page_size),
+        )
         .map_err(AppError::from)?;
     connection
-        .pragma_update(None, "cipher_default_kdf_iter", 64000_i64)
+        .pragma_update(None, "cipher_default_kdf_iter", i64::from(cipher.kdf_iter))
         .map_err(AppError::from)?;
     connection
         .pragma_update(None, "cipher_default_hmac_algorithm", "HMAC_SHA512")
@@ -160,6 +270,13 @@ fn is_memory_security_error(err: &AppError) -> bool {
 }
 
 fn run_migrations(connection: &Connection) -> AppResult<()> {
+    apply_schema(connection)?;
+    seed_default_project(connection)
+}
+
+/// Creates every table, column, index and view the app expects. Only schema statements run
+/// here, so it is safe to repeat against a database holding user data.
+fn apply_schema(connection: &Connection) -> AppResult<()> {
     connection.execute_batch(
         r#"
         CREATE TABLE IF NOT EXISTS comparison_projects (
@@ -214,6 +331,7 @@ fn run_migrations(connection: &Connection) -> AppResult<()> {
         );
 
         CREATE UNIQUE INDEX IF NOT EXISTS idx_raw_items_list_hash ON raw_items(list_id, source_row_hash);
+        CREATE INDEX IF NOT EXISTS idx_norm_cache_created ON normalization_cache(created_at);
         "#,
     )?;
 
@@ -227,13 +345,28 @@ fn run_migrations(connection: &Connection) -> AppResult<()> {
         "lists",
         "project_id INTEGER REFERENCES comparison_projects(id)",
     )?;
+    ensure_column(connection, "list_places", "source_row_hash TEXT")?;
+    ensure_column(connection, "list_places", "resolution_source TEXT")?;
+    ensure_column(connection, "normalization_cache", "confidence REAL")?;
+    ensure_column(connection, "places", "photo_name TEXT")?;
+    ensure_column(connection, "places", "rating REAL")?;
+    ensure_column(connection, "places", "user_rating_count INTEGER")?;
+    ensure_column(connection, "places", "phone_number TEXT")?;
+    ensure_column(connection, "places", "website_uri TEXT")?;
+    ensure_column(connection, "places", "opening_hours TEXT")?;
     ensure_column(connection, "lists", "slot TEXT NOT NULL DEFAULT 'A'")?;
     ensure_column(connection, "lists", "drive_file_name TEXT")?;
     ensure_column(connection, "lists", "drive_file_mime TEXT")?;
     ensure_column(connection, "lists", "drive_file_size INTEGER")?;
     ensure_column(connection, "lists", "drive_modified_time TEXT")?;
     ensure_column(connection, "lists", "drive_file_checksum TEXT")?;
+    ensure_column(connection, "lists", "display_name TEXT")?;
     ensure_column(connection, "comparison_projects", "last_compared_at TEXT")?;
+    ensure_column(
+        connection,
+        "comparison_projects",
+        "places_rate_limit_qps INTEGER",
+    )?;
     connection.execute_batch(
         r#"
         CREATE TABLE IF NOT EXISTS comparison_runs (
@@ -254,6 +387,41 @@ fn run_migrations(connection: &Connection) -> AppResult<()> {
         );
         "#,
     )?;
+    connection.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS comparison_run_members (
+            run_id INTEGER NOT NULL REFERENCES comparison_runs(id) ON DELETE CASCADE,
+            slot TEXT NOT NULL,
+            place_id TEXT NOT NULL,
+            PRIMARY KEY (run_id, slot, place_id)
+        );
+
+        CREATE TABLE IF NOT EXISTS refresh_state (
+            list_id INTEGER PRIMARY KEY REFERENCES lists(id) ON DELETE CASCADE,
+            status TEXT NOT NULL,
+            processed INTEGER NOT NULL DEFAULT 0,
+            total_rows INTEGER NOT NULL DEFAULT 0,
+            resolved INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL DEFAULT (DATETIME('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS import_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            project_id INTEGER NOT NULL REFERENCES comparison_projects(id) ON DELETE CASCADE,
+            slot TEXT NOT NULL,
+            source TEXT NOT NULL,
+            file_id TEXT,
+            file_name TEXT,
+            row_count INTEGER NOT NULL DEFAULT 0,
+            rejected_count INTEGER NOT NULL DEFAULT 0,
+            checksum TEXT,
+            imported_at TEXT NOT NULL DEFAULT (DATETIME('now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_import_history_project_slot
+            ON import_history(project_id, slot, imported_at);
+        "#,
+    )?;
     connection.execute(
         "CREATE INDEX IF NOT EXISTS idx_places_lat_lng ON places(lat, lng)",
         [],
@@ -277,7 +445,14 @@ fn run_migrations(connection: &Connection) -> AppResult<()> {
             p.formatted_address AS formatted_address,
             p.lat AS lat,
             p.lng AS lng,
-            p.types AS types
+            p.types AS types,
+            p.last_checked_at AS last_checked_at,
+            p.rating AS rating,
+            p.user_rating_count AS user_rating_count,
+            p.phone_number AS phone_number,
+            p.website_uri AS website_uri,
+            p.opening_hours AS opening_hours,
+            COALESCE(lpa.resolution_source, lpb.resolution_source) AS resolution_source
         FROM lists la
         JOIN list_places lpa ON lpa.list_id = la.id
         JOIN lists lb ON lb.project_id = la.project_id AND lb.slot = 'B'
@@ -293,7 +468,14 @@ fn run_migrations(connection: &Connection) -> AppResult<()> {
             p.formatted_address AS formatted_address,
             p.lat AS lat,
             p.lng AS lng,
-            p.types AS types
+            p.types AS types,
+            p.last_checked_at AS last_checked_at,
+            p.rating AS rating,
+            p.user_rating_count AS user_rating_count,
+            p.phone_number AS phone_number,
+            p.website_uri AS website_uri,
+            p.opening_hours AS opening_hours,
+            lpa.resolution_source AS resolution_source
         FROM lists la
         JOIN list_places lpa ON lpa.list_id = la.id
         LEFT JOIN lists lb ON lb.project_id = la.project_id AND lb.slot = 'B'
@@ -309,7 +491,14 @@ fn run_migrations(connection: &Connection) -> AppResult<()> {
             p.formatted_address AS formatted_address,
             p.lat AS lat,
             p.lng AS lng,
-            p.types AS types
+            p.types AS types,
+            p.last_checked_at AS last_checked_at,
+            p.rating AS rating,
+            p.user_rating_count AS user_rating_count,
+            p.phone_number AS phone_number,
+            p.website_uri AS website_uri,
+            p.opening_hours AS opening_hours,
+            lpb.resolution_source AS resolution_source
         FROM lists lb
         JOIN list_places lpb ON lpb.list_id = lb.id
         LEFT JOIN lists la ON la.project_id = lb.project_id AND la.slot = 'A'
@@ -318,10 +507,62 @@ fn run_migrations(connection: &Connection) -> AppResult<()> {
         WHERE lb.slot = 'B' AND lpa.place_id IS NULL;
         "#,
     )?;
-    seed_default_project(connection)?;
     Ok(())
 }
 
+/// Copies the WAL back into the database and truncates the `-wal` file to zero bytes.
+pub fn checkpoint_wal(connection: &Connection) -> AppResult<WalCheckpoint> {
+    let checkpoint = connection.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+        Ok(WalCheckpoint {
+            busy: row.get::<_, i64>(0)? != 0,
+            log_frames: row.get(1)?,
+            checkpointed_frames: row.get(2)?,
+        })
+    })?;
+    Ok(checkpoint)
+}
+
+/// Re-applies the schema against an open connection so a partially upgraded database gets
+/// any missing tables, views, indexes and columns. Data migrations and project seeding are
+/// skipped; they only run when the database is opened.
+pub fn repair_schema(connection: &Connection) -> AppResult<SchemaRepairReport> {
+    let before = schema_objects(connection)?;
+    apply_schema(connection)?;
+    let created: Vec<SchemaObject> = schema_objects(connection)?
+        .difference(&before)
+        .cloned()
+        .collect();
+    if !created.is_empty() {
+        warn!(
+            target: "database_bootstrap",
+            created = created.len(),
+            "schema repair recreated missing objects"
+        );
+    }
+    Ok(SchemaRepairReport { created })
+}
+
+fn schema_objects(connection: &Connection) -> AppResult<BTreeSet<SchemaObject>> {
+    let mut stmt = connection.prepare(
+        "SELECT type, name FROM sqlite_master
+        WHERE type IN ('table', 'view', 'index') AND name NOT LIKE 'sqlite_%'
+        UNION ALL
+        SELECT 'column', m.name || '.' || c.name
+        FROM sqlite_master m
+        JOIN pragma_table_info(m.name) c
+        WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%'",
+    )?;
+    let objects = stmt
+        .query_map([], |row| {
+            Ok(SchemaObject {
+                kind: row.get(0)?,
+                name: row.get(1)?,
+            })
+        })?
+        .collect::<Result<BTreeSet<_>, _>>()?;
+    Ok(objects)
+}
+
 fn ensure_column(connection: &Connection, table: &str, definition: &str) -> AppResult<()> {
     let column_name = definition
         .split_whitespace()
@@ -400,9 +641,38 @@ fn recover_encrypted_store(db_path: &Path) -> AppResult<()> {
     remove_if_exists(db_path)?;
     remove_if_exists(&wal_path(db_path))?;
     remove_if_exists(&shm_path(db_path))?;
+    remove_if_exists(&cipher_settings_path(db_path))?;
+    Ok(())
+}
+
+/// Parameters an existing database was created with; `None` for a new database. Files
+/// created before the parameters were recorded used the defaults.
+fn existing_cipher_settings(db_path: &Path) -> AppResult<Option<CipherSettings>> {
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    match std::fs::read(cipher_settings_path(db_path)) {
+        Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Some(CipherSettings::default())),
+        Err(err) => Err(AppError::Io(err)),
+    }
+}
+
+fn persist_cipher_settings(db_path: &Path, cipher: CipherSettings) -> AppResult<()> {
+    std::fs::write(cipher_settings_path(db_path), serde_json::to_vec(&cipher)?)?;
     Ok(())
 }
 
+fn cipher_settings_path(db_path: &Path) -> PathBuf {
+    let mut buf = db_path.to_path_buf();
+    let appended = format!(
+        "{}-cipher.json",
+        db_path.file_name().unwrap().to_string_lossy()
+    );
+    buf.set_file_name(appended);
+    buf
+}
+
 fn remove_if_exists(path: &Path) -> AppResult<()> {
     match std::fs::remove_file(path) {
         Ok(()) => Ok(()),
@@ -473,7 +743,7 @@ fn seed_default_project(connection: &Connection) -> AppResult<()> {
     )?;
 
     connection.execute(
-        "UPDATE lists SET slot = UPPER(slot) WHERE slot NOT IN ('A','B')",
+        "UPDATE lists SET slot = UPPER(slot) WHERE slot <> UPPER(slot)",
         [],
     )?;
 
@@ -508,6 +778,98 @@ mod tests {
         assert_eq!(bootstrap.key_lifecycle, SecretLifecycle::Created);
     }
 
+    #[test]
+    fn repair_recreates_dropped_view() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let conn = bootstrap(dir.path(), "repair.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+
+        let untouched = repair_schema(&conn).unwrap();
+        assert!(untouched.created.is_empty());
+
+        conn.execute_batch(
+            "DROP VIEW comparison_only_a;
+            DROP INDEX idx_places_lat_lng;",
+        )
+        .unwrap();
+        let report = repair_schema(&conn).unwrap();
+        let created: Vec<_> = report
+            .created
+            .iter()
+            .map(|object| (object.kind.as_str(), object.name.as_str()))
+            .collect();
+        assert_eq!(
+            created,
+            vec![
+                ("index", "idx_places_lat_lng"),
+                ("view", "comparison_only_a")
+            ]
+        );
+        let views: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'view' AND name = 'comparison_only_a'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(views, 1);
+    }
+
+    #[test]
+    fn repair_leaves_list_slots_and_projects_alone() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let conn = bootstrap(dir.path(), "repair-data.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        conn.execute_batch(
+            "INSERT INTO lists (project_id, slot, name)
+                SELECT id, 'A', 'Bakeries' FROM comparison_projects WHERE is_active = 1;
+            UPDATE comparison_projects SET is_active = 0;",
+        )
+        .unwrap();
+
+        repair_schema(&conn).unwrap();
+        let (slot, active): (String, i64) = conn
+            .query_row(
+                "SELECT
+                    (SELECT slot FROM lists WHERE name = 'Bakeries'),
+                    (SELECT COUNT(*) FROM comparison_projects WHERE is_active = 1)",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((slot.as_str(), active), ("A", 0));
+    }
+
+    #[test]
+    fn checkpoint_truncates_wal_after_large_write() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let context = bootstrap(dir.path(), "checkpoint.db", &vault)
+            .unwrap()
+            .context;
+        let conn = context.connection;
+        conn.execute_batch(
+            "INSERT INTO places (place_id, name, lat, lng)
+            WITH RECURSIVE seq(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM seq WHERE n < 5000)
+            SELECT 'bulk_' || n, printf('Place %d with a longer name', n), n * 0.001, n * 0.001
+            FROM seq;",
+        )
+        .unwrap();
+        let wal = wal_path(&context.path);
+        assert!(std::fs::metadata(&wal).unwrap().len() > 0);
+
+        let checkpoint = checkpoint_wal(&conn).unwrap();
+        assert!(!checkpoint.busy);
+        assert_eq!(checkpoint.log_frames, 0);
+        assert_eq!(std::fs::metadata(&wal).unwrap().len(), 0);
+    }
+
     #[test]
     fn ensures_data_file_is_encrypted() {
         let dir = tempdir().unwrap();
@@ -583,4 +945,83 @@ mod tests {
         let page_size: i64 = page_size.parse().unwrap_or(-1);
         assert_eq!(page_size, 4096);
     }
+
+    #[test]
+    fn custom_cipher_settings_apply_to_new_database_only() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let custom = CipherSettings {
+            kdf_iter: 10_000,
+            page_size: 8192,
+        };
+        let first = bootstrap_with_cipher(dir.path(), "kdf.db", &vault, custom).unwrap();
+        first
+            .context
+            .connection
+            .execute(
+                "INSERT INTO comparison_projects (name, slug) VALUES ('Tuned', 'tuned')",
+                [],
+            )
+            .unwrap();
+        drop(first);
+
+        let reopened = bootstrap_with_cipher(dir.path(), "kdf.db", &vault, custom).unwrap();
+        assert!(!reopened.recovered);
+        let name: String = reopened
+            .context
+            .connection
+            .query_row(
+                "SELECT name FROM comparison_projects WHERE slug = 'tuned'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(name, "Tuned");
+        drop(reopened);
+
+        // A different configuration must not lock the existing file out.
+        let defaults = bootstrap(dir.path(), "kdf.db", &vault).unwrap();
+        assert!(!defaults.recovered);
+        let kdf_iter: String = defaults
+            .context
+            .connection
+            .pragma_query_value(None, "cipher_default_kdf_iter", |row| row.get(0))
+            .unwrap();
+        assert_eq!(kdf_iter.parse::<i64>().unwrap(), 10_000);
+
+        let invalid = CipherSettings {
+            kdf_iter: 10_000,
+            page_size: 1000,
+        };
+        assert!(bootstrap_with_cipher(dir.path(), "other.db", &vault, invalid).is_err());
+    }
+
+    #[tokio::test]
+    async fn blocking_jobs_leave_the_runtime_responsive() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let db = Arc::new(Mutex::new(
+            bootstrap(dir.path(), "blocking.db", &vault)
+                .unwrap()
+                .context
+                .connection,
+        ));
+        let started = std::time::Instant::now();
+        let long_snapshot = run_blocking(&db, |conn| {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            Ok(
+                conn.query_row("SELECT COUNT(*) FROM comparison_projects", [], |row| {
+                    row.get::<_, i64>(0)
+                })?,
+            )
+        });
+        let lightweight = async {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            started.elapsed()
+        };
+        let (projects, lightweight_elapsed) = tokio::join!(long_snapshot, lightweight);
+        assert_eq!(projects.unwrap(), 1);
+        assert!(lightweight_elapsed < std::time::Duration::from_millis(250));
+        assert!(started.elapsed() >= std::time::Duration::from_millis(500));
+    }
 }
@@ -1,5 +1,7 @@
 use std::io;
 
+use reqwest::StatusCode;
+use serde::Serialize;
 use thiserror::Error;
 
 pub type AppResult<T> = Result<T, AppError>;
@@ -26,4 +28,98 @@ pub enum AppError {
     Tauri(#[from] tauri::Error),
     #[error("parse error: {0}")]
     Parse(String),
+    #[error("{0}")]
+    Cancelled(String),
+}
+
+/// Broad failure categories the frontend can branch on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Network,
+    RateLimited,
+    Parse,
+    Storage,
+    Database,
+    Config,
+    Keychain,
+    Cancelled,
+    Internal,
+}
+
+/// Serializable error returned by Tauri commands so the frontend can tell failure kinds apart
+/// instead of matching on flattened strings.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub details: Vec<String>,
+}
+
+impl AppError {
+    /// Classifies the error without assuming which operation raised it.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            AppError::Http(err) if err.status() == Some(StatusCode::TOO_MANY_REQUESTS) => {
+                ErrorKind::RateLimited
+            }
+            AppError::Http(_) => ErrorKind::Network,
+            AppError::Parse(_) | AppError::Json(_) | AppError::Csv(_) => ErrorKind::Parse,
+            AppError::Io(_) => ErrorKind::Storage,
+            AppError::Database(_) => ErrorKind::Database,
+            AppError::Config(_) => ErrorKind::Config,
+            AppError::Keychain(_) => ErrorKind::Keychain,
+            AppError::Cancelled(_) => ErrorKind::Cancelled,
+            AppError::Path(_) | AppError::Tauri(_) => ErrorKind::Internal,
+        }
+    }
+
+    fn details(&self) -> Vec<String> {
+        let sanitize = crate::sanitize_error_copy;
+        match self {
+            AppError::Http(err) => {
+                let mut details = Vec::new();
+                if let Some(status) = err.status() {
+                    details.push(format!("HTTP status: {}", status));
+                }
+                if err.is_timeout() {
+                    details.push("The request timed out before the server responded.".into());
+                } else if err.is_connect() {
+                    details.push("The server could not be reached.".into());
+                } else if err.status() != Some(StatusCode::TOO_MANY_REQUESTS) {
+                    if let Some(host) = err.url().and_then(|url| url.host_str()) {
+                        details.push(format!("Endpoint host: {}", host));
+                    }
+                    details.push(format!("Transport: {}", sanitize(&err.to_string())));
+                }
+                details
+            }
+            AppError::Parse(reason) => vec![format!("Parser: {}", sanitize(reason))],
+            AppError::Csv(reason) => vec![format!("Parser: {}", sanitize(&reason.to_string()))],
+            AppError::Json(reason) => {
+                vec![format!("JSON error: {}", sanitize(&reason.to_string()))]
+            }
+            AppError::Io(reason) => vec![format!("I/O error: {}", sanitize(&reason.to_string()))],
+            AppError::Database(reason) => {
+                vec![format!("SQLite error: {}", sanitize(&reason.to_string()))]
+            }
+            AppError::Keychain(reason) => {
+                vec![format!("Keychain: {}", sanitize(&reason.to_string()))]
+            }
+            AppError::Config(_)
+            | AppError::Cancelled(_)
+            | AppError::Path(_)
+            | AppError::Tauri(_) => Vec::new(),
+        }
+    }
+}
+
+impl From<AppError> for CommandError {
+    fn from(err: AppError) -> Self {
+        Self {
+            kind: err.kind(),
+            message: crate::sanitize_error_copy(&err.to_string()),
+            details: err.details(),
+        }
+    }
 }
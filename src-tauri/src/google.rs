@@ -1,4 +1,7 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::task::{Context, Poll};
 use std::time::Duration as StdDuration;
 
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
@@ -8,13 +11,15 @@ use md5;
 use parking_lot::Mutex;
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
-use reqwest::{Client, StatusCode, Url};
+use reqwest::header::{ACCEPT_RANGES, RANGE};
+use reqwest::{Client, Response, StatusCode, Url};
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpListener;
-use tokio::sync::oneshot;
+use tempfile::TempPath;
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, Notify};
 use tokio::time::{sleep, timeout};
 
 use crate::config::AppConfig;
@@ -25,7 +30,10 @@ use crate::telemetry::TelemetryClient;
 use tracing::warn;
 
 const TOKEN_ALIAS: &str = "google-oauth-token";
+const ACTIVE_ACCOUNT_ALIAS: &str = "google-oauth-active-account";
+const ACCOUNTS_ALIAS: &str = "google-oauth-accounts";
 const DRIVE_KML_MIME: &str = "application/vnd.google-earth.kml+xml";
+const DRIVE_KMZ_MIME: &str = "application/vnd.google-earth.kmz";
 const DRIVE_MAPS_MIME: &str = "application/vnd.google-apps.map";
 const DRIVE_KML_EXPORT_MIME: &str = "application/vnd.google-earth.kml+xml";
 const DEVICE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
@@ -33,16 +41,14 @@ const DEFAULT_WAIT_SECS: u64 = 5;
 const DEFAULT_LOOPBACK_TIMEOUT_SECS: u64 = 180;
 const LOOPBACK_PATH: &str = "/auth/callback";
 const LOOPBACK_HOST: &str = "127.0.0.1";
+const LOOPBACK_SESSION_MINUTES: i64 = 10;
+const MAX_LOOPBACK_REQUEST_LINE_BYTES: usize = 16 * 1024;
 const MAX_DOWNLOAD_ATTEMPTS: u32 = 3;
 const DOWNLOAD_RETRY_DELAY_MS: u64 = 500;
 
-const GOOGLE_SCOPES: &[&str] = &[
-    "https://www.googleapis.com/auth/drive.readonly",
-    "https://www.googleapis.com/auth/drive.metadata.readonly",
-    "openid",
-    "email",
-    "profile",
-];
+const GOOGLE_SCOPE_PREFIX: &str = "https://www.googleapis.com/auth/";
+const DRIVE_SCOPES: &[&str] = &["drive.readonly", "drive.file"];
+const OPENID_SCOPES: &[&str] = &["openid", "email", "profile"];
 
 #[derive(Clone)]
 pub struct GoogleServices {
@@ -52,6 +58,33 @@ pub struct GoogleServices {
     telemetry: TelemetryClient,
     pending_auth: Arc<Mutex<Option<LoopbackSession>>>,
     refresh_state: Arc<RefreshState>,
+    refresh_observer: Arc<Mutex<Option<RefreshObserver>>>,
+    offline: Arc<AtomicBool>,
+    session: Arc<SessionSignal>,
+    sign_out_observer: Arc<Mutex<Option<SignOutObserver>>>,
+}
+
+pub type RefreshObserver = Arc<dyn Fn(RefreshEvent) + Send + Sync>;
+/// Called when the user signs out, before the token is revoked.
+pub type SignOutObserver = Arc<dyn Fn() + Send + Sync>;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RefreshEvent {
+    Failed {
+        reason: String,
+        retry_at: DateTime<Utc>,
+    },
+    Recovered,
+}
+
+impl RefreshEvent {
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            RefreshEvent::Failed { .. } => "auth://refresh_failed",
+            RefreshEvent::Recovered => "auth://refresh_recovered",
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -62,9 +95,11 @@ struct GoogleSettings {
     auth_endpoint: String,
     token_endpoint: String,
     userinfo_endpoint: String,
+    revoke_endpoint: String,
     drive_api_base: String,
     scopes: String,
     picker_page_size: usize,
+    max_import_bytes: u64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -83,7 +118,7 @@ pub struct LoopbackFlowState {
     pub expires_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoogleIdentity {
     pub email: String,
     pub name: Option<String>,
@@ -91,6 +126,13 @@ pub struct GoogleIdentity {
     pub expires_at: DateTime<Utc>,
 }
 
+impl GoogleIdentity {
+    /// Compares the profile fields only; `expires_at` follows the token and changes on refresh.
+    fn same_profile(&self, other: &GoogleIdentity) -> bool {
+        self.email == other.email && self.name == other.name && self.picture == other.picture
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DriveFileMetadata {
     pub id: String,
@@ -99,6 +141,8 @@ pub struct DriveFileMetadata {
     pub modified_time: Option<String>,
     pub size: Option<u64>,
     pub md5_checksum: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -109,6 +153,81 @@ pub struct DownloadedFile {
     pub expected_bytes: Option<u64>,
 }
 
+#[derive(Debug)]
+pub struct DownloadedTempFile {
+    pub path: TempPath,
+    pub checksum_md5: String,
+    pub received_bytes: u64,
+    pub expected_bytes: Option<u64>,
+}
+
+struct DownloadStats {
+    checksum_md5: String,
+    received_bytes: u64,
+    expected_bytes: Option<u64>,
+}
+
+/// Bytes already written to the sink, carried across attempts so a dropped connection can
+/// resume with a range request when the server advertised `Accept-Ranges: bytes`.
+struct DownloadState {
+    received: u64,
+    digest: md5::Context,
+    total: Option<u64>,
+    resumable: bool,
+}
+
+impl Default for DownloadState {
+    fn default() -> Self {
+        Self {
+            received: 0,
+            digest: md5::Context::new(),
+            total: None,
+            resumable: false,
+        }
+    }
+}
+
+struct TempFileSink {
+    file: tokio::fs::File,
+    path: TempPath,
+}
+
+impl TempFileSink {
+    fn create() -> AppResult<Self> {
+        let temp = tempfile::Builder::new()
+            .prefix("drive-download-")
+            .tempfile()?;
+        let file = tokio::fs::File::from_std(temp.reopen()?);
+        Ok(Self {
+            file,
+            path: temp.into_temp_path(),
+        })
+    }
+
+    async fn into_temp_path(self) -> AppResult<TempPath> {
+        self.file.sync_all().await?;
+        Ok(self.path)
+    }
+}
+
+impl AsyncWrite for TempFileSink {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.file).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.file).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.file).poll_shutdown(cx)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredGoogleToken {
     pub access_token: String,
@@ -120,6 +239,16 @@ pub struct StoredGoogleToken {
     pub next_refresh: Option<DateTime<Utc>>,
     #[serde(default)]
     pub last_failure: Option<String>,
+    #[serde(default)]
+    pub identity: Option<GoogleIdentity>,
+    #[serde(default)]
+    pub account: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GoogleAccount {
+    pub email: String,
+    pub active: bool,
 }
 
 impl StoredGoogleToken {
@@ -141,6 +270,8 @@ impl StoredGoogleToken {
             token_type,
             next_refresh: None,
             last_failure: None,
+            identity: None,
+            account: None,
         }
     }
 
@@ -159,6 +290,7 @@ impl From<DriveFileRaw> for DriveFileMetadata {
             modified_time: value.modified_time,
             size: value.size.and_then(|s| s.parse().ok()),
             md5_checksum: value.md5_checksum,
+            source: None,
         }
     }
 }
@@ -176,6 +308,15 @@ struct AuthCallback {
     state: String,
 }
 
+/// Shared by every clone of the services so a sign-out reaches the background refresh loop.
+#[derive(Default)]
+struct SessionSignal {
+    signed_out: AtomicBool,
+    /// Bumped on sign-out; a refresh loop exits once the epoch it started in has passed.
+    epoch: AtomicU64,
+    shutdown: Notify,
+}
+
 struct RefreshState {
     next_refresh: Mutex<Option<DateTime<Utc>>>,
     refreshing: Mutex<bool>,
@@ -196,7 +337,8 @@ impl GoogleServices {
             _ => return Ok(None),
         };
 
-        let http = Client::builder()
+        let http = config
+            .http_client_builder()?
             .user_agent("google-maps-list-comparator/0.1.0")
             .build()?;
 
@@ -215,27 +357,89 @@ impl GoogleServices {
                 auth_endpoint: config.google_auth_endpoint.clone(),
                 token_endpoint: config.google_token_endpoint.clone(),
                 userinfo_endpoint: config.google_userinfo_endpoint.clone(),
+                revoke_endpoint: config.google_revoke_endpoint.clone(),
                 drive_api_base: config
                     .google_drive_api_base
                     .trim_end_matches('/')
                     .to_string(),
-                scopes: GOOGLE_SCOPES.join(" "),
+                scopes: resolve_scopes(&config.google_oauth_scopes)?,
                 picker_page_size: config.google_drive_picker_page_size,
+                max_import_bytes: config.max_import_bytes,
             },
             vault: vault.clone(),
             pending_auth: Arc::new(Mutex::new(None)),
             telemetry,
             refresh_state: Arc::clone(&refresh_state),
+            refresh_observer: Arc::new(Mutex::new(None)),
+            offline: Arc::new(AtomicBool::new(false)),
+            session: Arc::new(SessionSignal::default()),
+            sign_out_observer: Arc::new(Mutex::new(None)),
         };
 
         instance.restore_refresh_state();
+        instance.spawn_refresh_loop();
 
-        let refresher = instance.clone();
-        tokio::spawn(async move {
-            refresher.run_refresh_loop().await;
+        Ok(Some(instance))
+    }
+
+    fn spawn_refresh_loop(&self) {
+        let refresher = self.clone();
+        // Read before spawning so a sign-out racing the task's start still ends this loop.
+        let epoch = self.session.epoch.load(Ordering::SeqCst);
+        tauri::async_runtime::spawn(async move {
+            refresher.run_refresh_loop(epoch).await;
         });
+    }
 
-        Ok(Some(instance))
+    pub fn set_refresh_observer(&self, observer: RefreshObserver) {
+        *self.refresh_observer.lock() = Some(observer);
+    }
+
+    fn notify_refresh(&self, event: RefreshEvent) {
+        let observer = self.refresh_observer.lock().clone();
+        if let Some(callback) = observer {
+            callback(event);
+        }
+    }
+
+    pub fn set_sign_out_observer(&self, observer: SignOutObserver) {
+        *self.sign_out_observer.lock() = Some(observer);
+    }
+
+    /// True between a sign-out and the next sign-in or account switch; Drive calls are refused
+    /// meanwhile.
+    pub fn is_signed_out(&self) -> bool {
+        self.session.signed_out.load(Ordering::SeqCst)
+    }
+
+    fn end_session(&self) {
+        self.session.signed_out.store(true, Ordering::SeqCst);
+        self.session.epoch.fetch_add(1, Ordering::SeqCst);
+        self.session.shutdown.notify_waiters();
+        let observer = self.sign_out_observer.lock().clone();
+        if let Some(callback) = observer {
+            callback();
+        }
+    }
+
+    fn resume_session(&self) {
+        if self.session.signed_out.swap(false, Ordering::SeqCst) {
+            self.spawn_refresh_loop();
+        }
+    }
+
+    /// While offline, sign-in, identity and Drive calls fail immediately instead of timing out.
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
+    fn ensure_online(&self) -> AppResult<()> {
+        if self.offline.load(Ordering::SeqCst) {
+            return Err(AppError::Config(
+                "offline mode is on; turn it off to reach Google".into(),
+            ));
+        }
+        Ok(())
     }
 
     pub fn picker_page_size(&self) -> usize {
@@ -243,6 +447,7 @@ impl GoogleServices {
     }
 
     pub async fn start_device_flow(&self) -> AppResult<DeviceFlowState> {
+        self.ensure_online()?;
         let response = self
             .http
             .post(&self.config.device_code_endpoint)
@@ -272,13 +477,14 @@ impl GoogleServices {
     }
 
     pub async fn start_loopback_flow(&self) -> AppResult<LoopbackFlowState> {
+        self.ensure_online()?;
         let listener = TcpListener::bind((LOOPBACK_HOST, 0)).await?;
         let port = listener.local_addr()?.port();
         let redirect_url = format!("http://{LOOPBACK_HOST}:{port}{LOOPBACK_PATH}");
         let state = random_token(24);
         let code_verifier = random_verifier(64);
         let code_challenge = build_code_challenge(&code_verifier)?;
-        let expires_at = Utc::now() + Duration::minutes(10);
+        let expires_at = Utc::now() + Duration::minutes(LOOPBACK_SESSION_MINUTES);
 
         let mut auth_url = Url::parse(&self.config.auth_endpoint)
             .map_err(|err| AppError::Config(format!("invalid Google auth endpoint: {err}")))?;
@@ -296,7 +502,14 @@ impl GoogleServices {
 
         let (tx, rx) = oneshot::channel();
         tokio::spawn(async move {
-            let result = handle_loopback_callback(listener).await;
+            let listen_for = StdDuration::from_secs(LOOPBACK_SESSION_MINUTES as u64 * 60);
+            let result = timeout(listen_for, handle_loopback_callback(listener))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(AppError::Config(
+                        "timed out waiting for Google approval".into(),
+                    ))
+                });
             let _ = tx.send(result);
         });
 
@@ -356,22 +569,142 @@ impl GoogleServices {
         let token_response = self
             .exchange_code_for_token(&callback.code, &redirect_url, &code_verifier)
             .await?;
-        let token = self.store_token(token_response, None)?;
+        let token = self.store_token(token_response, None, None)?;
+        self.resume_session();
         self.fetch_identity(&token).await
     }
 
-    pub async fn current_identity(&self) -> AppResult<GoogleIdentity> {
+    pub async fn current_identity(&self, force: bool) -> AppResult<GoogleIdentity> {
         let token = self.ensure_token().await?;
-        self.fetch_identity(&token).await
+        self.identity_for(&token, force).await
     }
 
-    pub fn sign_out(&self) -> AppResult<()> {
-        let mut pending = self.pending_auth.lock();
-        *pending = None;
+    pub async fn sign_out(&self) -> AppResult<()> {
+        self.end_session();
+        {
+            let mut pending = self.pending_auth.lock();
+            *pending = None;
+        }
+        let token = self.load_token().ok().flatten();
+        if let Some(token) = token.as_ref() {
+            let revoked = self.revoke_token(token).await;
+            let _ = self.telemetry.record(
+                "signout_revocation",
+                serde_json::json!({
+                    "revoked": revoked,
+                }),
+            );
+        }
         self.vault.delete(TOKEN_ALIAS)?;
+        if let Some(email) = token.and_then(|token| token.account) {
+            self.vault.delete(&account_alias(&email))?;
+            let mut accounts = self.account_index()?;
+            accounts.retain(|entry| entry != &email);
+            self.write_account_index(&accounts)?;
+            match accounts.first() {
+                Some(next) => self.set_active_account(next)?,
+                None => self.vault.delete(ACTIVE_ACCOUNT_ALIAS)?,
+            }
+        }
+        self.reset_refresh_state();
         Ok(())
     }
 
+    pub fn list_accounts(&self) -> AppResult<Vec<GoogleAccount>> {
+        let active = self.active_account()?;
+        Ok(self
+            .account_index()?
+            .into_iter()
+            .map(|email| GoogleAccount {
+                active: active.as_deref() == Some(email.as_str()),
+                email,
+            })
+            .collect())
+    }
+
+    pub async fn switch_account(&self, email: &str) -> AppResult<GoogleIdentity> {
+        let known = self.account_index()?.iter().any(|entry| entry == email);
+        if !known || !self.vault.has(&account_alias(email))? {
+            return Err(AppError::Config(format!(
+                "no stored Google sign-in for {email}"
+            )));
+        }
+        self.set_active_account(email)?;
+        self.reset_refresh_state();
+        self.resume_session();
+        self.current_identity(false).await
+    }
+
+    fn active_account(&self) -> AppResult<Option<String>> {
+        Ok(self
+            .vault
+            .read_secret(ACTIVE_ACCOUNT_ALIAS)?
+            .map(|value| value.expose_secret().to_string()))
+    }
+
+    fn set_active_account(&self, email: &str) -> AppResult<()> {
+        self.vault
+            .write_secret(ACTIVE_ACCOUNT_ALIAS, &SecretString::new(email.into()))
+    }
+
+    fn account_index(&self) -> AppResult<Vec<String>> {
+        match self.vault.read_secret(ACCOUNTS_ALIAS)? {
+            Some(value) => Ok(serde_json::from_str(value.expose_secret())?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn write_account_index(&self, accounts: &[String]) -> AppResult<()> {
+        let payload = serde_json::to_string(accounts)?;
+        self.vault
+            .write_secret(ACCOUNTS_ALIAS, &SecretString::new(payload.into()))
+    }
+
+    /// Moves a freshly signed-in token under its account alias and makes that
+    /// account the active one.
+    fn register_account(&self, token: &StoredGoogleToken, email: &str) -> AppResult<()> {
+        let mut keyed = token.clone();
+        keyed.account = Some(email.to_string());
+        self.persist_refresh_state(&keyed, None)?;
+        self.vault.delete(TOKEN_ALIAS)?;
+        let mut accounts = self.account_index()?;
+        if !accounts.iter().any(|entry| entry == email) {
+            accounts.push(email.to_string());
+            self.write_account_index(&accounts)?;
+        }
+        self.set_active_account(email)
+    }
+
+    fn reset_refresh_state(&self) {
+        *self.refresh_state.next_refresh.lock() = None;
+        *self.refresh_state.last_failure.lock() = None;
+        self.restore_refresh_state();
+    }
+
+    async fn revoke_token(&self, token: &StoredGoogleToken) -> bool {
+        let value = token
+            .refresh_token
+            .as_deref()
+            .unwrap_or(token.access_token.as_str());
+        match self
+            .http
+            .post(&self.config.revoke_endpoint)
+            .form(&[("token", value)])
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => true,
+            Ok(response) => {
+                warn!(status = %response.status(), "google token revocation was rejected");
+                false
+            }
+            Err(err) => {
+                warn!(?err, "google token revocation request failed");
+                false
+            }
+        }
+    }
+
     pub async fn complete_device_flow(
         &self,
         device_code: &str,
@@ -394,7 +727,8 @@ impl GoogleServices {
 
             if response.status().is_success() {
                 let success: TokenSuccessResponse = response.json().await?;
-                let token = self.store_token(success, None)?;
+                let token = self.store_token(success, None, None)?;
+                self.resume_session();
                 return self.fetch_identity(&token).await;
             }
 
@@ -429,8 +763,15 @@ impl GoogleServices {
     }
 
     pub async fn ensure_token(&self) -> AppResult<StoredGoogleToken> {
-        match self.load_token()? {
+        self.ensure_online()?;
+        let token = if self.is_signed_out() {
+            None
+        } else {
+            self.load_token()?
+        };
+        match token {
             Some(token) if !token.is_expired() => {
+                self.clear_refresh_failure(&token);
                 self.update_next_refresh(&token);
                 Ok(token)
             }
@@ -438,7 +779,8 @@ impl GoogleServices {
                 let refresh = token.refresh_token.ok_or_else(|| {
                     AppError::Config("google token expired and cannot refresh".into())
                 })?;
-                let refreshed = self.refresh_token(&refresh).await?;
+                let refreshed = self.refresh_token(&refresh, token.account).await?;
+                self.clear_refresh_failure(&refreshed);
                 self.update_next_refresh(&refreshed);
                 Ok(refreshed)
             }
@@ -448,9 +790,18 @@ impl GoogleServices {
         }
     }
 
+    /// Refreshes the access token now, regardless of the scheduled refresh time.
+    pub async fn force_refresh(&self) -> AppResult<StoredGoogleToken> {
+        self.refresh_with(true).await
+    }
+
     pub async fn refresh_if_due(&self) -> AppResult<StoredGoogleToken> {
+        self.refresh_with(false).await
+    }
+
+    async fn refresh_with(&self, force: bool) -> AppResult<StoredGoogleToken> {
         let token = self.ensure_token().await?;
-        let due = {
+        let due = force || {
             let next = *self.refresh_state.next_refresh.lock();
             next.map(|when| Utc::now() >= when).unwrap_or(true)
         };
@@ -472,7 +823,7 @@ impl GoogleServices {
         }
 
         let result = match token.refresh_token {
-            Some(ref refresh) => self.refresh_token(refresh).await,
+            Some(ref refresh) => self.refresh_token(refresh, token.account.clone()).await,
             None => Err(AppError::Config(
                 "google token expired and cannot refresh".into(),
             )),
@@ -485,28 +836,53 @@ impl GoogleServices {
 
         match result {
             Ok(new_token) => {
+                self.clear_refresh_failure(&new_token);
                 self.update_next_refresh(&new_token);
-                *self.refresh_state.last_failure.lock() = None;
                 Ok(new_token)
             }
             Err(err) => {
                 let retry_at = Utc::now() + Duration::minutes(1);
                 *self.refresh_state.next_refresh.lock() = Some(retry_at);
                 let message = err.to_string();
+                let reason = sanitize_error_copy(&message);
                 *self.refresh_state.last_failure.lock() = Some(message.clone());
                 let _ = self.telemetry.record(
                     "refresh_error",
                     serde_json::json!({
-                        "reason": sanitize_error_copy(&message),
+                        "reason": reason.clone(),
                         "retry_at": retry_at,
                     }),
                 );
                 let _ = self.persist_refresh_state(&token, Some(&message));
+                self.notify_refresh(RefreshEvent::Failed { reason, retry_at });
                 Err(err)
             }
         }
     }
 
+    pub async fn get_file_metadata(&self, file_id: &str) -> AppResult<DriveFileMetadata> {
+        let token = self.ensure_token().await?;
+        let mut url = self.drive_url()?;
+        url.path_segments_mut()
+            .map_err(|_| AppError::Config("invalid Drive API base".into()))?
+            .push("files")
+            .push(file_id);
+        url.query_pairs_mut()
+            .append_pair("fields", "id,name,mimeType,modifiedTime,size,md5Checksum");
+
+        let response = self
+            .http
+            .get(url)
+            .bearer_auth(token.access_token)
+            .send()
+            .await?;
+        if let Some(err) = drive_auth_error(response.status()) {
+            return Err(err);
+        }
+        let raw: DriveFileRaw = response.error_for_status()?.json().await?;
+        Ok(raw.into())
+    }
+
     pub async fn list_kml_files(&self, limit: Option<usize>) -> AppResult<Vec<DriveFileMetadata>> {
         let token = self.ensure_token().await?;
         let target = limit.unwrap_or(self.config.picker_page_size).max(1);
@@ -526,7 +902,7 @@ impl GoogleServices {
                     .append_pair(
                         "q",
                         &format!(
-                            "(mimeType='{DRIVE_KML_MIME}' OR mimeType='{DRIVE_MAPS_MIME}') and trashed = false"
+                            "(mimeType='{DRIVE_KML_MIME}' OR mimeType='{DRIVE_KMZ_MIME}' OR mimeType='{DRIVE_MAPS_MIME}') and trashed = false"
                         ),
                     )
                     .append_pair(
@@ -574,13 +950,86 @@ impl GoogleServices {
         mime_type: Option<&str>,
         expected_size: Option<u64>,
         expected_md5: Option<&str>,
-        mut progress: F,
+        progress: F,
     ) -> AppResult<DownloadedFile>
     where
         F: FnMut(u64, Option<u64>) + Send,
     {
+        let (bytes, stats) = self
+            .download_with_retry(
+                file_id,
+                mime_type,
+                expected_size,
+                expected_md5,
+                progress,
+                None,
+                || Ok(Vec::new()),
+            )
+            .await?;
+        Ok(DownloadedFile {
+            bytes,
+            checksum_md5: stats.checksum_md5,
+            received_bytes: stats.received_bytes,
+            expected_bytes: stats.expected_bytes,
+        })
+    }
+
+    /// Streams the file into a temporary file instead of holding it in memory.
+    /// The file is removed when the returned `TempPath` is dropped, and a partial file is
+    /// removed as soon as `cancel` trips.
+    pub async fn download_file_to_disk<F>(
+        &self,
+        file_id: &str,
+        mime_type: Option<&str>,
+        expected_size: Option<u64>,
+        expected_md5: Option<&str>,
+        progress: F,
+        cancel: Option<&AtomicBool>,
+    ) -> AppResult<DownloadedTempFile>
+    where
+        F: FnMut(u64, Option<u64>) + Send,
+    {
+        let (sink, stats) = self
+            .download_with_retry(
+                file_id,
+                mime_type,
+                expected_size,
+                expected_md5,
+                progress,
+                cancel,
+                TempFileSink::create,
+            )
+            .await?;
+        Ok(DownloadedTempFile {
+            path: sink.into_temp_path().await?,
+            checksum_md5: stats.checksum_md5,
+            received_bytes: stats.received_bytes,
+            expected_bytes: stats.expected_bytes,
+        })
+    }
+
+    async fn download_with_retry<F, W, M>(
+        &self,
+        file_id: &str,
+        mime_type: Option<&str>,
+        expected_size: Option<u64>,
+        expected_md5: Option<&str>,
+        mut progress: F,
+        cancel: Option<&AtomicBool>,
+        mut make_sink: M,
+    ) -> AppResult<(W, DownloadStats)>
+    where
+        F: FnMut(u64, Option<u64>) + Send,
+        W: AsyncWrite + Unpin + Send,
+        M: FnMut() -> AppResult<W>,
+    {
+        if let Some(size) = expected_size {
+            self.check_import_size(size)?;
+        }
         let mut attempt = 0;
         let mut last_err: Option<AppError> = None;
+        let mut sink = make_sink()?;
+        let mut state = DownloadState::default();
         while attempt < MAX_DOWNLOAD_ATTEMPTS {
             attempt += 1;
             let result = self
@@ -590,21 +1039,34 @@ impl GoogleServices {
                     expected_size,
                     expected_md5,
                     &mut progress,
+                    cancel,
+                    &mut sink,
+                    &mut state,
                 )
                 .await;
             match result {
-                Ok(file) => return Ok(file),
+                Ok(stats) => return Ok((sink, stats)),
                 Err(err) => {
                     let retryable = should_retry_download(&err);
                     if !retryable || attempt >= MAX_DOWNLOAD_ATTEMPTS {
                         return Err(err);
                     }
+                    // Only a transport failure leaves a prefix worth keeping; size or
+                    // checksum mismatches start over from an empty sink.
+                    let resume =
+                        state.resumable && state.received > 0 && matches!(err, AppError::Http(_));
                     last_err = Some(err);
                     sleep(StdDuration::from_millis(
                         DOWNLOAD_RETRY_DELAY_MS * attempt as u64,
                     ))
                     .await;
-                    progress(0, expected_size);
+                    if resume {
+                        progress(state.received, state.total);
+                    } else {
+                        sink = make_sink()?;
+                        state = DownloadState::default();
+                        progress(0, expected_size);
+                    }
                 }
             }
         }
@@ -612,16 +1074,20 @@ impl GoogleServices {
         Err(last_err.expect("download attempts always produce an error on failure"))
     }
 
-    async fn download_once<F>(
+    async fn download_once<F, W>(
         &self,
         file_id: &str,
         mime_type: Option<&str>,
         expected_size: Option<u64>,
         expected_md5: Option<&str>,
         progress: &mut F,
-    ) -> AppResult<DownloadedFile>
+        cancel: Option<&AtomicBool>,
+        sink: &mut W,
+        state: &mut DownloadState,
+    ) -> AppResult<DownloadStats>
     where
         F: FnMut(u64, Option<u64>) + Send,
+        W: AsyncWrite + Unpin + Send,
     {
         let token = self.ensure_token().await?;
         let mut url = self.drive_url()?;
@@ -645,32 +1111,54 @@ impl GoogleServices {
             }
         }
 
-        let response = self
-            .http
-            .get(url)
-            .bearer_auth(token.access_token)
-            .send()
-            .await?;
+        let resuming = state.received > 0;
+        let mut request = self.http.get(url).bearer_auth(token.access_token);
+        if resuming {
+            request = request.header(RANGE, format!("bytes={}-", state.received));
+        }
+        let response = request.send().await?;
 
         if let Some(err) = drive_auth_error(response.status()) {
             return Err(err);
         }
         let response = response.error_for_status()?;
+        if resuming && response.status() != StatusCode::PARTIAL_CONTENT {
+            return Err(AppError::Parse(
+                "download resume mismatch: server ignored the range request".into(),
+            ));
+        }
+        if !resuming {
+            state.resumable = accepts_byte_ranges(&response);
+        }
 
-        let declared_total = response.content_length();
-        let target_total = declared_total.or(expected_size);
-        progress(0, target_total);
+        let declared_total = response
+            .content_length()
+            .map(|remaining| state.received + remaining);
+        let mut target_total = declared_total.or(state.total);
+        if target_total.is_none() && !is_map {
+            target_total = self.probe_download_size(file_id).await;
+        }
+        let target_total = target_total.or(expected_size);
+        if let Some(size) = target_total {
+            self.check_import_size(size)?;
+        }
+        state.total = target_total;
+        progress(state.received, target_total);
 
         let mut stream = response.bytes_stream();
-        let mut downloaded = 0_u64;
-        let mut buffer = Vec::new();
-
         while let Some(chunk) = stream.next().await {
             let chunk = chunk?;
-            downloaded += chunk.len() as u64;
-            buffer.extend_from_slice(&chunk);
-            progress(downloaded, target_total);
+            self.check_import_size(state.received + chunk.len() as u64)?;
+            sink.write_all(&chunk).await?;
+            state.digest.consume(&chunk);
+            state.received += chunk.len() as u64;
+            progress(state.received, target_total);
+            if cancel.is_some_and(|flag| flag.load(Ordering::SeqCst)) {
+                return Err(AppError::Cancelled("download cancelled".into()));
+            }
         }
+        sink.flush().await?;
+        let downloaded = state.received;
 
         if let Some(expected) = target_total {
             if downloaded != expected {
@@ -688,7 +1176,7 @@ impl GoogleServices {
             }
         }
 
-        let checksum = format!("{:x}", md5::compute(&buffer));
+        let checksum = format!("{:x}", state.digest.clone().compute());
         if let Some(expected) = expected_md5 {
             let trimmed = expected.trim();
             if !trimmed.is_empty() && checksum.to_lowercase() != trimmed.to_lowercase() {
@@ -698,14 +1186,35 @@ impl GoogleServices {
             }
         }
 
-        Ok(DownloadedFile {
-            bytes: buffer,
+        Ok(DownloadStats {
             checksum_md5: checksum,
             received_bytes: downloaded,
             expected_bytes: target_total,
         })
     }
 
+    /// Looks up the authoritative size when the download response does not declare one, so
+    /// progress can still report a total. Failures only cost the percentage.
+    async fn probe_download_size(&self, file_id: &str) -> Option<u64> {
+        match self.get_file_metadata(file_id).await {
+            Ok(metadata) => metadata.size,
+            Err(err) => {
+                warn!(?err, "failed to look up Drive download size");
+                None
+            }
+        }
+    }
+
+    fn check_import_size(&self, size: u64) -> AppResult<()> {
+        let limit = self.config.max_import_bytes;
+        if size > limit {
+            return Err(AppError::Config(format!(
+                "file is too large to import ({size} bytes; the limit is {limit} bytes)"
+            )));
+        }
+        Ok(())
+    }
+
     async fn exchange_code_for_token(
         &self,
         code: &str,
@@ -740,15 +1249,17 @@ impl GoogleServices {
         &self,
         success: TokenSuccessResponse,
         fallback_refresh: Option<String>,
+        account: Option<String>,
     ) -> AppResult<StoredGoogleToken> {
         let refresh_token = success.refresh_token.or(fallback_refresh);
-        let token = StoredGoogleToken::new(
+        let mut token = StoredGoogleToken::new(
             success.access_token,
             refresh_token,
             success.expires_in,
             success.scope,
             success.token_type,
         );
+        token.account = account;
         self.persist_refresh_state(&token, None)?;
         Ok(token)
     }
@@ -777,8 +1288,10 @@ impl GoogleServices {
             .map(|s| s.to_string())
             .or_else(|| self.refresh_state.last_failure.lock().clone());
         let payload = serde_json::to_string(&persisted)?;
-        self.vault
-            .write_secret(TOKEN_ALIAS, &SecretString::new(payload.into()))?;
+        self.vault.write_secret(
+            &token_alias(token.account.as_deref()),
+            &SecretString::new(payload.into()),
+        )?;
         Ok(())
     }
 
@@ -794,14 +1307,23 @@ impl GoogleServices {
     }
 
     fn load_token(&self) -> AppResult<Option<StoredGoogleToken>> {
-        let secret = self.vault.read_secret(TOKEN_ALIAS)?;
+        let alias = token_alias(self.active_account()?.as_deref());
+        let secret = match self.vault.read_secret(&alias)? {
+            Some(secret) => Some(secret),
+            // A sign-in that has not resolved its profile yet is still pending.
+            None => self.vault.read_secret(TOKEN_ALIAS)?,
+        };
         secret
             .map(|value| serde_json::from_str::<StoredGoogleToken>(value.expose_secret()))
             .transpose()
             .map_err(AppError::from)
     }
 
-    async fn refresh_token(&self, refresh_token: &str) -> AppResult<StoredGoogleToken> {
+    async fn refresh_token(
+        &self,
+        refresh_token: &str,
+        account: Option<String>,
+    ) -> AppResult<StoredGoogleToken> {
         let response = self
             .http
             .post(&self.config.token_endpoint)
@@ -816,7 +1338,7 @@ impl GoogleServices {
 
         if response.status().is_success() {
             let success: TokenSuccessResponse = response.json().await?;
-            self.store_token(success, Some(refresh_token.to_string()))
+            self.store_token(success, Some(refresh_token.to_string()), account)
         } else {
             Err(AppError::Config(format!(
                 "failed to refresh google token ({})",
@@ -825,6 +1347,24 @@ impl GoogleServices {
         }
     }
 
+    /// Returns the profile cached alongside the token, fetching it again only
+    /// when forced or after the token has been replaced by a refresh.
+    async fn identity_for(
+        &self,
+        token: &StoredGoogleToken,
+        force: bool,
+    ) -> AppResult<GoogleIdentity> {
+        if !force {
+            if let Some(identity) = token.identity.clone() {
+                return Ok(GoogleIdentity {
+                    expires_at: token.expires_at,
+                    ..identity
+                });
+            }
+        }
+        self.fetch_identity(token).await
+    }
+
     async fn fetch_identity(&self, token: &StoredGoogleToken) -> AppResult<GoogleIdentity> {
         let response = self
             .http
@@ -838,12 +1378,28 @@ impl GoogleServices {
             .email
             .ok_or_else(|| AppError::Config("Google profile missing email".into()))?;
 
-        Ok(GoogleIdentity {
+        let identity = GoogleIdentity {
             email,
             name: profile.name,
             picture: profile.picture,
             expires_at: token.expires_at,
-        })
+        };
+        // Only sign-in (no account yet) or a changed profile is written back; routine lookups
+        // must not touch the keychain.
+        let unchanged = token
+            .identity
+            .as_ref()
+            .is_some_and(|cached| cached.same_profile(&identity));
+        if token.account.is_none() || !unchanged {
+            let mut cached = token.clone();
+            cached.identity = Some(identity.clone());
+            if cached.account.is_none() {
+                self.register_account(&cached, &identity.email)?;
+            } else if let Err(err) = self.persist_refresh_state(&cached, None) {
+                warn!(?err, "failed to cache google identity");
+            }
+        }
+        Ok(identity)
     }
 
     fn drive_url(&self) -> AppResult<Url> {
@@ -853,26 +1409,38 @@ impl GoogleServices {
 
     pub async fn keepalive(&self) -> AppResult<GoogleIdentity> {
         let token = self.refresh_if_due().await?;
-        if let Some(reason) = self.refresh_state.last_failure.lock().clone() {
-            let _ = self.telemetry.record(
-                "refresh_recovered",
-                serde_json::json!({
-                    "reason": sanitize_error_copy(&reason),
-                }),
-            );
-            *self.refresh_state.last_failure.lock() = None;
-            let _ = self.persist_refresh_state(&token, None);
-        }
-        self.fetch_identity(&token).await
+        self.identity_for(&token, false).await
+    }
+
+    /// Any usable token means the previous refresh failure no longer applies; the cleared
+    /// state is persisted so it does not come back on restart.
+    fn clear_refresh_failure(&self, token: &StoredGoogleToken) {
+        let Some(reason) = self.refresh_state.last_failure.lock().take() else {
+            return;
+        };
+        let _ = self.telemetry.record(
+            "refresh_recovered",
+            serde_json::json!({
+                "reason": sanitize_error_copy(&reason),
+            }),
+        );
+        let _ = self.persist_refresh_state(token, None);
+        self.notify_refresh(RefreshEvent::Recovered);
     }
 
     pub fn last_refresh_failure(&self) -> Option<String> {
         self.refresh_state.last_failure.lock().clone()
     }
 
-    async fn run_refresh_loop(&self) {
+    async fn run_refresh_loop(&self, epoch: u64) {
         loop {
-            sleep(StdDuration::from_secs(60)).await;
+            tokio::select! {
+                _ = sleep(StdDuration::from_secs(60)) => {}
+                _ = self.session.shutdown.notified() => {}
+            }
+            if self.session.epoch.load(Ordering::SeqCst) != epoch {
+                return;
+            }
             if let Err(err) = self.refresh_if_due().await {
                 warn!(?err, "background token refresh failed");
             }
@@ -880,6 +1448,16 @@ impl GoogleServices {
     }
 }
 
+fn account_alias(email: &str) -> String {
+    format!("{TOKEN_ALIAS}:{email}")
+}
+
+fn token_alias(account: Option<&str>) -> String {
+    account
+        .map(account_alias)
+        .unwrap_or_else(|| TOKEN_ALIAS.to_string())
+}
+
 fn random_token(len: usize) -> String {
     thread_rng()
         .sample_iter(&Alphanumeric)
@@ -910,55 +1488,104 @@ fn build_code_challenge(verifier: &str) -> AppResult<String> {
     Ok(URL_SAFE_NO_PAD.encode(digest))
 }
 
+/// Serves the loopback listener until the OAuth redirect arrives. Browsers also probe the
+/// origin (`/favicon.ico`, CORS preflights), so requests for other paths are answered and
+/// skipped instead of ending the flow.
 async fn handle_loopback_callback(listener: TcpListener) -> Result<AuthCallback, AppError> {
-    let (mut socket, _) = listener.accept().await?;
-    let mut buffer = [0u8; 4096];
-    let read = socket.read(&mut buffer).await?;
-    let request = String::from_utf8_lossy(&buffer[..read]);
-    let path = request
-        .lines()
-        .next()
-        .and_then(|line| line.split_whitespace().nth(1))
-        .ok_or_else(|| AppError::Config("invalid redirect request".into()))?;
-
-    let url = Url::parse(&format!("http://{LOOPBACK_HOST}{path}"))
-        .map_err(|err| AppError::Config(format!("failed to parse redirect: {err}")))?;
-    let code = url
-        .query_pairs()
-        .find(|(k, _)| k == "code")
-        .map(|(_, v)| v.to_string());
-    let error = url
-        .query_pairs()
-        .find(|(k, _)| k == "error")
-        .map(|(_, v)| v.to_string());
-    let state = url
-        .query_pairs()
-        .find(|(k, _)| k == "state")
-        .map(|(_, v)| v.to_string());
-
-    let (status, body, result) = match (code, state, error) {
-        (Some(code), Some(state), None) => {
-            let body = success_body("Google sign-in approved. You can close this window.");
-            ("200 OK", body, Ok(AuthCallback { code, state }))
+    loop {
+        let (mut socket, _) = listener.accept().await?;
+        let request = match read_request_line(&mut socket).await {
+            Ok(Some(line)) => line,
+            Ok(None) => {
+                write_loopback_response(&mut socket, "414 URI Too Long", "").await;
+                continue;
+            }
+            Err(err) => {
+                warn!(?err, "failed to read loopback request");
+                continue;
+            }
+        };
+        let mut request_line = request.split_whitespace();
+        let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+            write_loopback_response(&mut socket, "400 Bad Request", "").await;
+            continue;
+        };
+
+        let Ok(url) = Url::parse(&format!("http://{LOOPBACK_HOST}{path}")) else {
+            write_loopback_response(&mut socket, "400 Bad Request", "").await;
+            continue;
+        };
+        if method.eq_ignore_ascii_case("OPTIONS") {
+            write_loopback_response(&mut socket, "204 No Content", "").await;
+            continue;
         }
-        (_, _, Some(err)) => {
-            let body = error_body("Google sign-in was denied. You may close this window.");
-            (
-                "400 Bad Request",
-                body,
-                Err(AppError::Config(format!("google sign-in failed: {err}"))),
-            )
+        if url.path() != LOOPBACK_PATH {
+            write_loopback_response(&mut socket, "404 Not Found", "").await;
+            continue;
         }
-        _ => {
-            let body = error_body("Missing authorization code. Please retry sign-in.");
-            (
-                "400 Bad Request",
-                body,
-                Err(AppError::Config("google sign-in missing code".into())),
-            )
+
+        let code = url
+            .query_pairs()
+            .find(|(k, _)| k == "code")
+            .map(|(_, v)| v.to_string());
+        let error = url
+            .query_pairs()
+            .find(|(k, _)| k == "error")
+            .map(|(_, v)| v.to_string());
+        let state = url
+            .query_pairs()
+            .find(|(k, _)| k == "state")
+            .map(|(_, v)| v.to_string());
+
+        let (status, body, result) = match (code, state, error) {
+            (Some(code), Some(state), None) => {
+                let body = success_body("Google sign-in approved. You can close this window.");
+                ("200 OK", body, Ok(AuthCallback { code, state }))
+            }
+            (_, _, Some(err)) => {
+                let body = error_body("Google sign-in was denied. You may close this window.");
+                (
+                    "400 Bad Request",
+                    body,
+                    Err(AppError::Config(format!("google sign-in failed: {err}"))),
+                )
+            }
+            _ => {
+                let body = error_body("Missing authorization code. Please retry sign-in.");
+                (
+                    "400 Bad Request",
+                    body,
+                    Err(AppError::Config("google sign-in missing code".into())),
+                )
+            }
+        };
+
+        write_loopback_response(&mut socket, status, &body).await;
+        return result;
+    }
+}
+
+/// Reads until the request line is complete, which may take several packets when the
+/// authorization code is long. `None` means the line exceeded the size limit.
+async fn read_request_line(socket: &mut TcpStream) -> std::io::Result<Option<String>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(end) = buffer.windows(2).position(|pair| pair == b"\r\n") {
+            return Ok(Some(String::from_utf8_lossy(&buffer[..end]).into_owned()));
+        }
+        if buffer.len() > MAX_LOOPBACK_REQUEST_LINE_BYTES {
+            return Ok(None);
         }
-    };
+        let read = socket.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(Some(String::from_utf8_lossy(&buffer).into_owned()));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+}
 
+async fn write_loopback_response(socket: &mut TcpStream, status: &str, body: &str) {
     let response = format!(
         "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
         body.len(),
@@ -966,8 +1593,6 @@ async fn handle_loopback_callback(listener: TcpListener) -> Result<AuthCallback,
     );
     let _ = socket.write_all(response.as_bytes()).await;
     let _ = socket.shutdown().await;
-
-    result
 }
 
 fn success_body(message: &str) -> String {
@@ -1038,6 +1663,34 @@ struct UserInfoResponse {
     picture: Option<String>,
 }
 
+/// Expands short Drive scope names to their full URLs and rejects scope sets
+/// that would leave Drive listing unauthorized.
+fn resolve_scopes(configured: &[String]) -> AppResult<String> {
+    let scopes: Vec<String> = configured
+        .iter()
+        .map(|scope| scope.trim())
+        .filter(|scope| !scope.is_empty())
+        .map(|scope| {
+            if scope.contains("://") || OPENID_SCOPES.contains(&scope) {
+                scope.to_string()
+            } else {
+                format!("{GOOGLE_SCOPE_PREFIX}{scope}")
+            }
+        })
+        .collect();
+    let has_drive = scopes.iter().any(|scope| {
+        DRIVE_SCOPES
+            .iter()
+            .any(|drive| scope.strip_prefix(GOOGLE_SCOPE_PREFIX) == Some(*drive))
+    });
+    if !has_drive {
+        return Err(AppError::Config(
+            "GOOGLE_OAUTH_SCOPES must include drive.readonly or drive.file".into(),
+        ));
+    }
+    Ok(scopes.join(" "))
+}
+
 fn compute_next_refresh(expires_at: DateTime<Utc>) -> DateTime<Utc> {
     let target = expires_at - Duration::minutes(5);
     if target > Utc::now() {
@@ -1057,6 +1710,14 @@ fn drive_auth_error(status: StatusCode) -> Option<AppError> {
     }
 }
 
+fn accepts_byte_ranges(response: &Response) -> bool {
+    response
+        .headers()
+        .get(ACCEPT_RANGES)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("bytes"))
+}
+
 fn should_retry_download(err: &AppError) -> bool {
     match err {
         AppError::Http(http_err) => {
@@ -1065,7 +1726,7 @@ fn should_retry_download(err: &AppError) -> bool {
                     || status == StatusCode::TOO_MANY_REQUESTS
                     || status == StatusCode::REQUEST_TIMEOUT
             } else {
-                http_err.is_timeout() || http_err.is_connect()
+                http_err.is_timeout() || http_err.is_connect() || http_err.is_body()
             }
         }
         AppError::Parse(reason) => {
@@ -1,25 +1,61 @@
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
 use base64::engine::general_purpose::STANDARD_NO_PAD;
 use base64::Engine;
+use chrono::{DateTime, Utc};
 use roxmltree::{Document, Node};
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha2::{Digest, Sha256};
+use tracing::warn;
+use zip::ZipArchive;
 
+use crate::comparison::ComparisonPagination;
+use crate::config::{DEFAULT_MAX_IMPORT_BYTES, DEFAULT_PLACE_ID_FIELDS};
 use crate::errors::{AppError, AppResult};
-use crate::google::DriveFileMetadata;
+use crate::google::{DownloadedFile, DriveFileMetadata};
 use crate::telemetry::TelemetryClient;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub const DRIVE_SOURCE: &str = "drive_kml";
+pub const LOCAL_FILE_SOURCE: &str = "local_file";
+pub const PASTED_KML_SOURCE: &str = "pasted_kml";
+const KML_MIME: &str = "application/vnd.google-earth.kml+xml";
+pub const KMZ_MIME: &str = "application/vnd.google-earth.kmz";
+pub const GEOJSON_MIME: &str = "application/geo+json";
+pub const CSV_MIME: &str = "text/csv";
+const KMZ_ROOT_ENTRY: &str = "doc.kml";
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// A project's list position. `A` and `B` drive the pairwise comparison; the remaining
+/// slots only take part in multi-list comparisons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ListSlot {
     A,
     B,
+    C,
+    D,
+    E,
 }
 
 impl ListSlot {
+    pub const ALL: [ListSlot; 5] = [
+        ListSlot::A,
+        ListSlot::B,
+        ListSlot::C,
+        ListSlot::D,
+        ListSlot::E,
+    ];
+
     pub fn as_tag(&self) -> &'static str {
         match self {
             ListSlot::A => "A",
             ListSlot::B => "B",
+            ListSlot::C => "C",
+            ListSlot::D => "D",
+            ListSlot::E => "E",
         }
     }
 
@@ -27,6 +63,9 @@ impl ListSlot {
         match self {
             ListSlot::A => "List A",
             ListSlot::B => "List B",
+            ListSlot::C => "List C",
+            ListSlot::D => "List D",
+            ListSlot::E => "List E",
         }
     }
 
@@ -34,6 +73,9 @@ impl ListSlot {
         match value.trim().to_ascii_uppercase().as_str() {
             "A" => Ok(ListSlot::A),
             "B" => Ok(ListSlot::B),
+            "C" => Ok(ListSlot::C),
+            "D" => Ok(ListSlot::D),
+            "E" => Ok(ListSlot::E),
             _ => Err(AppError::Config(format!("invalid list slot: {value}"))),
         }
     }
@@ -50,6 +92,9 @@ pub struct NormalizedRow {
     pub raw_coordinates: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub layer_path: Option<String>,
+    /// Geometry type the coordinate was averaged from; `None` for points.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derived_from: Option<String>,
 }
 
 impl NormalizedRow {
@@ -74,7 +119,7 @@ impl NormalizedRow {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RawPlacemark {
     pub name: Option<String>,
     pub description: Option<String>,
@@ -103,10 +148,59 @@ impl ParsedRow {
     }
 }
 
+/// What a rejected entry was in its source file, so its position can be labelled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectedSource {
+    #[default]
+    Placemark,
+    Feature,
+    CsvRow,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RejectedPlacemark {
     pub message: String,
     pub raw: RawPlacemark,
+    /// 1-based position of the placemark or feature, or the CSV row number counting the
+    /// header as row 1.
+    #[serde(default)]
+    pub placemark_index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    #[serde(default)]
+    pub source: RejectedSource,
+}
+
+impl RejectedPlacemark {
+    fn new(
+        message: impl Into<String>,
+        raw: RawPlacemark,
+        (placemark_index, line): (usize, Option<u32>),
+    ) -> Self {
+        Self {
+            message: message.into(),
+            raw,
+            placemark_index,
+            line,
+            source: RejectedSource::Placemark,
+        }
+    }
+
+    fn with_source(mut self, source: RejectedSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn location(&self) -> String {
+        let index = self.placemark_index;
+        match (self.source, self.line) {
+            (RejectedSource::CsvRow, _) => format!("Row {index}"),
+            (RejectedSource::Feature, _) => format!("Feature #{index}"),
+            (RejectedSource::Placemark, Some(line)) => format!("Placemark #{index} (line {line})"),
+            (RejectedSource::Placemark, None) => format!("Placemark #{index}"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -119,6 +213,40 @@ impl ParsedKml {
     fn new(rows: Vec<ParsedRow>, rejected: Vec<RejectedPlacemark>) -> Self {
         Self { rows, rejected }
     }
+
+    /// Fails imports that would persist nothing, distinguishing an empty file from one whose
+    /// placemarks were all rejected.
+    pub fn ensure_importable(&self) -> AppResult<()> {
+        if !self.rows.is_empty() {
+            return Ok(());
+        }
+        match self.rejected.first() {
+            None => Err(AppError::Parse("no placemarks found".into())),
+            Some(first) => Err(AppError::Parse(format!(
+                "all {} placemarks were rejected ({}: {})",
+                self.rejected.len(),
+                first.location(),
+                first.message
+            ))),
+        }
+    }
+
+    /// Rejects imports with more than `max_rows` rows before anything is persisted, or keeps
+    /// only the first `max_rows` when `truncate` is set.
+    pub fn enforce_row_limit(&mut self, max_rows: usize, truncate: bool) -> AppResult<()> {
+        let total = self.rows.len();
+        if total <= max_rows {
+            return Ok(());
+        }
+        if !truncate {
+            return Err(AppError::Config(format!(
+                "import has {total} rows, more than the limit of {max_rows}; raise MAX_IMPORT_ROWS or split the file"
+            )));
+        }
+        warn!(total, max_rows, "truncating oversized import");
+        self.rows.truncate(max_rows);
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -128,12 +256,90 @@ pub struct ImportSummary {
     pub row_count: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FreshnessStatus {
+    UpToDate,
+    Stale,
+    NotLinked,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SlotFreshness {
+    pub slot: String,
+    pub status: FreshnessStatus,
+    pub file_id: Option<String>,
+    pub stored_modified_time: Option<String>,
+    pub live_modified_time: Option<String>,
+}
+
+impl SlotFreshness {
+    pub fn not_linked(slot: ListSlot) -> Self {
+        Self {
+            slot: slot.as_tag().to_string(),
+            status: FreshnessStatus::NotLinked,
+            file_id: None,
+            stored_modified_time: None,
+            live_modified_time: None,
+        }
+    }
+
+    /// Compares the metadata recorded at import time with what Drive reports now.
+    pub fn compare(slot: ListSlot, stored: &DriveFileMetadata, live: &DriveFileMetadata) -> Self {
+        let checksum_changed = match (&stored.md5_checksum, &live.md5_checksum) {
+            (Some(before), Some(after)) => !before.eq_ignore_ascii_case(after),
+            _ => false,
+        };
+        let modified_changed = stored.modified_time != live.modified_time;
+        Self {
+            slot: slot.as_tag().to_string(),
+            status: if checksum_changed || modified_changed {
+                FreshnessStatus::Stale
+            } else {
+                FreshnessStatus::UpToDate
+            },
+            file_id: Some(stored.id.clone()),
+            stored_modified_time: stored.modified_time.clone(),
+            live_modified_time: live.modified_time.clone(),
+        }
+    }
+}
+
+/// Column mapping for spreadsheet imports. Header names match case-insensitively.
+/// When `coordinates_column` is set it holds a combined `lat,lng` pair (or
+/// `lng,lat` with `longitude_first`) instead of separate columns.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CsvImportOptions {
+    pub name_column: String,
+    pub latitude_column: String,
+    pub longitude_column: String,
+    pub coordinates_column: Option<String>,
+    pub description_column: Option<String>,
+    pub place_id_column: Option<String>,
+    pub longitude_first: bool,
+}
+
+impl Default for CsvImportOptions {
+    fn default() -> Self {
+        Self {
+            name_column: "name".into(),
+            latitude_column: "latitude".into(),
+            longitude_column: "longitude".into(),
+            coordinates_column: None,
+            description_column: Some("description".into()),
+            place_id_column: Some("place_id".into()),
+            longitude_first: false,
+        }
+    }
+}
+
 fn ensure_list_record(connection: &Connection, project_id: i64, slot: ListSlot) -> AppResult<i64> {
     connection.execute(
         "INSERT INTO lists (project_id, slot, name, source)
-        SELECT ?1, ?2, ?3, 'drive_kml'
+        SELECT ?1, ?2, ?3, ?4
         WHERE NOT EXISTS (SELECT 1 FROM lists WHERE project_id = ?1 AND slot = ?2)",
-        (project_id, slot.as_tag(), slot.display_name()),
+        (project_id, slot.as_tag(), slot.display_name(), DRIVE_SOURCE),
     )?;
 
     connection
@@ -162,8 +368,9 @@ pub fn persist_drive_selection(
                     drive_file_size = ?4,
                     drive_modified_time = ?5,
                     drive_file_checksum = ?6,
-                    name = ?7
-                WHERE id = ?8",
+                    name = ?7,
+                    source = ?8
+                WHERE id = ?9",
                 (
                     file.id.as_str(),
                     file.name.as_str(),
@@ -172,6 +379,7 @@ pub fn persist_drive_selection(
                     file.modified_time.clone(),
                     file.md5_checksum.clone(),
                     slot.display_name(),
+                    file.source.as_deref().unwrap_or(DRIVE_SOURCE),
                     list_id,
                 ),
             )?;
@@ -193,7 +401,150 @@ pub fn persist_drive_selection(
     Ok(list_id)
 }
 
+/// Sets the user-facing label of a slot's list. A blank name clears it so the slot falls back
+/// to its default label. The label is kept across re-imports, clears and slot swaps.
+pub fn rename_list(
+    connection: &Connection,
+    project_id: i64,
+    slot: ListSlot,
+    display_name: Option<&str>,
+) -> AppResult<i64> {
+    let list_id = ensure_list_record(connection, project_id, slot)?;
+    let display_name = display_name.map(str::trim).filter(|name| !name.is_empty());
+    connection.execute(
+        "UPDATE lists SET display_name = ?1 WHERE id = ?2",
+        params![display_name, list_id],
+    )?;
+    Ok(list_id)
+}
+
+/// The label shown for a slot: its display name when set, otherwise the slot default.
+pub fn list_label(connection: &Connection, project_id: i64, slot: ListSlot) -> AppResult<String> {
+    let display_name: Option<String> = connection
+        .query_row(
+            "SELECT display_name FROM lists WHERE project_id = ?1 AND slot = ?2 LIMIT 1",
+            (project_id, slot.as_tag()),
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    Ok(display_name.unwrap_or_else(|| slot.display_name().to_string()))
+}
+
+/// Empties a slot: removes its raw rows, place assignments, and source metadata while keeping
+/// the `lists` row so the slot can be imported into again.
+pub fn clear_list_slot(
+    connection: &mut Connection,
+    project_id: i64,
+    slot: ListSlot,
+) -> AppResult<i64> {
+    let tx = connection.transaction()?;
+    let list_id = ensure_list_record(&tx, project_id, slot)?;
+    tx.execute("DELETE FROM raw_items WHERE list_id = ?1", [list_id])?;
+    tx.execute("DELETE FROM list_places WHERE list_id = ?1", [list_id])?;
+    persist_drive_selection(&tx, project_id, slot, None)?;
+    tx.execute(
+        "UPDATE lists SET name = ?1, source = ?2 WHERE id = ?3",
+        (slot.display_name(), DRIVE_SOURCE, list_id),
+    )?;
+    tx.commit()?;
+    Ok(list_id)
+}
+
+/// Swaps the A and B lists of a project. The unique `(project_id, slot)` index forces a detour
+/// through a placeholder slot value.
+pub fn swap_list_slots(connection: &mut Connection, project_id: i64) -> AppResult<()> {
+    const PLACEHOLDER_SLOT: &str = "swap";
+    let tx = connection.transaction()?;
+    for (from, to) in [
+        (ListSlot::A.as_tag(), PLACEHOLDER_SLOT),
+        (ListSlot::B.as_tag(), ListSlot::A.as_tag()),
+        (PLACEHOLDER_SLOT, ListSlot::B.as_tag()),
+    ] {
+        tx.execute(
+            "UPDATE lists SET slot = ?1 WHERE project_id = ?2 AND slot = ?3",
+            (to, project_id, from),
+        )?;
+    }
+    // Keep default list names in step with the slot they now occupy.
+    tx.execute(
+        "UPDATE lists
+        SET name = CASE slot WHEN 'A' THEN ?2 ELSE ?3 END
+        WHERE project_id = ?1 AND name IN (?2, ?3)",
+        (
+            project_id,
+            ListSlot::A.display_name(),
+            ListSlot::B.display_name(),
+        ),
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn stored_drive_selection(
+    connection: &Connection,
+    project_id: i64,
+    slot: ListSlot,
+) -> AppResult<Option<DriveFileMetadata>> {
+    let selection = connection
+        .query_row(
+            "SELECT drive_file_id, drive_file_name, drive_file_mime, drive_file_size,
+                    drive_modified_time, drive_file_checksum, source
+            FROM lists
+            WHERE project_id = ?1 AND slot = ?2 AND drive_file_id IS NOT NULL
+            LIMIT 1",
+            (project_id, slot.as_tag()),
+            |row| {
+                Ok(DriveFileMetadata {
+                    id: row.get(0)?,
+                    name: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                    mime_type: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                    size: row.get::<_, Option<i64>>(3)?.map(|value| value as u64),
+                    modified_time: row.get(4)?,
+                    md5_checksum: row.get(5)?,
+                    source: row.get(6)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(selection)
+}
+
+/// The slot's stored selection when it points at a Drive file, as opposed to a local or
+/// pasted import that has no remote copy to check against.
+pub fn linked_drive_file(
+    connection: &Connection,
+    project_id: i64,
+    slot: ListSlot,
+) -> AppResult<Option<DriveFileMetadata>> {
+    Ok(
+        stored_drive_selection(connection, project_id, slot)?.filter(|file| {
+            !matches!(
+                file.source.as_deref(),
+                Some(LOCAL_FILE_SOURCE | PASTED_KML_SOURCE)
+            )
+        }),
+    )
+}
+
+fn default_place_id_fields() -> Vec<String> {
+    DEFAULT_PLACE_ID_FIELDS
+        .iter()
+        .map(|field| field.to_string())
+        .collect()
+}
+
 pub fn parse_kml(bytes: &[u8]) -> AppResult<ParsedKml> {
+    parse_kml_with_fields(bytes, &default_place_id_fields())
+}
+
+/// Parses KML, reading place IDs from any `<Data>`/`<SimpleData>` whose name
+/// matches one of `place_id_fields` (case-insensitively).
+pub fn parse_kml_with_fields(bytes: &[u8], place_id_fields: &[String]) -> AppResult<ParsedKml> {
+    if bytes.starts_with(ZIP_MAGIC) {
+        let kml = extract_kmz(bytes, DEFAULT_MAX_IMPORT_BYTES)?;
+        return parse_kml_with_fields(&kml, place_id_fields);
+    }
     let xml = std::str::from_utf8(bytes)
         .map_err(|err| AppError::Parse(format!("invalid UTF-8 in KML: {err}")))?;
     let document =
@@ -201,52 +552,417 @@ pub fn parse_kml(bytes: &[u8]) -> AppResult<ParsedKml> {
 
     let mut rows = Vec::new();
     let mut rejected = Vec::new();
-    for placemark in document
+    for (index, placemark) in document
         .descendants()
         .filter(|node| node.tag_name().name() == "Placemark")
+        .enumerate()
     {
-        let raw = extract_raw_placemark(placemark);
-        let coordinates = match raw.coordinates.clone() {
-            Some(value) => value,
-            None => {
-                rejected.push(RejectedPlacemark {
-                    message: "Placemark missing coordinates".into(),
-                    raw,
-                });
-                continue;
+        let origin = (
+            index + 1,
+            Some(document.text_pos_at(placemark.range().start).row),
+        );
+        let raw = extract_raw_placemark(placemark, place_id_fields);
+        let geometries = extract_geometries(placemark);
+        if geometries.is_empty() {
+            rejected.push(RejectedPlacemark::new(
+                "Placemark missing coordinates",
+                raw,
+                origin,
+            ));
+            continue;
+        }
+
+        // MultiGeometry and multi-point strings fan out into one row per geometry.
+        let fan_out = geometries.len() > 1;
+        let base_title = normalize_label(raw.name.as_deref())
+            .unwrap_or_else(|| "Untitled placemark".to_string());
+        for (index, geometry) in geometries.into_iter().enumerate() {
+            let mut raw_entry = raw.clone();
+            if fan_out {
+                raw_entry.coordinates = Some(geometry.raw_coordinates.clone());
             }
+            match geometry.position {
+                Some((longitude, latitude, _)) if out_of_bounds(longitude, latitude) => {
+                    rejected.push(RejectedPlacemark::new(
+                        bounds_message(longitude, latitude),
+                        raw_entry,
+                        origin,
+                    ));
+                }
+                Some((longitude, latitude, altitude)) => {
+                    let title = if fan_out {
+                        format!("{base_title} #{}", index + 1)
+                    } else {
+                        base_title.clone()
+                    };
+                    let mut row = build_row(
+                        raw_entry,
+                        title,
+                        geometry.raw_coordinates,
+                        (longitude, latitude, altitude),
+                    );
+                    row.normalized.derived_from = geometry.derived_from.map(str::to_string);
+                    rows.push(row);
+                }
+                None => {
+                    rejected.push(RejectedPlacemark::new(
+                        "Placemark missing valid coordinates",
+                        raw_entry,
+                        origin,
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(ParsedKml::new(rows, rejected))
+}
+
+/// Parses downloaded bytes with the parser that matches the file's mime type. KMZ archives
+/// may not expand beyond `max_bytes`.
+pub fn parse_import(
+    bytes: &[u8],
+    mime_type: &str,
+    csv_options: &CsvImportOptions,
+    place_id_fields: &[String],
+    max_bytes: u64,
+) -> AppResult<ParsedKml> {
+    if bytes.starts_with(ZIP_MAGIC) {
+        return parse_kml_with_fields(&extract_kmz(bytes, max_bytes)?, place_id_fields);
+    }
+    match mime_type {
+        GEOJSON_MIME => parse_geojson(bytes, place_id_fields),
+        CSV_MIME => parse_csv(bytes, csv_options),
+        _ => parse_kml_with_fields(bytes, place_id_fields),
+    }
+}
+
+pub fn format_label(mime_type: &str) -> &'static str {
+    match mime_type {
+        KMZ_MIME => "KMZ",
+        GEOJSON_MIME => "GeoJSON",
+        CSV_MIME => "CSV",
+        _ => "KML",
+    }
+}
+
+/// Parses a GeoJSON FeatureCollection, reading place IDs from the first property whose key
+/// matches one of `place_id_fields` (case-insensitively).
+pub fn parse_geojson(bytes: &[u8], place_id_fields: &[String]) -> AppResult<ParsedKml> {
+    let document: Value = serde_json::from_slice(bytes)
+        .map_err(|err| AppError::Parse(format!("invalid GeoJSON: {err}")))?;
+    if document.get("type").and_then(Value::as_str) != Some("FeatureCollection") {
+        return Err(AppError::Parse(
+            "GeoJSON must be a FeatureCollection".into(),
+        ));
+    }
+    let features = document
+        .get("features")
+        .and_then(Value::as_array)
+        .ok_or_else(|| AppError::Parse("GeoJSON FeatureCollection missing features".into()))?;
+
+    let mut rows = Vec::new();
+    let mut rejected = Vec::new();
+    for (index, feature) in features.iter().enumerate() {
+        let origin = (index + 1, None);
+        let properties = feature.get("properties");
+        let property = |key: &str| {
+            properties
+                .and_then(|props| props.get(key))
+                .and_then(Value::as_str)
+                .map(collapse_whitespace)
+                .filter(|value| !value.is_empty())
+        };
+        let geometry = feature.get("geometry");
+        let coordinates = geometry
+            .and_then(|geom| geom.get("coordinates"))
+            .and_then(Value::as_array);
+        // Non-numeric entries stay in place so they cannot shift later values into lng/lat.
+        let position =
+            coordinates.map(|values| values.iter().map(Value::as_f64).collect::<Vec<_>>());
+        let mut raw = RawPlacemark {
+            name: property("name"),
+            description: property("description"),
+            coordinates: coordinates.map(|values| {
+                values
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }),
+            place_id: place_id_fields.iter().find_map(|field| {
+                properties
+                    .and_then(Value::as_object)
+                    .and_then(|props| {
+                        props
+                            .iter()
+                            .find(|(key, _)| field.eq_ignore_ascii_case(key))
+                    })
+                    .and_then(|(_, value)| value.as_str())
+                    .map(collapse_whitespace)
+                    .filter(|value| !value.is_empty())
+            }),
+            altitude: None,
+            layer_path: None,
         };
 
-        let mut raw_entry = raw;
-        match parse_coordinates(&coordinates) {
-            Some((longitude, latitude, altitude)) => {
-                let normalized = NormalizedRow {
-                    title: normalize_label(raw_entry.name.as_deref())
-                        .unwrap_or_else(|| "Untitled placemark".to_string()),
-                    description: normalize_text(raw_entry.description.as_deref()),
-                    longitude: normalize_coordinate(longitude),
-                    latitude: normalize_coordinate(latitude),
-                    altitude,
-                    place_id: raw_entry.place_id.clone(),
-                    raw_coordinates: coordinates,
-                    layer_path: raw_entry.layer_path.clone(),
-                };
-                raw_entry.altitude = altitude;
-                rows.push(ParsedRow::new(normalized, raw_entry));
+        let geometry_type = geometry
+            .and_then(|geom| geom.get("type"))
+            .and_then(Value::as_str)
+            .unwrap_or("missing");
+        if geometry_type != "Point" {
+            raw.coordinates = None;
+            rejected.push(
+                RejectedPlacemark::new(
+                    format!("Unsupported GeoJSON geometry: {geometry_type}"),
+                    raw,
+                    origin,
+                )
+                .with_source(RejectedSource::Feature),
+            );
+            continue;
+        }
+        match position.as_deref() {
+            Some([Some(longitude), Some(latitude), ..]) if out_of_bounds(*longitude, *latitude) => {
+                rejected.push(
+                    RejectedPlacemark::new(bounds_message(*longitude, *latitude), raw, origin)
+                        .with_source(RejectedSource::Feature),
+                );
             }
-            None => {
-                rejected.push(RejectedPlacemark {
-                    message: "Placemark missing valid coordinates".into(),
-                    raw: raw_entry,
-                });
+            Some([Some(longitude), Some(latitude), rest @ ..]) => {
+                let title = normalize_label(raw.name.as_deref())
+                    .unwrap_or_else(|| "Untitled placemark".to_string());
+                let coordinates = raw.coordinates.clone().unwrap_or_default();
+                let position = (*longitude, *latitude, rest.first().copied().flatten());
+                rows.push(build_row(raw, title, coordinates, position));
+            }
+            _ => rejected.push(
+                RejectedPlacemark::new("Feature missing valid coordinates", raw, origin)
+                    .with_source(RejectedSource::Feature),
+            ),
+        }
+    }
+
+    Ok(ParsedKml::new(rows, rejected))
+}
+
+pub fn parse_csv(bytes: &[u8], options: &CsvImportOptions) -> AppResult<ParsedKml> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name.trim()))
+    };
+    let required = |name: &str| {
+        column(name).ok_or_else(|| AppError::Parse(format!("CSV is missing a \"{name}\" column")))
+    };
+    let name_idx = required(&options.name_column)?;
+    let coordinate_columns = match &options.coordinates_column {
+        Some(combined) => (required(combined)?, None),
+        None => (
+            required(&options.latitude_column)?,
+            Some(required(&options.longitude_column)?),
+        ),
+    };
+    let description_idx = options.description_column.as_deref().and_then(column);
+    let place_id_idx = options.place_id_column.as_deref().and_then(column);
+
+    let mut rows = Vec::new();
+    let mut rejected = Vec::new();
+    for (line, record) in reader.records().enumerate() {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                let origin = (line + 2, err.position().map(|pos| pos.line() as u32));
+                rejected.push(
+                    RejectedPlacemark::new(
+                        format!("Malformed CSV record: {err}"),
+                        RawPlacemark::default(),
+                        origin,
+                    )
+                    .with_source(RejectedSource::CsvRow),
+                );
                 continue;
             }
+        };
+        // Rows are numbered as a spreadsheet shows them, with the header as row 1.
+        let origin = (line + 2, record.position().map(|pos| pos.line() as u32));
+        let field = |idx: Option<usize>| {
+            idx.and_then(|idx| record.get(idx))
+                .map(collapse_whitespace)
+                .filter(|value| !value.is_empty())
+        };
+        let (latitude, longitude) = match coordinate_columns {
+            (combined, None) => {
+                let value = field(Some(combined)).unwrap_or_default();
+                let mut parts = value.split(',').map(|part| part.trim().to_string());
+                let first = parts.next().unwrap_or_default();
+                let second = parts.next().unwrap_or_default();
+                if options.longitude_first {
+                    (second, first)
+                } else {
+                    (first, second)
+                }
+            }
+            (lat_idx, Some(lng_idx)) => (
+                field(Some(lat_idx)).unwrap_or_default(),
+                field(Some(lng_idx)).unwrap_or_default(),
+            ),
+        };
+        let raw = RawPlacemark {
+            name: field(Some(name_idx)),
+            description: field(description_idx),
+            coordinates: Some(format!("{longitude},{latitude}")),
+            place_id: field(place_id_idx),
+            altitude: None,
+            layer_path: None,
+        };
+        match (longitude.parse::<f64>(), latitude.parse::<f64>()) {
+            (Ok(lng), Ok(lat)) if out_of_bounds(lng, lat) => {
+                rejected.push(
+                    RejectedPlacemark::new(bounds_message(lng, lat), raw, origin)
+                        .with_source(RejectedSource::CsvRow),
+                );
+            }
+            (Ok(lng), Ok(lat)) => {
+                let title = normalize_label(raw.name.as_deref())
+                    .unwrap_or_else(|| "Untitled placemark".to_string());
+                let coordinates = format!("{lng},{lat}");
+                rows.push(build_row(raw, title, coordinates, (lng, lat, None)));
+            }
+            _ => rejected.push(
+                RejectedPlacemark::new("Invalid CSV coordinates", raw, origin)
+                    .with_source(RejectedSource::CsvRow),
+            ),
         }
     }
 
     Ok(ParsedKml::new(rows, rejected))
 }
 
+fn build_row(
+    mut raw: RawPlacemark,
+    title: String,
+    raw_coordinates: String,
+    (longitude, latitude, altitude): (f64, f64, Option<f64>),
+) -> ParsedRow {
+    let normalized = NormalizedRow {
+        title,
+        description: normalize_text(raw.description.as_deref()),
+        longitude: normalize_coordinate(longitude),
+        latitude: normalize_coordinate(latitude),
+        altitude,
+        place_id: raw.place_id.clone(),
+        raw_coordinates,
+        layer_path: raw.layer_path.clone(),
+        derived_from: None,
+    };
+    raw.altitude = altitude;
+    ParsedRow::new(normalized, raw)
+}
+
+/// Reads a KML, KMZ, GeoJSON, or CSV file from disk and describes it with synthetic Drive
+/// metadata so the rest of the import pipeline can treat it like a download.
+pub fn read_local_file(path: &Path) -> AppResult<(DriveFileMetadata, DownloadedFile)> {
+    let raw = fs::read(path)?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string());
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let mime_type = if raw.starts_with(ZIP_MAGIC) || extension == "kmz" {
+        KMZ_MIME
+    } else if matches!(extension.as_str(), "geojson" | "json") {
+        GEOJSON_MIME
+    } else if extension == "csv" {
+        CSV_MIME
+    } else {
+        KML_MIME
+    };
+    let checksum = format!("{:x}", md5::compute(&raw));
+    let modified_time = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .map(|time| DateTime::<Utc>::from(time).to_rfc3339());
+    let received_bytes = raw.len() as u64;
+
+    let metadata = DriveFileMetadata {
+        id: path.to_string_lossy().to_string(),
+        name: file_name,
+        mime_type: mime_type.to_string(),
+        modified_time,
+        size: Some(received_bytes),
+        md5_checksum: Some(checksum.clone()),
+        source: Some(LOCAL_FILE_SOURCE.to_string()),
+    };
+    let download = DownloadedFile {
+        bytes: raw,
+        checksum_md5: checksum,
+        received_bytes,
+        expected_bytes: Some(received_bytes),
+    };
+    Ok((metadata, download))
+}
+
+/// Wraps KML text pasted into the app with synthetic metadata, mirroring [`read_local_file`].
+pub fn pasted_kml(name: &str, kml: &str) -> (DriveFileMetadata, DownloadedFile) {
+    let bytes = kml.as_bytes().to_vec();
+    let checksum = format!("{:x}", md5::compute(&bytes));
+    let received_bytes = bytes.len() as u64;
+    let metadata = DriveFileMetadata {
+        id: format!("pasted:{checksum}"),
+        name: name.to_string(),
+        mime_type: KML_MIME.to_string(),
+        modified_time: Some(Utc::now().to_rfc3339()),
+        size: Some(received_bytes),
+        md5_checksum: Some(checksum.clone()),
+        source: Some(PASTED_KML_SOURCE.to_string()),
+    };
+    let download = DownloadedFile {
+        bytes,
+        checksum_md5: checksum,
+        received_bytes,
+        expected_bytes: Some(received_bytes),
+    };
+    (metadata, download)
+}
+
+/// Pulls the KML document out of a KMZ archive, preferring `doc.kml` and
+/// otherwise taking the first `.kml` entry. Bundled images are ignored. The
+/// entry size declared in the ZIP header is not trusted: reading stops once
+/// the KML exceeds `max_bytes`.
+pub fn extract_kmz(bytes: &[u8], max_bytes: u64) -> AppResult<Vec<u8>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|err| AppError::Parse(format!("invalid KMZ archive: {err}")))?;
+    let entry_name = if archive.index_for_name(KMZ_ROOT_ENTRY).is_some() {
+        KMZ_ROOT_ENTRY.to_string()
+    } else {
+        archive
+            .file_names()
+            .find(|name| name.to_ascii_lowercase().ends_with(".kml"))
+            .map(str::to_string)
+            .ok_or_else(|| AppError::Parse("KMZ archive does not contain a KML file".into()))?
+    };
+    let mut entry = archive
+        .by_name(&entry_name)
+        .map_err(|err| AppError::Parse(format!("invalid KMZ archive: {err}")))?;
+    let mut kml = Vec::with_capacity(entry.size().min(max_bytes) as usize);
+    entry.by_ref().take(max_bytes + 1).read_to_end(&mut kml)?;
+    if kml.len() as u64 > max_bytes {
+        return Err(AppError::Config(format!(
+            "KMZ archive is too large to import (its KML expands beyond the limit of {max_bytes} bytes)"
+        )));
+    }
+    Ok(kml)
+}
+
 pub fn persist_rows(
     connection: &mut Connection,
     project_id: i64,
@@ -276,8 +992,8 @@ where
     F: FnMut(usize, usize),
 {
     let tx = connection.transaction()?;
-    let list_name = slot.display_name();
     let list_id = persist_drive_selection(&tx, project_id, slot, Some(drive_file))?;
+    let list_name = list_label(&tx, project_id, slot)?;
     tx.execute(
         "UPDATE lists SET imported_at = DATETIME('now') WHERE id = ?1",
         [list_id],
@@ -302,12 +1018,186 @@ where
     tx.commit()?;
 
     Ok(ImportSummary {
-        list_name: list_name.to_string(),
+        list_name,
         list_id,
         row_count: rows.len(),
     })
 }
 
+/// One past import of a slot, kept after later re-imports overwrite the `lists` metadata.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportHistoryEntry {
+    pub id: i64,
+    pub slot: String,
+    pub source: String,
+    pub file_id: Option<String>,
+    pub file_name: Option<String>,
+    pub row_count: usize,
+    pub rejected_count: usize,
+    pub checksum: Option<String>,
+    pub imported_at: String,
+}
+
+pub fn record_import_history(
+    connection: &Connection,
+    project_id: i64,
+    slot: ListSlot,
+    drive_file: &DriveFileMetadata,
+    row_count: usize,
+    rejected_count: usize,
+    checksum: Option<&str>,
+) -> AppResult<i64> {
+    connection.execute(
+        "INSERT INTO import_history
+            (project_id, slot, source, file_id, file_name, row_count, rejected_count, checksum)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            project_id,
+            slot.as_tag(),
+            drive_file.source.as_deref().unwrap_or(DRIVE_SOURCE),
+            drive_file.id,
+            drive_file.name,
+            row_count as i64,
+            rejected_count as i64,
+            checksum.or(drive_file.md5_checksum.as_deref()),
+        ],
+    )?;
+    Ok(connection.last_insert_rowid())
+}
+
+/// Lists a project's imports newest first, optionally limited to one slot.
+pub fn list_import_history(
+    connection: &Connection,
+    project_id: i64,
+    slot: Option<ListSlot>,
+) -> AppResult<Vec<ImportHistoryEntry>> {
+    let mut stmt = connection.prepare(
+        "SELECT id, slot, source, file_id, file_name, row_count, rejected_count, checksum,
+                imported_at
+        FROM import_history
+        WHERE project_id = ?1 AND (?2 IS NULL OR slot = ?2)
+        ORDER BY imported_at DESC, id DESC",
+    )?;
+    let rows = stmt.query_map(
+        params![project_id, slot.map(|value| value.as_tag())],
+        |row| {
+            Ok(ImportHistoryEntry {
+                id: row.get(0)?,
+                slot: row.get(1)?,
+                source: row.get(2)?,
+                file_id: row.get(3)?,
+                file_name: row.get(4)?,
+                row_count: row.get::<_, i64>(5)? as usize,
+                rejected_count: row.get::<_, i64>(6)? as usize,
+                checksum: row.get(7)?,
+                imported_at: row.get(8)?,
+            })
+        },
+    )?;
+    rows.collect::<Result<Vec<_>, _>>().map_err(AppError::from)
+}
+
+/// A stored `raw_items` row as imported, with the normalization cache entry it resolved to.
+#[derive(Debug, Clone, Serialize)]
+pub struct RawRowEntry {
+    pub id: i64,
+    pub source_row_hash: String,
+    pub row: NormalizedRow,
+    /// The placemark as read from the file; `None` for rows stored before it was kept.
+    pub original: Option<RawPlacemark>,
+    pub resolved: bool,
+    pub place_id: Option<String>,
+    pub confidence: Option<f64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RawRowPage {
+    pub rows: Vec<RawRowEntry>,
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+/// Pages through a slot's stored rows in import order for debugging resolution failures.
+pub fn load_raw_rows(
+    connection: &Connection,
+    project_id: i64,
+    slot: ListSlot,
+    pagination: ComparisonPagination,
+) -> AppResult<RawRowPage> {
+    let list_id: Option<i64> = connection
+        .query_row(
+            "SELECT id FROM lists WHERE project_id = ?1 AND slot = ?2 LIMIT 1",
+            params![project_id, slot.as_tag()],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(list_id) = list_id else {
+        return Ok(RawRowPage {
+            rows: Vec::new(),
+            total: 0,
+            page: 1,
+            page_size: pagination.page_size,
+        });
+    };
+    let total: i64 = connection.query_row(
+        "SELECT COUNT(*) FROM raw_items WHERE list_id = ?1",
+        [list_id],
+        |row| row.get(0),
+    )?;
+    let paging = pagination.with_total(total as usize);
+
+    let mut stmt = connection.prepare(
+        "SELECT ri.id, ri.source_row_hash, ri.raw_json, ri.created_at, nc.place_id, nc.confidence
+        FROM raw_items ri
+        LEFT JOIN normalization_cache nc ON nc.source_row_hash = ri.source_row_hash
+        WHERE ri.list_id = ?1
+        ORDER BY ri.id ASC
+        LIMIT ?2 OFFSET ?3",
+    )?;
+    let stored = stmt
+        .query_map(
+            params![list_id, paging.page_size as i64, paging.offset()],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, Option<f64>>(5)?,
+                ))
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut rows = Vec::with_capacity(stored.len());
+    for (id, hash, payload, created_at, place_id, confidence) in stored {
+        // Older imports stored the bare `NormalizedRow`; newer ones the full `ParsedRow`.
+        let (row, original) = match serde_json::from_str::<ParsedRow>(&payload) {
+            Ok(parsed) => (parsed.normalized, Some(parsed.original)),
+            Err(_) => (serde_json::from_str::<NormalizedRow>(&payload)?, None),
+        };
+        rows.push(RawRowEntry {
+            id,
+            source_row_hash: hash,
+            row,
+            original,
+            resolved: place_id.is_some(),
+            place_id,
+            confidence,
+            created_at,
+        });
+    }
+    Ok(RawRowPage {
+        rows,
+        total: total as usize,
+        page: paging.page,
+        page_size: paging.page_size,
+    })
+}
+
 pub fn enqueue_place_hashes(
     telemetry: &TelemetryClient,
     slot: ListSlot,
@@ -326,12 +1216,12 @@ pub fn enqueue_place_hashes(
     Ok(())
 }
 
-fn extract_raw_placemark(node: Node<'_, '_>) -> RawPlacemark {
+fn extract_raw_placemark(node: Node<'_, '_>, place_id_fields: &[String]) -> RawPlacemark {
     RawPlacemark {
         name: extract_tag_text(node, "name"),
         description: extract_tag_text(node, "description"),
         coordinates: extract_coordinates(node),
-        place_id: extract_place_id(node),
+        place_id: extract_place_id(node, place_id_fields),
         altitude: None,
         layer_path: resolve_layer_path(node),
     }
@@ -349,12 +1239,114 @@ fn extract_coordinates(node: Node<'_, '_>) -> Option<String> {
     node.descendants()
         .find(|child| child.tag_name().name() == "coordinates")
         .and_then(|child| child.text())
-        .map(|value| value.trim().to_string())
+        .map(|value| coordinate_tuples(value).join(" "))
         .filter(|value| !value.is_empty())
 }
 
-fn parse_coordinates(value: &str) -> Option<(f64, f64, Option<f64>)> {
-    let entry = value.split_whitespace().next()?;
+struct PlacemarkGeometry {
+    raw_coordinates: String,
+    position: Option<(f64, f64, Option<f64>)>,
+    derived_from: Option<&'static str>,
+}
+
+impl PlacemarkGeometry {
+    fn point(tuple: String) -> Self {
+        Self {
+            position: parse_coordinates(&tuple),
+            raw_coordinates: tuple,
+            derived_from: None,
+        }
+    }
+
+    /// Averages the vertices of a line or ring into a single representative coordinate.
+    fn centroid(mut tuples: Vec<String>, derived_from: &'static str) -> Self {
+        let raw_coordinates = tuples.join(" ");
+        if tuples.len() > 1 && tuples.first() == tuples.last() {
+            tuples.pop();
+        }
+        let vertices: Option<Vec<_>> = tuples
+            .iter()
+            .map(|tuple| parse_coordinates(tuple))
+            .collect();
+        let position = vertices
+            .filter(|vertices| !vertices.is_empty())
+            .map(|vertices| {
+                let count = vertices.len() as f64;
+                let longitude = vertices.iter().map(|vertex| vertex.0).sum::<f64>() / count;
+                let latitude = vertices.iter().map(|vertex| vertex.1).sum::<f64>() / count;
+                (longitude, latitude, None)
+            });
+        Self {
+            raw_coordinates,
+            position,
+            derived_from: Some(derived_from),
+        }
+    }
+}
+
+fn extract_geometries(placemark: Node<'_, '_>) -> Vec<PlacemarkGeometry> {
+    let mut geometries = Vec::new();
+    for node in placemark.descendants() {
+        match node.tag_name().name() {
+            "Point" => geometries.extend(
+                extract_coordinate_tuples(node)
+                    .into_iter()
+                    .map(PlacemarkGeometry::point),
+            ),
+            "LineString" => {
+                geometries.push(PlacemarkGeometry::centroid(
+                    extract_coordinate_tuples(node),
+                    "LineString",
+                ));
+            }
+            "Polygon" => {
+                let outer = node
+                    .descendants()
+                    .find(|child| child.tag_name().name() == "outerBoundaryIs")
+                    .unwrap_or(node);
+                geometries.push(PlacemarkGeometry::centroid(
+                    extract_coordinate_tuples(outer),
+                    "Polygon",
+                ));
+            }
+            _ => {}
+        }
+    }
+    if geometries.is_empty() {
+        // Coordinates outside a recognised geometry element are treated as points.
+        geometries.extend(
+            extract_coordinate_tuples(placemark)
+                .into_iter()
+                .map(PlacemarkGeometry::point),
+        );
+    }
+    geometries.retain(|geometry| !geometry.raw_coordinates.is_empty());
+    geometries
+}
+
+fn extract_coordinate_tuples(node: Node<'_, '_>) -> Vec<String> {
+    node.descendants()
+        .filter(|child| child.tag_name().name() == "coordinates")
+        .filter_map(|child| child.text())
+        .flat_map(coordinate_tuples)
+        .collect()
+}
+
+/// Splits a `<coordinates>` body into `lng,lat[,alt]` tuples. Pretty-printed exports wrap
+/// tuples across lines and indent around the commas, so whitespace next to a comma is dropped
+/// before splitting on the remaining whitespace.
+fn coordinate_tuples(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(",")
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_coordinates(entry: &str) -> Option<(f64, f64, Option<f64>)> {
     let mut parts = entry.split(',');
     let longitude = parts.next()?.trim().parse().ok()?;
     let latitude = parts.next()?.trim().parse().ok()?;
@@ -362,6 +1354,14 @@ fn parse_coordinates(value: &str) -> Option<(f64, f64, Option<f64>)> {
     Some((longitude, latitude, altitude))
 }
 
+fn out_of_bounds(longitude: f64, latitude: f64) -> bool {
+    !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude)
+}
+
+fn bounds_message(longitude: f64, latitude: f64) -> String {
+    format!("Coordinates out of range (longitude {longitude}, latitude {latitude})")
+}
+
 fn resolve_layer_path(node: Node<'_, '_>) -> Option<String> {
     let mut path = Vec::new();
     for ancestor in node.ancestors() {
@@ -392,15 +1392,80 @@ fn normalize_label(value: Option<&str>) -> Option<String> {
 
 fn normalize_text(value: Option<&str>) -> Option<String> {
     value
-        .map(|v| collapse_whitespace(v))
+        .map(|v| collapse_whitespace(&strip_html(v)))
         .filter(|v| !v.is_empty())
 }
 
-fn normalize_coordinate(value: f64) -> f64 {
-    (value * 1_000_000.0).round() / 1_000_000.0
+/// My Maps descriptions are HTML inside CDATA; keep only the visible text.
+fn strip_html(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        match rest[start..].find('>') {
+            Some(end) => {
+                // Tags separate words visually, so keep a boundary between them.
+                text.push(' ');
+                rest = &rest[start + end + 1..];
+            }
+            None => {
+                text.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    text.push_str(rest);
+    decode_entities(&text)
 }
 
-fn collapse_whitespace(value: &str) -> String {
+fn decode_entities(value: &str) -> String {
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let entity = candidate
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| decode_entity(&candidate[1..end]).map(|ch| (ch, end)));
+        match entity {
+            Some((ch, end)) => {
+                decoded.push(ch);
+                rest = &candidate[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &candidate[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => name.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+fn normalize_coordinate(value: f64) -> f64 {
+    (value * 1_000_000.0).round() / 1_000_000.0
+}
+
+fn collapse_whitespace(value: &str) -> String {
     value
         .split_whitespace()
         .filter(|segment| !segment.is_empty())
@@ -410,15 +1475,15 @@ fn collapse_whitespace(value: &str) -> String {
         .to_string()
 }
 
-fn extract_place_id(node: Node<'_, '_>) -> Option<String> {
+fn extract_place_id(node: Node<'_, '_>, place_id_fields: &[String]) -> Option<String> {
     for candidate in node.descendants() {
         match candidate.tag_name().name() {
             "Data" | "SimpleData" => {
                 if let Some(name) = candidate.attribute("name") {
-                    if matches!(
-                        name,
-                        "PlaceID" | "placeId" | "gx_id" | "google_maps_place_id"
-                    ) {
+                    if place_id_fields
+                        .iter()
+                        .any(|field| field.eq_ignore_ascii_case(name))
+                    {
                         if let Some(value) = candidate
                             .descendants()
                             .find(|child| child.tag_name().name() == "value")
@@ -509,6 +1574,7 @@ mod tests {
             modified_time: None,
             size: None,
             md5_checksum: None,
+            source: None,
         };
         let summary = persist_rows(
             &mut conn,
@@ -530,4 +1596,878 @@ mod tests {
             .unwrap();
         assert_eq!(count, 2);
     }
+
+    #[test]
+    fn records_each_import_in_history() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let bootstrap = bootstrap(dir.path(), "history.db", &vault).unwrap();
+        let mut conn = bootstrap.context.connection;
+        let project_id: i64 = conn
+            .query_row(
+                "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let parsed = parse_kml(SAMPLE_KML.as_bytes()).unwrap();
+        let mut drive_file = DriveFileMetadata {
+            id: "first-file".into(),
+            name: "First.kml".into(),
+            mime_type: KML_MIME.into(),
+            modified_time: None,
+            size: None,
+            md5_checksum: Some("first-md5".into()),
+            source: None,
+        };
+
+        let first = persist_rows(
+            &mut conn,
+            project_id,
+            ListSlot::A,
+            &drive_file,
+            &parsed.rows,
+        )
+        .unwrap();
+        record_import_history(
+            &conn,
+            project_id,
+            ListSlot::A,
+            &drive_file,
+            first.row_count,
+            0,
+            None,
+        )
+        .unwrap();
+
+        drive_file.id = "second-file".into();
+        drive_file.name = "Second.kml".into();
+        let second = persist_rows(
+            &mut conn,
+            project_id,
+            ListSlot::A,
+            &drive_file,
+            &parsed.rows[..1],
+        )
+        .unwrap();
+        record_import_history(
+            &conn,
+            project_id,
+            ListSlot::A,
+            &drive_file,
+            second.row_count,
+            1,
+            Some("second-md5"),
+        )
+        .unwrap();
+
+        let history = list_import_history(&conn, project_id, Some(ListSlot::A)).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].file_id.as_deref(), Some("second-file"));
+        assert_eq!(history[0].row_count, 1);
+        assert_eq!(history[0].rejected_count, 1);
+        assert_eq!(history[0].checksum.as_deref(), Some("second-md5"));
+        assert_eq!(history[1].file_id.as_deref(), Some("first-file"));
+        assert_eq!(history[1].row_count, 2);
+        assert_eq!(history[1].checksum.as_deref(), Some("first-md5"));
+        assert!(list_import_history(&conn, project_id, Some(ListSlot::B))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn inspects_raw_rows_with_resolution_flags() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let bootstrap = bootstrap(dir.path(), "raw-rows.db", &vault).unwrap();
+        let mut conn = bootstrap.context.connection;
+        let project_id: i64 = conn
+            .query_row(
+                "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let parsed = parse_kml(SAMPLE_KML.as_bytes()).unwrap();
+        let drive_file = DriveFileMetadata {
+            id: "raw".into(),
+            name: "Raw.kml".into(),
+            mime_type: KML_MIME.into(),
+            modified_time: None,
+            size: None,
+            md5_checksum: None,
+            source: None,
+        };
+        persist_rows(
+            &mut conn,
+            project_id,
+            ListSlot::A,
+            &drive_file,
+            &parsed.rows,
+        )
+        .unwrap();
+        let hashes: Vec<_> = parsed
+            .rows
+            .iter()
+            .map(|row| row.source_row_hash.clone())
+            .collect();
+        conn.execute(
+            "INSERT INTO normalization_cache (source_row_hash, place_id, confidence)
+            VALUES (?1, 'resolved-place', 0.9)",
+            [&hashes[0]],
+        )
+        .unwrap();
+
+        let page = load_raw_rows(
+            &conn,
+            project_id,
+            ListSlot::A,
+            ComparisonPagination::new(Some(1), Some(10)),
+        )
+        .unwrap();
+        assert_eq!(page.total, 2);
+        let inspected: Vec<_> = page
+            .rows
+            .iter()
+            .map(|row| (row.source_row_hash.as_str(), row.resolved))
+            .collect();
+        assert_eq!(
+            inspected,
+            vec![(hashes[0].as_str(), true), (hashes[1].as_str(), false)]
+        );
+        assert_eq!(page.rows[0].place_id.as_deref(), Some("resolved-place"));
+        assert_eq!(page.rows[0].row.title, parsed.rows[0].normalized.title);
+        assert!(page.rows[1].original.is_some());
+
+        let second = load_raw_rows(
+            &conn,
+            project_id,
+            ListSlot::A,
+            ComparisonPagination::new(Some(2), Some(1)),
+        )
+        .unwrap();
+        assert_eq!(second.rows.len(), 1);
+        assert_eq!(second.rows[0].source_row_hash, hashes[1]);
+        assert!(load_raw_rows(
+            &conn,
+            project_id,
+            ListSlot::B,
+            ComparisonPagination::default()
+        )
+        .unwrap()
+        .rows
+        .is_empty());
+    }
+
+    #[test]
+    fn rejects_imports_over_the_row_limit() {
+        let placemarks: String = (0..12)
+            .map(|index| {
+                format!(
+                    "<Placemark><name>Spot {index}</name>\
+                    <Point><coordinates>{}.0,1.0,0</coordinates></Point></Placemark>",
+                    index + 1
+                )
+            })
+            .collect();
+        let kml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<kml xmlns="http://www.opengis.net/kml/2.2"><Document>{placemarks}</Document></kml>"#
+        );
+
+        let mut parsed = parse_kml(kml.as_bytes()).unwrap();
+        assert_eq!(parsed.rows.len(), 12);
+        match parsed.enforce_row_limit(10, false) {
+            Err(AppError::Config(message)) => {
+                assert!(message.contains("12 rows"), "{message}");
+                assert!(message.contains("limit of 10"), "{message}");
+            }
+            other => panic!("expected row limit error, got {other:?}"),
+        }
+        assert_eq!(parsed.rows.len(), 12);
+
+        parsed.enforce_row_limit(10, true).unwrap();
+        assert_eq!(parsed.rows.len(), 10);
+        assert_eq!(parsed.rows[0].normalized.title, "Spot 0");
+    }
+
+    #[test]
+    fn renamed_list_keeps_display_name_across_reimport() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let bootstrap = bootstrap(dir.path(), "rename.db", &vault).unwrap();
+        let mut conn = bootstrap.context.connection;
+        let project_id: i64 = conn
+            .query_row(
+                "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let parsed = parse_kml(SAMPLE_KML.as_bytes()).unwrap();
+        let drive_file = DriveFileMetadata {
+            id: "picks".into(),
+            name: "Picks.kml".into(),
+            mime_type: KML_MIME.into(),
+            modified_time: None,
+            size: None,
+            md5_checksum: None,
+            source: None,
+        };
+        assert_eq!(
+            list_label(&conn, project_id, ListSlot::A).unwrap(),
+            "List A"
+        );
+
+        rename_list(&conn, project_id, ListSlot::A, Some("  My picks ")).unwrap();
+        let summary = persist_rows(
+            &mut conn,
+            project_id,
+            ListSlot::A,
+            &drive_file,
+            &parsed.rows,
+        )
+        .unwrap();
+        assert_eq!(summary.list_name, "My picks");
+        assert_eq!(
+            list_label(&conn, project_id, ListSlot::A).unwrap(),
+            "My picks"
+        );
+
+        rename_list(&conn, project_id, ListSlot::A, Some(" ")).unwrap();
+        assert_eq!(
+            list_label(&conn, project_id, ListSlot::A).unwrap(),
+            "List A"
+        );
+    }
+
+    fn zip_entries(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in entries {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            std::io::Write::write_all(&mut writer, contents).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn rejects_kmz_entries_that_expand_past_the_import_limit() {
+        let kmz = zip_entries(&[("doc.kml", SAMPLE_KML.as_bytes())]);
+        let limit = SAMPLE_KML.len() as u64 - 1;
+
+        match parse_import(&kmz, KMZ_MIME, &CsvImportOptions::default(), &[], limit) {
+            Err(AppError::Config(message)) => assert!(message.contains("too large"), "{message}"),
+            other => panic!("expected size limit error, got {other:?}"),
+        }
+        let parsed = parse_import(
+            &kmz,
+            KMZ_MIME,
+            &CsvImportOptions::default(),
+            &[],
+            SAMPLE_KML.len() as u64,
+        )
+        .unwrap();
+        assert!(!parsed.rows.is_empty());
+    }
+
+    #[test]
+    fn parses_kmz_archives() {
+        let kmz = zip_entries(&[
+            ("images/icon-1.png", &[0x89, b'P', b'N', b'G']),
+            ("My Places.kml", SAMPLE_KML.as_bytes()),
+        ]);
+        let from_kmz = parse_kml(&kmz).unwrap();
+        let from_kml = parse_kml(SAMPLE_KML.as_bytes()).unwrap();
+        assert_eq!(from_kmz.rows.len(), from_kml.rows.len());
+        for (zipped, plain) in from_kmz.rows.iter().zip(&from_kml.rows) {
+            assert_eq!(zipped.source_row_hash, plain.source_row_hash);
+        }
+
+        let assets_only = zip_entries(&[("images/icon-1.png", &[0x89, b'P', b'N', b'G'])]);
+        assert!(matches!(parse_kml(&assets_only), Err(AppError::Parse(_))));
+    }
+
+    #[test]
+    fn imports_local_kml_and_kmz_files() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let bootstrap = bootstrap(dir.path(), "local.db", &vault).unwrap();
+        let mut conn = bootstrap.context.connection;
+        let project_id: i64 = conn
+            .query_row(
+                "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+
+        let kml_path = dir.path().join("export.kml");
+        fs::write(&kml_path, SAMPLE_KML).unwrap();
+        let kmz_path = dir.path().join("export.kmz");
+        fs::write(
+            &kmz_path,
+            zip_entries(&[(KMZ_ROOT_ENTRY, SAMPLE_KML.as_bytes())]),
+        )
+        .unwrap();
+
+        for (slot, path) in [(ListSlot::A, &kml_path), (ListSlot::B, &kmz_path)] {
+            let (metadata, local) = read_local_file(path).unwrap();
+            assert_eq!(metadata.source.as_deref(), Some(LOCAL_FILE_SOURCE));
+            let parsed = parse_kml(&local.bytes).unwrap();
+            let summary =
+                persist_rows(&mut conn, project_id, slot, &metadata, &parsed.rows).unwrap();
+            assert_eq!(summary.row_count, 2);
+
+            let (count, source): (i64, String) = conn
+                .query_row(
+                    "SELECT (SELECT COUNT(*) FROM raw_items WHERE list_id = l.id), l.source
+                    FROM lists l WHERE l.id = ?1",
+                    [summary.list_id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap();
+            assert_eq!(count, 2);
+            assert_eq!(source, LOCAL_FILE_SOURCE);
+        }
+    }
+
+    #[test]
+    fn imports_pasted_kml_text() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let bootstrap = bootstrap(dir.path(), "pasted.db", &vault).unwrap();
+        let mut conn = bootstrap.context.connection;
+        let project_id: i64 = conn
+            .query_row(
+                "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+
+        let (metadata, download) = pasted_kml("Clipboard", SAMPLE_KML);
+        assert_eq!(metadata.source.as_deref(), Some(PASTED_KML_SOURCE));
+        let parsed = parse_kml(&download.bytes).unwrap();
+        let summary =
+            persist_rows(&mut conn, project_id, ListSlot::A, &metadata, &parsed.rows).unwrap();
+
+        let (count, source): (i64, String) = conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM raw_items WHERE list_id = l.id), l.source
+                FROM lists l WHERE l.id = ?1",
+                [summary.list_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(source, PASTED_KML_SOURCE);
+    }
+
+    #[test]
+    fn clears_slot_but_keeps_list_row() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let bootstrap = bootstrap(dir.path(), "clear.db", &vault).unwrap();
+        let mut conn = bootstrap.context.connection;
+        let project_id: i64 = conn
+            .query_row(
+                "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let (metadata, download) = pasted_kml("Slot A", SAMPLE_KML);
+        persist_drive_selection(&conn, project_id, ListSlot::A, Some(&metadata)).unwrap();
+        let parsed = parse_kml(&download.bytes).unwrap();
+        let summary =
+            persist_rows(&mut conn, project_id, ListSlot::A, &metadata, &parsed.rows).unwrap();
+        conn.execute(
+            "INSERT INTO places (place_id, name, lat, lng) VALUES ('place-1', 'Example', 1.0, 2.0)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO list_places (list_id, place_id) VALUES (?1, 'place-1')",
+            [summary.list_id],
+        )
+        .unwrap();
+
+        let list_id = clear_list_slot(&mut conn, project_id, ListSlot::A).unwrap();
+        assert_eq!(list_id, summary.list_id);
+        let (raw_items, assignments, lists): (i64, i64, i64) = conn
+            .query_row(
+                "SELECT
+                    (SELECT COUNT(*) FROM raw_items WHERE list_id = ?1),
+                    (SELECT COUNT(*) FROM list_places WHERE list_id = ?1),
+                    (SELECT COUNT(*) FROM lists WHERE id = ?1)",
+                [list_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((raw_items, assignments, lists), (0, 0, 1));
+        assert!(stored_drive_selection(&conn, project_id, ListSlot::A)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn reports_stale_when_drive_file_changed() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let bootstrap = bootstrap(dir.path(), "freshness.db", &vault).unwrap();
+        let conn = bootstrap.context.connection;
+        let project_id: i64 = conn
+            .query_row(
+                "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(stored_drive_selection(&conn, project_id, ListSlot::A)
+            .unwrap()
+            .is_none());
+
+        let imported = DriveFileMetadata {
+            id: "drive-file".into(),
+            name: "List A".into(),
+            mime_type: KML_MIME.into(),
+            modified_time: Some("2024-01-01T00:00:00Z".into()),
+            size: Some(42),
+            md5_checksum: Some("abc123".into()),
+            source: None,
+        };
+        persist_drive_selection(&conn, project_id, ListSlot::A, Some(&imported)).unwrap();
+        let stored = stored_drive_selection(&conn, project_id, ListSlot::A)
+            .unwrap()
+            .expect("stored selection");
+
+        let unchanged = SlotFreshness::compare(ListSlot::A, &stored, &imported);
+        assert_eq!(unchanged.status, FreshnessStatus::UpToDate);
+
+        let live = DriveFileMetadata {
+            modified_time: Some("2024-02-01T00:00:00Z".into()),
+            ..imported.clone()
+        };
+        let changed = SlotFreshness::compare(ListSlot::A, &stored, &live);
+        assert_eq!(changed.status, FreshnessStatus::Stale);
+        assert_eq!(
+            changed.live_modified_time.as_deref(),
+            Some("2024-02-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn fans_multigeometry_into_rows() {
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <kml xmlns="http://www.opengis.net/kml/2.2">
+          <Document>
+            <Placemark>
+              <name>Weekend route</name>
+              <MultiGeometry>
+                <Point><coordinates>-122.1,37.1,0</coordinates></Point>
+                <Point><coordinates>-122.2,37.2,0</coordinates></Point>
+                <LineString>
+                  <coordinates>-122.3,37.3,0 -122.4,37.4,0</coordinates>
+                </LineString>
+              </MultiGeometry>
+            </Placemark>
+            <Placemark>
+              <name>Single stop</name>
+              <Point><coordinates>-122.5,37.5,0</coordinates></Point>
+            </Placemark>
+          </Document>
+        </kml>"#;
+
+        let parsed = parse_kml(kml.as_bytes()).unwrap();
+        assert!(parsed.rejected.is_empty());
+        let titles: Vec<_> = parsed
+            .rows
+            .iter()
+            .map(|row| row.normalized.title.as_str())
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Weekend route #1",
+                "Weekend route #2",
+                "Weekend route #3",
+                "Single stop",
+            ]
+        );
+        assert_eq!(parsed.rows[2].normalized.longitude, -122.35);
+        assert_eq!(
+            parsed.rows[2].normalized.derived_from.as_deref(),
+            Some("LineString")
+        );
+        assert!(parsed.rows[0].normalized.derived_from.is_none());
+        assert_eq!(parsed.rows[3].normalized.raw_coordinates, "-122.5,37.5,0");
+    }
+
+    #[test]
+    fn uses_vertex_average_for_polygons() {
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <kml xmlns="http://www.opengis.net/kml/2.2">
+          <Document>
+            <Placemark>
+              <name>City park</name>
+              <Polygon>
+                <outerBoundaryIs>
+                  <LinearRing>
+                    <coordinates>
+                      -122.0,37.0,0 -121.0,37.0,0 -121.0,38.0,0 -122.0,38.0,0 -122.0,37.0,0
+                    </coordinates>
+                  </LinearRing>
+                </outerBoundaryIs>
+                <innerBoundaryIs>
+                  <LinearRing>
+                    <coordinates>-121.6,37.4,0 -121.4,37.4,0 -121.5,37.6,0 -121.6,37.4,0</coordinates>
+                  </LinearRing>
+                </innerBoundaryIs>
+              </Polygon>
+            </Placemark>
+          </Document>
+        </kml>"#;
+
+        let parsed = parse_kml(kml.as_bytes()).unwrap();
+        assert!(parsed.rejected.is_empty());
+        assert_eq!(parsed.rows.len(), 1);
+        let row = &parsed.rows[0].normalized;
+        assert_eq!(row.title, "City park");
+        assert_eq!(row.longitude, -121.5);
+        assert_eq!(row.latitude, 37.5);
+        assert_eq!(row.derived_from.as_deref(), Some("Polygon"));
+    }
+
+    #[test]
+    fn parses_geojson_points_and_rejects_other_geometries() {
+        let geojson = r#"{
+          "type": "FeatureCollection",
+          "features": [
+            {
+              "type": "Feature",
+              "geometry": { "type": "Point", "coordinates": [-122.4194, 37.7749] },
+              "properties": { "name": "Ferry Building", "place_id": "ChIJferry" }
+            },
+            {
+              "type": "Feature",
+              "geometry": { "type": "Point", "coordinates": [2.2945, 48.8584, 35.0] },
+              "properties": { "name": "Eiffel Tower", "description": "Paris" }
+            },
+            {
+              "type": "Feature",
+              "geometry": { "type": "LineString", "coordinates": [[0, 0], [1, 1]] },
+              "properties": { "name": "Walk" }
+            }
+          ]
+        }"#;
+
+        let parsed = parse_import(
+            geojson.as_bytes(),
+            GEOJSON_MIME,
+            &CsvImportOptions::default(),
+            &default_place_id_fields(),
+            DEFAULT_MAX_IMPORT_BYTES,
+        )
+        .unwrap();
+        assert_eq!(parsed.rows.len(), 2);
+        assert_eq!(parsed.rejected.len(), 1);
+        assert!(parsed.rejected[0].message.contains("LineString"));
+
+        let ferry = &parsed.rows[0].normalized;
+        assert_eq!(ferry.title, "Ferry Building");
+        assert_eq!(ferry.longitude, -122.4194);
+        assert_eq!(ferry.latitude, 37.7749);
+        assert_eq!(ferry.place_id.as_deref(), Some("ChIJferry"));
+
+        let tower = &parsed.rows[1].normalized;
+        assert_eq!(tower.altitude, Some(35.0));
+        assert_eq!(tower.description.as_deref(), Some("Paris"));
+    }
+
+    #[test]
+    fn rejects_geojson_points_with_non_numeric_coordinates() {
+        let geojson = r#"{
+          "type": "FeatureCollection",
+          "features": [
+            {
+              "type": "Feature",
+              "geometry": { "type": "Point", "coordinates": ["x", 10, 20] },
+              "properties": { "name": "Shifted" }
+            },
+            {
+              "type": "Feature",
+              "geometry": { "type": "Point", "coordinates": [10, 20, "high"] },
+              "properties": { "name": "Kept" }
+            }
+          ]
+        }"#;
+
+        let parsed = parse_geojson(geojson.as_bytes(), &default_place_id_fields()).unwrap();
+        assert_eq!(parsed.rejected.len(), 1);
+        assert_eq!(
+            parsed.rejected[0].message,
+            "Feature missing valid coordinates"
+        );
+        assert_eq!(parsed.rows.len(), 1);
+        let kept = &parsed.rows[0].normalized;
+        assert_eq!((kept.longitude, kept.latitude), (10.0, 20.0));
+        assert_eq!(kept.altitude, None);
+    }
+
+    #[test]
+    fn parses_csv_coordinate_lists() {
+        let csv = "Name,Latitude,Longitude,Notes\n\
+                   Ferry Building,37.7955,-122.3937,Market\n\
+                   Coit Tower,37.8024,-122.4058,\n";
+        let options = CsvImportOptions {
+            description_column: Some("notes".into()),
+            ..CsvImportOptions::default()
+        };
+        let parsed = parse_csv(csv.as_bytes(), &options).unwrap();
+        assert!(parsed.rejected.is_empty());
+        assert_eq!(parsed.rows.len(), 2);
+        let ferry = &parsed.rows[0].normalized;
+        assert_eq!(ferry.title, "Ferry Building");
+        assert_eq!(ferry.latitude, 37.7955);
+        assert_eq!(ferry.longitude, -122.3937);
+        assert_eq!(ferry.description.as_deref(), Some("Market"));
+        assert_eq!(parsed.rows[1].normalized.description, None);
+
+        let combined = "title,position\nPier 39,\"-122.4098,37.8087\"\n";
+        let options = CsvImportOptions {
+            name_column: "title".into(),
+            coordinates_column: Some("position".into()),
+            longitude_first: true,
+            ..CsvImportOptions::default()
+        };
+        let parsed = parse_csv(combined.as_bytes(), &options).unwrap();
+        assert_eq!(parsed.rows[0].normalized.latitude, 37.8087);
+        assert_eq!(parsed.rows[0].normalized.longitude, -122.4098);
+    }
+
+    #[test]
+    fn rejects_csv_rows_with_invalid_coordinates() {
+        let csv = "name,latitude,longitude\nGood,37.1,-122.1\nBad,north,-122.2\n";
+        let parsed = parse_csv(csv.as_bytes(), &CsvImportOptions::default()).unwrap();
+        assert_eq!(parsed.rows.len(), 1);
+        assert_eq!(parsed.rejected.len(), 1);
+        assert_eq!(parsed.rejected[0].raw.name.as_deref(), Some("Bad"));
+        assert_eq!(parsed.rejected[0].placemark_index, 3);
+        assert_eq!(parsed.rejected[0].location(), "Row 3");
+    }
+
+    #[test]
+    fn rejects_malformed_csv_records_and_keeps_importing() {
+        let csv = b"name,latitude,longitude\nGood,37.1,-122.1\nBad\xff,37.2,-122.2\nAlso good,37.3,-122.3\n";
+        let parsed = parse_csv(csv, &CsvImportOptions::default()).unwrap();
+        let titles: Vec<_> = parsed
+            .rows
+            .iter()
+            .map(|row| row.normalized.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Good", "Also good"]);
+        assert_eq!(parsed.rejected.len(), 1);
+        assert!(parsed.rejected[0].message.contains("Malformed"));
+        assert_eq!(parsed.rejected[0].location(), "Row 3");
+    }
+
+    #[test]
+    fn strips_html_from_descriptions() {
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <kml xmlns="http://www.opengis.net/kml/2.2">
+          <Document>
+            <Placemark>
+              <name>Bakery</name>
+              <description><![CDATA[Fresh bread<br>Open daily &amp; late<br/><a href="https://example.com/menu">See the menu</a>]]></description>
+              <Point><coordinates>-122.1,37.1,0</coordinates></Point>
+            </Placemark>
+          </Document>
+        </kml>"#;
+
+        let parsed = parse_kml(kml.as_bytes()).unwrap();
+        assert_eq!(
+            parsed.rows[0].normalized.description.as_deref(),
+            Some("Fresh bread Open daily & late See the menu")
+        );
+    }
+
+    #[test]
+    fn recognizes_configured_place_id_fields() {
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <kml xmlns="http://www.opengis.net/kml/2.2">
+          <Document>
+            <Placemark>
+              <name>Museum</name>
+              <Point><coordinates>-122.1,37.1,0</coordinates></Point>
+              <ExtendedData>
+                <Data name="GooglePlaceId"><value>ChIJcustom</value></Data>
+              </ExtendedData>
+            </Placemark>
+          </Document>
+        </kml>"#;
+
+        let defaults = parse_kml(kml.as_bytes()).unwrap();
+        assert_eq!(defaults.rows[0].normalized.place_id, None);
+
+        let fields = vec!["googlePlaceId".to_string()];
+        let parsed = parse_kml_with_fields(kml.as_bytes(), &fields).unwrap();
+        assert_eq!(
+            parsed.rows[0].normalized.place_id.as_deref(),
+            Some("ChIJcustom")
+        );
+
+        let geojson = r#"{
+          "type": "FeatureCollection",
+          "features": [{
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [-122.1, 37.1] },
+            "properties": { "name": "Museum", "GooglePlaceId": "ChIJcustom" }
+          }]
+        }"#;
+        let defaults = parse_geojson(geojson.as_bytes(), &default_place_id_fields()).unwrap();
+        assert_eq!(defaults.rows[0].normalized.place_id, None);
+        let parsed = parse_geojson(geojson.as_bytes(), &fields).unwrap();
+        assert_eq!(
+            parsed.rows[0].normalized.place_id.as_deref(),
+            Some("ChIJcustom")
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_coordinates() {
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <kml xmlns="http://www.opengis.net/kml/2.2">
+          <Document>
+            <Placemark>
+              <name>Corrupt</name>
+              <Point><coordinates>200.0,91.0,0</coordinates></Point>
+            </Placemark>
+            <Placemark>
+              <name>Edge of the map</name>
+              <Point><coordinates>-180.0,90.0,0</coordinates></Point>
+            </Placemark>
+          </Document>
+        </kml>"#;
+
+        let parsed = parse_kml(kml.as_bytes()).unwrap();
+        assert_eq!(parsed.rows.len(), 1);
+        assert_eq!(parsed.rows[0].normalized.title, "Edge of the map");
+        assert_eq!(parsed.rejected.len(), 1);
+        assert_eq!(parsed.rejected[0].raw.name.as_deref(), Some("Corrupt"));
+        assert!(parsed.rejected[0].message.contains("out of range"));
+    }
+
+    #[test]
+    fn records_position_of_rejected_placemarks() {
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<kml xmlns="http://www.opengis.net/kml/2.2">
+  <Document>
+    <Placemark>
+      <name>Fine</name>
+      <Point><coordinates>-122.1,37.1,0</coordinates></Point>
+    </Placemark>
+    <Placemark>
+      <name>No geometry</name>
+    </Placemark>
+    <Placemark>
+      <name>Garbled</name>
+      <Point><coordinates>east,north</coordinates></Point>
+    </Placemark>
+  </Document>
+</kml>"#;
+
+        let parsed = parse_kml(kml.as_bytes()).unwrap();
+        let positions: Vec<_> = parsed
+            .rejected
+            .iter()
+            .map(|entry| (entry.placemark_index, entry.line))
+            .collect();
+        assert_eq!(positions, vec![(2, Some(8)), (3, Some(11))]);
+        assert_eq!(parsed.rejected[1].location(), "Placemark #3 (line 11)");
+    }
+
+    #[test]
+    fn rejects_documents_without_placemarks() {
+        let empty = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <kml xmlns="http://www.opengis.net/kml/2.2"><Document><name>Empty</name></Document></kml>
+        "#;
+        let parsed = parse_kml(empty.as_bytes()).unwrap();
+        assert!(parsed.rows.is_empty());
+        assert!(parsed.rejected.is_empty());
+        match parsed.ensure_importable() {
+            Err(AppError::Parse(message)) => assert_eq!(message, "no placemarks found"),
+            other => panic!("expected parse error, got {other:?}"),
+        }
+
+        assert!(parse_kml(SAMPLE_KML.as_bytes())
+            .unwrap()
+            .ensure_importable()
+            .is_ok());
+    }
+
+    #[test]
+    fn reports_when_every_placemark_was_rejected() {
+        let kml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <kml xmlns="http://www.opengis.net/kml/2.2">
+          <Document>
+            <Placemark><name>No geometry</name></Placemark>
+            <Placemark>
+              <name>Off the map</name>
+              <Point><coordinates>200,95</coordinates></Point>
+            </Placemark>
+          </Document>
+        </kml>
+        "#;
+        let parsed = parse_kml(kml.as_bytes()).unwrap();
+        assert_eq!(parsed.rejected.len(), 2);
+        match parsed.ensure_importable() {
+            Err(AppError::Parse(message)) => {
+                assert!(message.starts_with("all 2 placemarks were rejected"));
+                assert!(message.contains("Placemark missing coordinates"));
+                assert_ne!(message, "no placemarks found");
+            }
+            other => panic!("expected parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_coordinates_wrapped_across_lines() {
+        let kml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+        <kml xmlns=\"http://www.opengis.net/kml/2.2\">
+          <Document>
+            <Placemark>
+              <name>Wrapped</name>
+              <Point>
+                <coordinates>
+
+                  -122.084000,
+                  37.421998,
+                  12
+                </coordinates>
+              </Point>
+            </Placemark>
+            <Placemark>
+              <name>Tabbed</name>
+              <Point><coordinates>\n\t\t-0.1 ,\t51.5\n\t</coordinates></Point>
+            </Placemark>
+          </Document>
+        </kml>";
+
+        let parsed = parse_kml(kml.as_bytes()).unwrap();
+        assert!(parsed.rejected.is_empty(), "{:?}", parsed.rejected);
+        assert_eq!(parsed.rows.len(), 2);
+
+        let wrapped = &parsed.rows[0].normalized;
+        assert_eq!(wrapped.longitude, -122.084);
+        assert_eq!(wrapped.latitude, 37.421998);
+        assert_eq!(wrapped.altitude, Some(12.0));
+        assert_eq!(wrapped.raw_coordinates, "-122.084000,37.421998,12");
+
+        let tabbed = &parsed.rows[1].normalized;
+        assert_eq!(tabbed.longitude, -0.1);
+        assert_eq!(tabbed.latitude, 51.5);
+        assert_eq!(tabbed.altitude, None);
+    }
 }
@@ -11,15 +11,15 @@ mod secrets;
 mod settings;
 mod telemetry;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use base64::engine::general_purpose::STANDARD_NO_PAD;
 use base64::Engine;
-use chrono::Utc;
 use csv::WriterBuilder;
 use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
@@ -33,32 +33,43 @@ use tracing::warn;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 use crate::commands::FoundationHealth;
-use crate::comparison::{
-    ComparisonPagination, ComparisonSegment, ComparisonSegmentPage, ComparisonSnapshot,
-    PlaceComparisonRow,
-};
+use crate::comparison::{ComparisonDiff, ComparisonLists, ComparisonSnapshot, PlaceComparisonRow};
 use crate::db::{DatabaseBootstrap, DatabaseContext, DB_KEY_ALIAS};
-use crate::errors::{AppError, AppResult};
-use crate::places::{NormalizationProgress, NormalizationStats, PlaceNormalizer};
-use crate::projects::ComparisonProjectRecord;
+use crate::places::{
+    throttle_progress, NormalizationProgress, NormalizationStats, PlaceNormalizer,
+    ProgressObserver, RefreshRegistry, RefreshScope,
+};
+use crate::projects::{ComparisonProjectRecord, ComparisonRunRecord, ProjectBundle};
 use crate::secrets::SecretLifecycle;
-use crate::settings::{RuntimeSettings, UpdateRuntimeSettingsPayload, UserSettings};
+use crate::settings::{MapStyle, RuntimeSettings, UpdateRuntimeSettingsPayload, UserSettings};
 use secrecy::ExposeSecret;
 
 const VAULT_SERVICE_NAME: &str = "GoogleMapsListComparator";
 
 pub use commands::foundation_health;
+pub use comparison::{
+    compute_slot_sets, compute_snapshot_with, find_duplicates, load_segment_after,
+    load_segment_in_bounds, load_segment_page, segment_nearest, BoundingBox, ComparisonPagination,
+    ComparisonSegment, ComparisonSegmentPage, DuplicateCluster, FuzzyMatchOptions, LooseOnlyReport,
+    MultiListComparison, NearestPlace, OverlapPairing, PendingRows, SegmentCursor, SegmentExtent,
+    SegmentFilter, SlotSet, TypeFacet,
+};
 pub use config::AppConfig;
-pub use db::bootstrap;
+pub use db::{bootstrap, bootstrap_with_cipher, CipherSettings, SchemaRepairReport, WalCheckpoint};
+pub use errors::{AppError, AppResult, CommandError, ErrorKind};
 pub use google::{
-    DeviceFlowState, DriveFileMetadata, GoogleIdentity, GoogleServices, LoopbackFlowState,
+    DeviceFlowState, DownloadedFile, DownloadedTempFile, DriveFileMetadata, GoogleAccount,
+    GoogleIdentity, GoogleServices, LoopbackFlowState, RefreshEvent, SignOutObserver,
 };
 pub use ingestion::{
-    enqueue_place_hashes, parse_kml, persist_rows, ImportSummary, ListSlot, ParsedKml, ParsedRow,
-    RejectedPlacemark,
+    enqueue_place_hashes, linked_drive_file, parse_csv, parse_geojson, parse_kml,
+    parse_kml_with_fields, persist_drive_selection, persist_rows, read_local_file,
+    CsvImportOptions, FreshnessStatus, ImportHistoryEntry, ImportSummary, ListSlot, ParsedKml,
+    ParsedRow, RawRowEntry, RawRowPage, RejectedPlacemark, SlotFreshness,
 };
+pub use places::{PlaceEnrichment, RefreshRegistry};
 pub use secrets::SecretVault;
-pub use telemetry::TelemetryClient;
+pub use telemetry::{TelemetryClient, TelemetryEvent, TelemetryFlushGuard, TelemetryFlushTask};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct ImportProgressPayload {
@@ -143,11 +154,13 @@ pub struct RefreshProgressPayload {
     pub resolved: usize,
     pub pending: usize,
     pub rate_limit_qps: u32,
+    pub resumed_from: Option<usize>,
     pub message: String,
 }
 
 #[derive(Debug, Serialize, Clone)]
 pub struct MapStyleDescriptor {
+    pub style: MapStyle,
     pub style_url: Option<String>,
 }
 
@@ -160,8 +173,19 @@ pub struct ExportSummary {
     pub segment: String,
 }
 
+/// Delivers a backend event to the frontend. The app forwards events to the webview; tests
+/// can pass a closure that records or reacts to them instead.
+pub type EventSink = Arc<dyn Fn(&str, Value) + Send + Sync>;
+
+fn emit_event(events: &EventSink, event: &str, payload: impl Serialize) {
+    match serde_json::to_value(payload) {
+        Ok(value) => events(event, value),
+        Err(err) => warn!(?err, event, "failed to serialize event payload"),
+    }
+}
+
 pub struct AppState {
-    handle: tauri::AppHandle,
+    events: EventSink,
     db: Arc<Mutex<SqlConnection>>,
     active_project_id: Arc<Mutex<i64>>,
     db_path: PathBuf,
@@ -170,32 +194,73 @@ pub struct AppState {
     settings: Arc<Mutex<UserSettings>>,
     settings_path: PathBuf,
     telemetry: TelemetryClient,
+    _telemetry_guard: TelemetryFlushGuard,
+    telemetry_flush_task: Mutex<Option<TelemetryFlushTask>>,
     db_bootstrap_recovered: bool,
     db_key_lifecycle: SecretLifecycle,
     google: Option<GoogleServices>,
     places: PlaceNormalizer,
-    refresh_cancel_token: Arc<Mutex<Option<Arc<AtomicBool>>>>,
+    refresh_runs: RefreshRegistry,
+    /// Cancel flags for imports in flight, one per project slot being imported. Shared with
+    /// the sign-out observer, which trips them all.
+    import_cancels: Arc<Mutex<HashMap<(i64, ListSlot), Arc<AtomicBool>>>>,
 }
 
 impl AppState {
     fn initialize(app: &tauri::AppHandle) -> AppResult<Self> {
         init_tracing();
-        let config = AppConfig::from_env();
-        let vault = SecretVault::new(VAULT_SERVICE_NAME);
         let data_dir = app.path().app_data_dir()?;
         let handle = app.clone();
+        let events: EventSink = Arc::new(move |event, payload| {
+            if let Err(err) = handle.emit(event, payload) {
+                warn!(?err, event, "failed to emit event");
+            }
+        });
+        Self::open(
+            &data_dir,
+            AppConfig::from_env(),
+            SecretVault::new(VAULT_SERVICE_NAME),
+            events,
+        )
+    }
 
-        std::fs::create_dir_all(&data_dir)?;
-        let settings_path = settings::settings_path(&data_dir);
+    /// Opens the app's database, settings and services under `data_dir`. `initialize` calls this
+    /// with the Tauri data directory, the OS keychain and the webview as event sink.
+    pub fn open(
+        data_dir: &Path,
+        config: AppConfig,
+        vault: SecretVault,
+        events: EventSink,
+    ) -> AppResult<Self> {
+        std::fs::create_dir_all(data_dir)?;
+        let settings_path = settings::settings_path(data_dir);
         let settings = UserSettings::load(&settings_path, &config)?;
         let DatabaseBootstrap {
             context: DatabaseContext { connection, path },
             key_lifecycle,
             recovered,
-        } = bootstrap(&data_dir, &config.database_file_name, &vault)?;
-        let telemetry = TelemetryClient::new(&data_dir, &config)?;
+        } = bootstrap_with_cipher(data_dir, &config.database_file_name, &vault, config.cipher)?;
+        let telemetry = TelemetryClient::new(data_dir, &config)?;
         telemetry.set_enabled(settings.telemetry_enabled);
+        telemetry.set_salt(&settings.telemetry_salt);
         let google = GoogleServices::maybe_new(&config, &vault, telemetry.clone())?;
+        let refresh_runs = RefreshRegistry::default();
+        let import_cancels: Arc<Mutex<HashMap<(i64, ListSlot), Arc<AtomicBool>>>> = Arc::default();
+        if let Some(services) = google.as_ref() {
+            services.set_offline(settings.offline);
+            let runs = refresh_runs.clone();
+            let imports = Arc::clone(&import_cancels);
+            services.set_sign_out_observer(Arc::new(move || {
+                runs.cancel();
+                for flag in imports.lock().values() {
+                    flag.store(true, Ordering::SeqCst);
+                }
+            }));
+            let emitter = Arc::clone(&events);
+            services.set_refresh_observer(Arc::new(move |event: RefreshEvent| {
+                emit_event(&emitter, event.event_name(), &event);
+            }));
+        }
 
         if let Err(err) = telemetry.record(
             "vault_audit",
@@ -228,10 +293,12 @@ impl AppState {
         let active_project_id = Arc::new(Mutex::new(initial_project_id));
         let places = PlaceNormalizer::new(Arc::clone(&db), &config);
         places.set_rate_limit(settings.places_rate_limit_qps);
+        places.set_cache_ttl(settings.cache_ttl_hours());
+        places.set_offline(settings.offline);
         let settings = Arc::new(Mutex::new(settings));
 
         Ok(Self {
-            handle,
+            events,
             db,
             active_project_id,
             db_path: path,
@@ -239,15 +306,27 @@ impl AppState {
             config,
             settings,
             settings_path,
+            _telemetry_guard: telemetry.flush_guard(),
+            telemetry_flush_task: Mutex::new(telemetry.start_periodic_flush()),
             telemetry,
             db_bootstrap_recovered: recovered,
             db_key_lifecycle: key_lifecycle,
             google,
             places,
-            refresh_cancel_token: Arc::new(Mutex::new(None)),
+            refresh_runs,
+            import_cancels,
         })
     }
 
+    pub fn read_telemetry_events(&self, limit: usize) -> AppResult<Vec<TelemetryEvent>> {
+        self.telemetry.read_recent(limit)
+    }
+
+    pub fn shutdown(&self) {
+        self.telemetry_flush_task.lock().take();
+        self.telemetry.flush_lossy();
+    }
+
     pub fn foundation_health(&self) -> AppResult<FoundationHealth> {
         let has_key = self.vault.has(DB_KEY_ALIAS)?;
         Ok(FoundationHealth::new(
@@ -266,14 +345,16 @@ impl AppState {
         self.settings.lock().runtime_profile()
     }
 
-    pub fn map_style_descriptor(&self) -> MapStyleDescriptor {
-        let style_url = self.config.maptiler_key.as_ref().map(|key| {
-            format!(
-                "https://api.maptiler.com/maps/streets/style.json?key={}",
-                key.expose_secret()
-            )
-        });
-        MapStyleDescriptor { style_url }
+    pub fn map_style_descriptor(&self, style: Option<&str>) -> MapStyleDescriptor {
+        let style = style
+            .map(MapStyle::parse)
+            .unwrap_or_else(|| self.settings.lock().map_style);
+        let style_url = self
+            .config
+            .maptiler_key
+            .as_ref()
+            .map(|key| style.style_url(key.expose_secret()));
+        MapStyleDescriptor { style, style_url }
     }
 
     pub fn list_comparison_projects(&self) -> AppResult<Vec<ComparisonProjectRecord>> {
@@ -296,6 +377,15 @@ impl AppState {
         Ok(record)
     }
 
+    pub fn set_project_rate_limit(
+        &self,
+        project_id: i64,
+        qps: Option<u32>,
+    ) -> AppResult<ComparisonProjectRecord> {
+        let conn = self.db.lock();
+        projects::set_project_rate_limit(&conn, project_id, qps)
+    }
+
     pub fn rename_comparison_project(
         &self,
         project_id: i64,
@@ -311,6 +401,62 @@ impl AppState {
         Ok(record)
     }
 
+    pub fn delete_comparison_project(&self, project_id: i64) -> AppResult<ComparisonProjectRecord> {
+        let record = {
+            let mut conn = self.db.lock();
+            let active_id = projects::delete_project(&mut conn, project_id)?;
+            projects::project_by_id(&conn, active_id)?
+        };
+        self.places.forget_project(project_id);
+        *self.active_project_id.lock() = record.id;
+        Ok(record)
+    }
+
+    pub async fn duplicate_comparison_project(
+        &self,
+        project_id: i64,
+    ) -> AppResult<ComparisonProjectRecord> {
+        let copy = {
+            let mut conn = self.db.lock();
+            projects::duplicate_project(&mut conn, project_id)?
+        };
+        for slot in ListSlot::ALL {
+            self.places
+                .normalize_slot(copy.id, slot, None, None)
+                .await?;
+        }
+        Ok(copy)
+    }
+
+    pub fn export_project(&self, project_id: Option<i64>, destination: PathBuf) -> AppResult<()> {
+        let resolved = self.resolve_project_id(project_id)?;
+        let bundle = {
+            let conn = self.db.lock();
+            projects::export_project_bundle(&conn, resolved)?
+        };
+        if let Some(parent) = destination.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        fs::write(&destination, serde_json::to_vec_pretty(&bundle)?)?;
+        Ok(())
+    }
+
+    pub async fn import_project(&self, source: PathBuf) -> AppResult<ComparisonProjectRecord> {
+        let bundle: ProjectBundle = serde_json::from_slice(&fs::read(&source)?)?;
+        let record = {
+            let mut conn = self.db.lock();
+            projects::import_project_bundle(&mut conn, &bundle)?
+        };
+        for slot in ListSlot::ALL {
+            self.places
+                .normalize_slot(record.id, slot, None, None)
+                .await?;
+        }
+        Ok(record)
+    }
+
     pub fn set_active_comparison_project(
         &self,
         project_id: i64,
@@ -324,12 +470,31 @@ impl AppState {
         Ok(record)
     }
 
+    pub fn ensure_single_active_project(&self) -> AppResult<ComparisonProjectRecord> {
+        let record = {
+            let conn = self.db.lock();
+            projects::ensure_single_active_project(&conn)?
+        };
+        *self.active_project_id.lock() = record.id;
+        Ok(record)
+    }
+
     pub fn active_comparison_project(&self) -> AppResult<ComparisonProjectRecord> {
         let project_id = *self.active_project_id.lock();
         let conn = self.db.lock();
         projects::project_by_id(&conn, project_id)
     }
 
+    pub fn comparison_run_history(
+        &self,
+        project_id: Option<i64>,
+        limit: Option<usize>,
+    ) -> AppResult<Vec<ComparisonRunRecord>> {
+        let resolved = self.resolve_project_id(project_id)?;
+        let conn = self.db.lock();
+        projects::comparison_run_history(&conn, resolved, limit)
+    }
+
     pub fn record_telemetry_event(
         &self,
         name: String,
@@ -347,33 +512,17 @@ impl AppState {
         self.google()?.start_device_flow().await
     }
 
-    pub fn comparison_snapshot(
+    pub async fn comparison_snapshot(
         &self,
         project_id: Option<i64>,
         pagination: Option<ComparisonPagination>,
+        fuzzy: Option<FuzzyMatchOptions>,
     ) -> AppResult<ComparisonSnapshot> {
         let resolved = self.resolve_project_id(project_id)?;
-        let started_at = Utc::now();
-        let timer = std::time::Instant::now();
-        let snapshot = {
-            let conn = self.db.lock();
-            comparison::compute_snapshot(&conn, resolved, pagination)?
-        };
-        let duration_ms = timer.elapsed().as_millis();
-        {
-            let conn = self.db.lock();
-            if let Err(err) = projects::record_comparison_run(
-                &conn,
-                resolved,
-                snapshot.lists.list_a_id,
-                snapshot.lists.list_b_id,
-                &snapshot.stats,
-                started_at.to_rfc3339(),
-                duration_ms,
-            ) {
-                warn!(?err, "failed to persist comparison run history");
-            }
-        }
+        let (snapshot, duration_ms) = db::run_blocking(&self.db, move |conn| {
+            projects::run_comparison(conn, resolved, pagination, fuzzy)
+        })
+        .await?;
         if let Err(err) = self.telemetry.record(
             "compare_run",
             json!({
@@ -397,30 +546,176 @@ impl AppState {
         Ok(snapshot)
     }
 
+    pub async fn comparison_diff(
+        &self,
+        project_id: Option<i64>,
+        slot: ListSlot,
+    ) -> AppResult<ComparisonDiff> {
+        let resolved = self.resolve_project_id(project_id)?;
+        db::run_blocking(&self.db, move |conn| {
+            comparison::compute_diff(conn, resolved, slot)
+        })
+        .await
+    }
+
+    pub async fn compare_slots(&self, project_id: Option<i64>) -> AppResult<MultiListComparison> {
+        let resolved = self.resolve_project_id(project_id)?;
+        db::run_blocking(&self.db, move |conn| {
+            comparison::compute_slot_sets(conn, resolved)
+        })
+        .await
+    }
+
+    pub async fn list_pending_rows(&self, project_id: Option<i64>) -> AppResult<Vec<PendingRows>> {
+        let resolved = self.resolve_project_id(project_id)?;
+        db::run_blocking(&self.db, move |conn| {
+            comparison::list_pending_rows(conn, resolved)
+        })
+        .await
+    }
+
+    pub async fn find_duplicates(
+        &self,
+        project_id: Option<i64>,
+        slot: ListSlot,
+        options: FuzzyMatchOptions,
+    ) -> AppResult<Vec<DuplicateCluster>> {
+        let resolved = self.resolve_project_id(project_id)?;
+        db::run_blocking(&self.db, move |conn| {
+            comparison::find_duplicates(conn, resolved, slot, options)
+        })
+        .await
+    }
+
     pub fn comparison_segment_page(
         &self,
         project_id: Option<i64>,
         segment: ComparisonSegment,
         pagination: ComparisonPagination,
+        after: Option<SegmentCursor>,
+        filter: SegmentFilter,
     ) -> AppResult<ComparisonSegmentPage> {
         let resolved = self.resolve_project_id(project_id)?;
         let conn = self.db.lock();
-        comparison::load_segment_page(&conn, resolved, segment, pagination)
+        match after {
+            Some(cursor) => comparison::load_segment_after(
+                &conn,
+                resolved,
+                segment,
+                &cursor,
+                pagination.page_size,
+                &filter,
+            ),
+            None => comparison::load_segment_page(&conn, resolved, segment, pagination, &filter),
+        }
+    }
+
+    pub fn segment_in_bounds(
+        &self,
+        project_id: Option<i64>,
+        segment: ComparisonSegment,
+        bounds: BoundingBox,
+    ) -> AppResult<Vec<PlaceComparisonRow>> {
+        let resolved = self.resolve_project_id(project_id)?;
+        let conn = self.db.lock();
+        comparison::load_segment_in_bounds(&conn, resolved, segment, bounds)
+    }
+
+    pub async fn overlap_pairing(&self, project_id: Option<i64>) -> AppResult<Vec<OverlapPairing>> {
+        let resolved = self.resolve_project_id(project_id)?;
+        db::run_blocking(&self.db, move |conn| {
+            comparison::overlap_pairings(conn, resolved)
+        })
+        .await
+    }
+
+    pub fn segment_bounds(
+        &self,
+        project_id: Option<i64>,
+        segment: Option<ComparisonSegment>,
+    ) -> AppResult<Option<SegmentExtent>> {
+        let resolved = self.resolve_project_id(project_id)?;
+        let conn = self.db.lock();
+        comparison::segment_extent(&conn, resolved, segment)
+    }
+
+    pub async fn segment_geojson(
+        &self,
+        project_id: Option<i64>,
+        segment: Option<ComparisonSegment>,
+        bounds: Option<BoundingBox>,
+    ) -> AppResult<Value> {
+        let resolved = self.resolve_project_id(project_id)?;
+        db::run_blocking(&self.db, move |conn| {
+            comparison::segment_feature_collection(conn, resolved, segment, bounds)
+        })
+        .await
+    }
+
+    pub async fn segment_nearest(
+        &self,
+        project_id: Option<i64>,
+        segment: ComparisonSegment,
+        lat: f64,
+        lng: f64,
+        limit: Option<usize>,
+    ) -> AppResult<Vec<NearestPlace>> {
+        let resolved = self.resolve_project_id(project_id)?;
+        db::run_blocking(&self.db, move |conn| {
+            comparison::segment_nearest(conn, resolved, segment, lat, lng, limit)
+        })
+        .await
+    }
+
+    pub async fn loose_only_sets(
+        &self,
+        project_id: Option<i64>,
+        radius_m: Option<f64>,
+    ) -> AppResult<LooseOnlyReport> {
+        let resolved = self.resolve_project_id(project_id)?;
+        db::run_blocking(&self.db, move |conn| {
+            comparison::loose_only_sets(conn, resolved, radius_m)
+        })
+        .await
+    }
+
+    pub async fn place_photo_url(
+        &self,
+        place_id: String,
+        max_width: Option<u32>,
+    ) -> AppResult<Option<String>> {
+        let api_key = self.config.google_places_api_key.clone();
+        db::run_blocking(&self.db, move |conn| {
+            places::place_photo_url(conn, api_key.as_ref(), &place_id, max_width)
+        })
+        .await
+    }
+
+    pub fn segment_type_facets(
+        &self,
+        project_id: Option<i64>,
+        segment: ComparisonSegment,
+        query: Option<String>,
+    ) -> AppResult<Vec<TypeFacet>> {
+        let resolved = self.resolve_project_id(project_id)?;
+        let conn = self.db.lock();
+        comparison::segment_type_facets(&conn, resolved, segment, query.as_deref())
     }
 
-    pub fn export_comparison_segment(
+    pub async fn export_comparison_segment(
         &self,
         project_id: Option<i64>,
         segment: ComparisonSegment,
         format: &str,
         selection: Option<Vec<String>>,
         destination: PathBuf,
+        neutralize_formulas: bool,
     ) -> AppResult<ExportSummary> {
         let resolved = self.resolve_project_id(project_id)?;
-        let snapshot = {
-            let conn = self.db.lock();
-            comparison::compute_snapshot(&conn, resolved, None)?
-        };
+        let snapshot = db::run_blocking(&self.db, move |conn| {
+            comparison::compute_snapshot(conn, resolved, None)
+        })
+        .await?;
         let target_rows = snapshot.rows_for_segment(segment);
         let selection_set = selection.map(|ids| ids.into_iter().collect::<HashSet<_>>());
         let filtered: Vec<&PlaceComparisonRow> = target_rows
@@ -441,8 +736,14 @@ impl AppState {
 
         let export_format = ExportFormat::parse(format)?;
         match export_format {
-            ExportFormat::Csv => export_csv(&destination, &filtered)?,
-            ExportFormat::Json => export_json(&destination, &filtered)?,
+            ExportFormat::Csv => export_csv(
+                &destination,
+                &filtered,
+                &snapshot.lists,
+                neutralize_formulas,
+            )?,
+            ExportFormat::Json => export_json(&destination, &filtered, &snapshot.lists)?,
+            ExportFormat::GeoJson => export_geojson(&destination, &filtered, segment)?,
         }
 
         if let Err(err) = self.telemetry.record(
@@ -502,12 +803,20 @@ impl AppState {
         }
     }
 
-    pub async fn current_identity(&self) -> AppResult<GoogleIdentity> {
-        self.google()?.current_identity().await
+    pub async fn current_identity(&self, force: bool) -> AppResult<GoogleIdentity> {
+        self.google()?.current_identity(force).await
     }
 
-    pub fn sign_out_google(&self) -> AppResult<()> {
-        self.google()?.sign_out()
+    pub async fn sign_out_google(&self) -> AppResult<()> {
+        self.google()?.sign_out().await
+    }
+
+    pub fn list_google_accounts(&self) -> AppResult<Vec<GoogleAccount>> {
+        self.google()?.list_accounts()
+    }
+
+    pub async fn switch_google_account(&self, email: String) -> AppResult<GoogleIdentity> {
+        self.google()?.switch_account(&email).await
     }
 
     pub async fn keepalive_google(&self) -> AppResult<GoogleIdentity> {
@@ -548,6 +857,99 @@ impl AppState {
         Ok(())
     }
 
+    pub fn rename_list(
+        &self,
+        project_id: Option<i64>,
+        slot: ListSlot,
+        display_name: Option<String>,
+    ) -> AppResult<String> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        let conn = self.db.lock();
+        ingestion::rename_list(&conn, resolved_project, slot, display_name.as_deref())?;
+        ingestion::list_label(&conn, resolved_project, slot)
+    }
+
+    pub fn clear_list_slot(&self, project_id: Option<i64>, slot: ListSlot) -> AppResult<()> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        let mut conn = self.db.lock();
+        ingestion::clear_list_slot(&mut conn, resolved_project, slot)?;
+        Ok(())
+    }
+
+    pub fn swap_list_slots(&self, project_id: Option<i64>) -> AppResult<()> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        let mut conn = self.db.lock();
+        ingestion::swap_list_slots(&mut conn, resolved_project)
+    }
+
+    pub fn list_import_history(
+        &self,
+        project_id: Option<i64>,
+        slot: Option<ListSlot>,
+    ) -> AppResult<Vec<ImportHistoryEntry>> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        let conn = self.db.lock();
+        ingestion::list_import_history(&conn, resolved_project, slot)
+    }
+
+    pub fn raw_rows(
+        &self,
+        project_id: Option<i64>,
+        slot: ListSlot,
+        pagination: ComparisonPagination,
+    ) -> AppResult<RawRowPage> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        let conn = self.db.lock();
+        ingestion::load_raw_rows(&conn, resolved_project, slot, pagination)
+    }
+
+    pub async fn check_drive_freshness(
+        &self,
+        project_id: Option<i64>,
+    ) -> AppResult<Vec<SlotFreshness>> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        let mut report = Vec::new();
+        for slot in [ListSlot::A, ListSlot::B] {
+            let stored = {
+                let conn = self.db.lock();
+                ingestion::linked_drive_file(&conn, resolved_project, slot)?
+            };
+            let Some(stored) = stored else {
+                report.push(SlotFreshness::not_linked(slot));
+                continue;
+            };
+            let live = self.google()?.get_file_metadata(&stored.id).await?;
+            report.push(SlotFreshness::compare(slot, &stored, &live));
+        }
+        Ok(report)
+    }
+
+    /// Re-reads the Drive metadata of a slot's linked file and stores it without
+    /// re-downloading, so freshness checks compare against the current name and checksum.
+    pub async fn refresh_drive_metadata(
+        &self,
+        project_id: Option<i64>,
+        slot: ListSlot,
+    ) -> AppResult<DriveFileMetadata> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        let stored = {
+            let conn = self.db.lock();
+            ingestion::linked_drive_file(&conn, resolved_project, slot)?
+        }
+        .ok_or_else(|| {
+            AppError::Config(format!(
+                "{} is not linked to a Drive file",
+                slot.display_name()
+            ))
+        })?;
+        let live = self.google()?.get_file_metadata(&stored.id).await?;
+        {
+            let conn = self.db.lock();
+            ingestion::persist_drive_selection(&conn, resolved_project, slot, Some(&live))?;
+        }
+        Ok(live)
+    }
+
     pub async fn import_drive_file(
         &self,
         project_id: Option<i64>,
@@ -568,57 +970,263 @@ impl AppState {
             modified_time,
             size,
             md5_checksum,
+            source: None,
         };
-        {
-            let mut conn = self.db.lock();
-            ingestion::persist_drive_selection(
-                &mut conn,
+        let cancel = self.begin_import(resolved_project, slot);
+        let result = self
+            .import_drive_file_inner(
                 resolved_project,
                 slot,
-                Some(&drive_file),
-            )?;
+                drive_file,
+                file_hash.clone(),
+                &cancel,
+            )
+            .await;
+        self.finish_import(resolved_project, slot, &cancel);
+        match result {
+            Ok(summary) => Ok(summary),
+            Err(err) => {
+                self.report_import_failure(slot, file_name, &file_hash, &err);
+                Err(err)
+            }
         }
-        match self
-            .import_drive_file_inner(resolved_project, slot, drive_file, file_hash.clone())
-            .await
-        {
+    }
+
+    /// Downloads both Drive files concurrently; parsing, persistence and normalization still
+    /// serialize on the database and normalizer locks.
+    pub async fn import_both_slots(
+        &self,
+        project_id: Option<i64>,
+        list_a: DriveFileMetadata,
+        list_b: DriveFileMetadata,
+    ) -> AppResult<Vec<ImportSummary>> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        let import = |slot: ListSlot, file: DriveFileMetadata| {
+            self.import_drive_file(
+                Some(resolved_project),
+                slot,
+                file.id,
+                file.name,
+                Some(file.mime_type),
+                file.modified_time,
+                file.size,
+                file.md5_checksum,
+            )
+        };
+        let (summary_a, summary_b) =
+            tokio::join!(import(ListSlot::A, list_a), import(ListSlot::B, list_b));
+        Ok(vec![summary_a?, summary_b?])
+    }
+
+    pub async fn import_local_file(
+        &self,
+        project_id: Option<i64>,
+        slot: ListSlot,
+        path: PathBuf,
+        csv_options: Option<CsvImportOptions>,
+    ) -> AppResult<ImportSummary> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        let file_hash = fingerprint(&path.to_string_lossy());
+        let csv_options = csv_options.unwrap_or_default();
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+        self.notify_progress(ImportProgressPayload::new(
+            slot,
+            "read",
+            "Reading local file",
+            0.0,
+            file_name.clone(),
+        ));
+        let cancel = self.begin_import(resolved_project, slot);
+        let result = self
+            .import_local_file_inner(
+                resolved_project,
+                slot,
+                &path,
+                &file_hash,
+                &csv_options,
+                &cancel,
+            )
+            .await;
+        self.finish_import(resolved_project, slot, &cancel);
+        match result {
             Ok(summary) => Ok(summary),
             Err(err) => {
-                let (summary, details) = describe_import_error(&err);
-                let detail_payload = if details.is_empty() {
-                    None
-                } else {
-                    Some(details.clone())
-                };
-                self.notify_progress(ImportProgressPayload::error(
+                self.report_import_failure(
                     slot,
-                    Some(file_name),
-                    summary.clone(),
-                    detail_payload,
-                ));
-                if let Err(telemetry_err) = self.telemetry.record(
-                    "import_failed",
-                    json!({
-                        "slot": slot.as_tag(),
-                        "file_hash": file_hash.clone(),
-                        "summary": summary.clone(),
-                        "detail_count": details.len(),
-                    }),
-                ) {
-                    warn!(?telemetry_err, "failed to record import_failed telemetry");
-                }
-                warn!(
-                    slot = slot.as_tag(),
-                    file_hash,
-                    summary = summary.as_str(),
-                    detail_count = details.len(),
-                    "drive import failed"
+                    file_name.unwrap_or_else(|| path.to_string_lossy().to_string()),
+                    &file_hash,
+                    &err,
                 );
                 Err(err)
             }
         }
     }
 
+    async fn import_local_file_inner(
+        &self,
+        project_id: i64,
+        slot: ListSlot,
+        path: &Path,
+        file_hash: &str,
+        csv_options: &CsvImportOptions,
+        cancel: &Arc<AtomicBool>,
+    ) -> AppResult<ImportSummary> {
+        let (local_file, download) = ingestion::read_local_file(path)?;
+        self.import_synthetic_file(
+            project_id,
+            slot,
+            local_file,
+            download,
+            ingestion::LOCAL_FILE_SOURCE,
+            file_hash,
+            csv_options,
+            cancel,
+        )
+        .await
+    }
+
+    pub async fn import_kml_string(
+        &self,
+        project_id: Option<i64>,
+        slot: ListSlot,
+        name: String,
+        kml: String,
+    ) -> AppResult<ImportSummary> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        let (pasted_file, download) = ingestion::pasted_kml(&name, &kml);
+        let file_hash = fingerprint(&pasted_file.id);
+        self.notify_progress(ImportProgressPayload::new(
+            slot,
+            "read",
+            "Reading pasted KML",
+            0.0,
+            Some(name.clone()),
+        ));
+        let cancel = self.begin_import(resolved_project, slot);
+        let result = self
+            .import_synthetic_file(
+                resolved_project,
+                slot,
+                pasted_file,
+                download,
+                ingestion::PASTED_KML_SOURCE,
+                &file_hash,
+                &CsvImportOptions::default(),
+                &cancel,
+            )
+            .await;
+        self.finish_import(resolved_project, slot, &cancel);
+        match result {
+            Ok(summary) => Ok(summary),
+            Err(err) => {
+                self.report_import_failure(slot, name, &file_hash, &err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Imports a file that did not come from Drive (a local file or pasted KML) under its
+    /// synthetic metadata; `source` tags the `import_started` telemetry.
+    async fn import_synthetic_file(
+        &self,
+        project_id: i64,
+        slot: ListSlot,
+        file: DriveFileMetadata,
+        download: DownloadedFile,
+        source: &str,
+        file_hash: &str,
+        csv_options: &CsvImportOptions,
+        cancel: &Arc<AtomicBool>,
+    ) -> AppResult<ImportSummary> {
+        if let Err(err) = self.telemetry.record(
+            "import_started",
+            json!({
+                "slot": slot.as_tag(),
+                "file_hash": file_hash,
+                "file_name": file.name.clone(),
+                "file_size": file.size,
+                "checksum": file.md5_checksum,
+                "source": source,
+            }),
+        ) {
+            warn!(?err, "failed to record import_started telemetry");
+        }
+        self.ingest_download(
+            project_id,
+            slot,
+            &file,
+            download,
+            file_hash,
+            csv_options,
+            Some(Arc::clone(cancel)),
+        )
+        .await
+    }
+
+    fn report_import_failure(
+        &self,
+        slot: ListSlot,
+        file_name: String,
+        file_hash: &str,
+        err: &AppError,
+    ) {
+        if let AppError::Cancelled(reason) = err {
+            self.notify_progress(ImportProgressPayload::new(
+                slot,
+                "cancelled",
+                format!("Import cancelled for {}", slot.display_name()),
+                0.0,
+                Some(file_name),
+            ));
+            if let Err(telemetry_err) = self.telemetry.record(
+                "import_cancelled",
+                json!({
+                    "slot": slot.as_tag(),
+                    "file_hash": file_hash,
+                    "reason": reason,
+                }),
+            ) {
+                warn!(
+                    ?telemetry_err,
+                    "failed to record import_cancelled telemetry"
+                );
+            }
+            return;
+        }
+        let (summary, details) = describe_import_error(err);
+        let detail_payload = if details.is_empty() {
+            None
+        } else {
+            Some(details.clone())
+        };
+        self.notify_progress(ImportProgressPayload::error(
+            slot,
+            Some(file_name),
+            summary.clone(),
+            detail_payload,
+        ));
+        if let Err(telemetry_err) = self.telemetry.record(
+            "import_failed",
+            json!({
+                "slot": slot.as_tag(),
+                "file_hash": file_hash,
+                "summary": summary.clone(),
+                "detail_count": details.len(),
+            }),
+        ) {
+            warn!(?telemetry_err, "failed to record import_failed telemetry");
+        }
+        warn!(
+            slot = slot.as_tag(),
+            file_hash,
+            summary = summary.as_str(),
+            detail_count = details.len(),
+            "import failed"
+        );
+    }
+
     fn record_signin_success(&self, identity: &GoogleIdentity) {
         if let Err(err) = self.telemetry.record(
             "signin_success",
@@ -661,15 +1269,58 @@ impl AppState {
     ) -> AppResult<Vec<NormalizationStats>> {
         let resolved_project = self.resolve_project_id(project_id)?;
         let targets = slots.unwrap_or_else(|| vec![ListSlot::A, ListSlot::B]);
-        let cancel_flag = Arc::new(AtomicBool::new(false));
-        {
-            let mut guard = self.refresh_cancel_token.lock();
-            *guard = Some(cancel_flag.clone());
+        self.run_place_refresh(resolved_project, targets, RefreshScope::All, request_id)
+            .await
+    }
+
+    pub async fn retry_pending_rows(
+        &self,
+        project_id: Option<i64>,
+        slot: ListSlot,
+        request_id: Option<String>,
+    ) -> AppResult<NormalizationStats> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        let mut stats = self
+            .run_place_refresh(
+                resolved_project,
+                vec![slot],
+                RefreshScope::Pending,
+                request_id,
+            )
+            .await?;
+        Ok(stats.remove(0))
+    }
+
+    /// Previews a refresh of one slot: cache hits, stale entries and projected Places calls,
+    /// without calling Google or touching the database.
+    pub async fn normalize_slot_dry_run(
+        &self,
+        project_id: Option<i64>,
+        slot: ListSlot,
+    ) -> AppResult<NormalizationStats> {
+        let resolved_project = self.resolve_project_id(project_id)?;
+        self.places
+            .normalize_slot_dry_run(resolved_project, slot)
+            .await
+    }
+
+    async fn run_place_refresh(
+        &self,
+        resolved_project: i64,
+        targets: Vec<ListSlot>,
+        scope: RefreshScope,
+        request_id: Option<String>,
+    ) -> AppResult<Vec<NormalizationStats>> {
+        if self.google.as_ref().is_some_and(|svc| svc.is_signed_out()) {
+            return Err(AppError::Config(
+                "google sign-in required before refreshing places".into(),
+            ));
         }
+        let run = self.refresh_runs.begin()?;
         let rate_limit = self.places.rate_limit_qps();
-        let handle = self.handle.clone();
+        let events = Arc::clone(&self.events);
         let request_token = request_id.clone();
-        let notifier = Arc::new(move |progress: NormalizationProgress| {
+        let notifier: ProgressObserver = Arc::new(move |progress: NormalizationProgress| {
             let payload = RefreshProgressPayload {
                 slot: progress.slot.as_tag().to_string(),
                 request_id: request_token.clone(),
@@ -679,39 +1330,63 @@ impl AppState {
                 resolved: progress.resolved,
                 pending: progress.total_rows.saturating_sub(progress.processed),
                 rate_limit_qps: rate_limit,
-                message: format!(
-                    "Refreshing {} ({}/{})",
-                    progress.slot.display_name(),
-                    progress.processed,
-                    progress.total_rows
-                ),
+                resumed_from: progress.resumed_from,
+                message: match progress.resumed_from {
+                    Some(previous) => format!(
+                        "Refreshing {} ({}/{}, resumed from {})",
+                        progress.slot.display_name(),
+                        progress.processed,
+                        progress.total_rows,
+                        previous
+                    ),
+                    None => format!(
+                        "Refreshing {} ({}/{})",
+                        progress.slot.display_name(),
+                        progress.processed,
+                        progress.total_rows
+                    ),
+                },
             };
-            if let Err(err) = handle.emit("refresh://progress", payload) {
-                warn!(?err, "failed to emit refresh progress");
-            }
+            emit_event(&events, "refresh://progress", payload);
         });
         let result = self
             .places
             .refresh_slots(
                 resolved_project,
                 &targets,
-                Some(notifier),
-                Some(cancel_flag.clone()),
+                scope,
+                Some(throttle_progress(
+                    notifier,
+                    self.config.refresh_progress_every_rows,
+                    Duration::from_millis(self.config.refresh_progress_interval_ms),
+                )),
+                Some(run.cancel_flag()),
             )
             .await;
-        {
-            let mut guard = self.refresh_cancel_token.lock();
-            guard.take();
-        }
+        let cancelled = run.is_cancelled();
+        drop(run);
         match result {
             Ok(stats) => {
-                let cancelled = cancel_flag.load(AtomicOrdering::SeqCst);
                 for entry in &stats {
                     let stage = if cancelled && entry.unresolved > 0 {
                         "cancelled"
                     } else {
                         "complete"
                     };
+                    if stage == "cancelled" || entry.resumed_from.is_some() {
+                        if let Err(err) = self.telemetry.record(
+                            "refresh_interrupted",
+                            json!({
+                                "slot": entry.slot.as_tag(),
+                                "stage": stage,
+                                "processed": entry.total_rows.saturating_sub(entry.unresolved),
+                                "total_rows": entry.total_rows,
+                                "resumed_from": entry.resumed_from,
+                            }),
+                        ) {
+                            warn!(?err, "failed to record refresh_interrupted telemetry");
+                        }
+                    }
                     self.notify_refresh_progress(RefreshProgressPayload {
                         slot: entry.slot.as_tag().to_string(),
                         request_id: request_id.clone(),
@@ -721,6 +1396,7 @@ impl AppState {
                         resolved: entry.resolved,
                         pending: entry.unresolved,
                         rate_limit_qps: rate_limit,
+                        resumed_from: entry.resumed_from,
                         message: if stage == "complete" {
                             format!(
                                 "Refreshed {} places for {}",
@@ -753,6 +1429,7 @@ impl AppState {
                     resolved: 0,
                     pending: 0,
                     rate_limit_qps: rate_limit,
+                    resumed_from: None,
                     message: sanitize_error_copy(&err.to_string()),
                 });
                 Err(err)
@@ -766,6 +1443,7 @@ impl AppState {
         slot: ListSlot,
         drive_file: DriveFileMetadata,
         file_hash: String,
+        cancel: &Arc<AtomicBool>,
     ) -> AppResult<ImportSummary> {
         if let Err(err) = self.telemetry.record(
             "drive_file_selected",
@@ -822,20 +1500,66 @@ impl AppState {
         };
 
         let downloader = self.google()?.clone();
-        let download = downloader
-            .download_file(
+        let temp_download = downloader
+            .download_file_to_disk(
                 &drive_file.id,
                 Some(&drive_file.mime_type),
                 expected_bytes,
                 drive_file.md5_checksum.as_deref(),
                 &mut progress_cb,
+                Some(cancel.as_ref()),
+            )
+            .await?;
+        let download = DownloadedFile {
+            bytes: fs::read(&temp_download.path)?,
+            checksum_md5: temp_download.checksum_md5,
+            received_bytes: temp_download.received_bytes,
+            expected_bytes: temp_download.expected_bytes,
+        };
+
+        let summary = self
+            .ingest_download(
+                project_id,
+                slot,
+                &drive_file,
+                download,
+                &file_hash,
+                &CsvImportOptions::default(),
+                Some(Arc::clone(cancel)),
             )
             .await?;
 
+        // Large imports leave a big -wal file behind; fold it back once the writes are done.
+        if let Err(err) = self.checkpoint_wal().await {
+            warn!(?err, "failed to checkpoint WAL after import");
+        }
+        Ok(summary)
+    }
+
+    async fn ingest_download(
+        &self,
+        project_id: i64,
+        slot: ListSlot,
+        drive_file: &DriveFileMetadata,
+        download: DownloadedFile,
+        file_hash: &str,
+        csv_options: &CsvImportOptions,
+        cancel: Option<Arc<AtomicBool>>,
+    ) -> AppResult<ImportSummary> {
+        let is_cancelled = || {
+            cancel
+                .as_ref()
+                .is_some_and(|flag| flag.load(Ordering::SeqCst))
+        };
+        let expected_bytes = drive_file.size;
+        let progress_label = drive_file.name.clone();
         let mut parse_progress = ImportProgressPayload::new(
             slot,
             "parse",
-            "Parsing KML data",
+            format!(
+                "Parsing {} data",
+                ingestion::format_label(&drive_file.mime_type)
+            ),
             0.65,
             Some(drive_file.name.clone()),
         );
@@ -844,7 +1568,18 @@ impl AppState {
         parse_progress.checksum = Some(download.checksum_md5.clone());
         self.notify_progress(parse_progress);
 
-        let parsed = parse_kml(&download.bytes)?;
+        let mut parsed = ingestion::parse_import(
+            &download.bytes,
+            &drive_file.mime_type,
+            csv_options,
+            &self.config.place_id_fields,
+            self.config.max_import_bytes,
+        )?;
+        parsed.ensure_importable()?;
+        parsed.enforce_row_limit(
+            self.config.max_import_rows,
+            self.config.truncate_oversized_imports,
+        )?;
         let total_rows = parsed.rows.len();
         let rejected_rows = parsed.rejected.len();
         let persist_message = if rejected_rows > 0 {
@@ -864,6 +1599,16 @@ impl AppState {
         );
         persist_progress.total_rows = Some(total_rows);
         persist_progress.rejected_rows = Some(rejected_rows);
+        if rejected_rows > 0 {
+            persist_progress.details = Some(
+                parsed
+                    .rejected
+                    .iter()
+                    .take(5)
+                    .map(|entry| format!("{}: {}", entry.location(), entry.message))
+                    .collect(),
+            );
+        }
         persist_progress.bytes_downloaded = Some(download.received_bytes);
         persist_progress.expected_bytes = download.expected_bytes.or(expected_bytes);
         persist_progress.checksum = Some(download.checksum_md5.clone());
@@ -876,14 +1621,21 @@ impl AppState {
                 .take(3)
                 .map(|entry| entry.message.clone())
                 .collect();
+            let positions: Vec<usize> = parsed
+                .rejected
+                .iter()
+                .take(3)
+                .map(|entry| entry.placemark_index)
+                .collect();
             if let Err(err) = self.telemetry.record(
                 "kml_rows_rejected",
                 json!({
                     "slot": slot.as_tag(),
-                    "file_hash": file_hash.clone(),
+                    "file_hash": file_hash,
                     "rejected": rejected_rows,
                     "kept": total_rows,
                     "examples": examples,
+                    "positions": positions,
                 }),
             ) {
                 warn!(?err, "failed to record kml_rows_rejected telemetry");
@@ -896,13 +1648,18 @@ impl AppState {
             );
         }
 
+        if is_cancelled() {
+            return Err(AppError::Cancelled(
+                "import cancelled before saving rows".into(),
+            ));
+        }
         let summary = {
             let mut conn = self.db.lock();
-            ingestion::persist_rows_with_progress(
+            let summary = ingestion::persist_rows_with_progress(
                 &mut conn,
                 project_id,
                 slot,
-                &drive_file,
+                drive_file,
                 &parsed.rows,
                 Some(|processed, total| {
                     let pct = if total == 0 {
@@ -921,7 +1678,17 @@ impl AppState {
                     payload.total_rows = Some(total);
                     self.notify_progress(payload);
                 }),
-            )?
+            )?;
+            ingestion::record_import_history(
+                &conn,
+                project_id,
+                slot,
+                drive_file,
+                summary.row_count,
+                rejected_rows,
+                Some(&download.checksum_md5),
+            )?;
+            summary
         };
 
         enqueue_place_hashes(&self.telemetry, slot, &parsed.rows)?;
@@ -936,9 +1703,27 @@ impl AppState {
 
         let normalization = self
             .places
-            .normalize_slot(project_id, slot, None, None)
+            .normalize_slot(project_id, slot, None, cancel.clone())
             .await?;
 
+        // Rows are already saved once matching starts, so a cancel here keeps them and leaves
+        // the unmatched ones pending for a later retry.
+        if is_cancelled() {
+            self.notify_progress(ImportProgressPayload::new(
+                slot,
+                "cancelled",
+                format!(
+                    "Imported {} rows for {}; cancelled with {} places left to match",
+                    parsed.rows.len(),
+                    slot.display_name(),
+                    normalization.unresolved
+                ),
+                1.0,
+                Some(drive_file.name.clone()),
+            ));
+            return Ok(summary);
+        }
+
         self.notify_progress(ImportProgressPayload::new(
             slot,
             "complete",
@@ -985,15 +1770,11 @@ impl AppState {
     }
 
     fn notify_progress(&self, payload: ImportProgressPayload) {
-        if let Err(err) = self.handle.emit("import://progress", payload) {
-            warn!(?err, "failed to emit import progress");
-        }
+        emit_event(&self.events, "import://progress", payload);
     }
 
     fn notify_refresh_progress(&self, payload: RefreshProgressPayload) {
-        if let Err(err) = self.handle.emit("refresh://progress", payload) {
-            warn!(?err, "failed to emit refresh progress");
-        }
+        emit_event(&self.events, "refresh://progress", payload);
     }
 
     fn resolve_project_id(&self, project_id: Option<i64>) -> AppResult<i64> {
@@ -1012,32 +1793,144 @@ impl AppState {
         &self,
         payload: UpdateRuntimeSettingsPayload,
     ) -> AppResult<RuntimeSettings> {
-        let sanitized = payload.sanitized();
+        let sanitized = payload.sanitized()?;
         {
             let mut settings = self.settings.lock();
-            let previous_enabled = settings.telemetry_enabled;
-            let previous_qps = settings.places_rate_limit_qps;
+            let previous = settings.runtime_profile();
             settings.apply_patch(&sanitized);
             settings.persist(&self.settings_path)?;
-            if settings.telemetry_enabled != previous_enabled {
-                self.telemetry.set_enabled(settings.telemetry_enabled);
+            self.apply_settings_changes(&previous, &settings.runtime_profile());
+        }
+        Ok(self.publish_settings())
+    }
+
+    pub fn reset_settings(&self, regenerate_salt: bool) -> AppResult<RuntimeSettings> {
+        {
+            let mut settings = self.settings.lock();
+            let previous = settings.runtime_profile();
+            settings.reset(&self.config, regenerate_salt);
+            settings.persist(&self.settings_path)?;
+            self.apply_settings_changes(&previous, &settings.runtime_profile());
+        }
+        Ok(self.publish_settings())
+    }
+
+    fn publish_settings(&self) -> RuntimeSettings {
+        self.runtime_settings()
+            .publish(|event, payload| emit_event(&self.events, event, payload))
+    }
+
+    fn apply_settings_changes(&self, previous: &RuntimeSettings, current: &RuntimeSettings) {
+        if current.telemetry_enabled != previous.telemetry_enabled {
+            self.telemetry.set_enabled(current.telemetry_enabled);
+        }
+        if current.telemetry_salt != previous.telemetry_salt {
+            self.telemetry.set_salt(&current.telemetry_salt);
+        }
+        if current.places_rate_limit_qps != previous.places_rate_limit_qps {
+            self.places.set_rate_limit(current.places_rate_limit_qps);
+        }
+        if current.normalization_cache_ttl_hours != previous.normalization_cache_ttl_hours {
+            self.places
+                .set_cache_ttl(current.normalization_cache_ttl_hours);
+        }
+        if current.offline != previous.offline {
+            self.places.set_offline(current.offline);
+            if let Some(google) = self.google.as_ref() {
+                google.set_offline(current.offline);
             }
-            if settings.places_rate_limit_qps != previous_qps {
-                self.places.set_rate_limit(settings.places_rate_limit_qps);
+        }
+    }
+
+    pub fn clear_normalization_cache(&self, project_id: Option<i64>) -> AppResult<usize> {
+        if let Some(candidate) = project_id {
+            let conn = self.db.lock();
+            projects::project_by_id(&conn, candidate)?;
+        }
+        self.places.clear_cache(project_id)
+    }
+
+    pub fn purge_stale_cache(&self) -> AppResult<usize> {
+        self.places.purge_stale_cache()
+    }
+
+    pub async fn repair_schema(&self) -> AppResult<SchemaRepairReport> {
+        db::run_blocking(&self.db, |conn| db::repair_schema(conn)).await
+    }
+
+    pub async fn checkpoint_wal(&self) -> AppResult<WalCheckpoint> {
+        db::run_blocking(&self.db, |conn| db::checkpoint_wal(conn)).await
+    }
+
+    fn begin_import(&self, project_id: i64, slot: ListSlot) -> Arc<AtomicBool> {
+        let flag = Arc::new(AtomicBool::new(false));
+        self.import_cancels
+            .lock()
+            .insert((project_id, slot), Arc::clone(&flag));
+        flag
+    }
+
+    fn finish_import(&self, project_id: i64, slot: ListSlot, flag: &Arc<AtomicBool>) {
+        let mut active = self.import_cancels.lock();
+        let key = (project_id, slot);
+        if active
+            .get(&key)
+            .is_some_and(|current| Arc::ptr_eq(current, flag))
+        {
+            active.remove(&key);
+        }
+    }
+
+    /// Cancels the project's import running for `slot`, or every import running in the
+    /// project when `slot` is `None`. Returns whether any import was signalled.
+    pub fn cancel_import(
+        &self,
+        project_id: Option<i64>,
+        slot: Option<ListSlot>,
+    ) -> AppResult<bool> {
+        let project_id = self.resolve_project_id(project_id)?;
+        let active = self.import_cancels.lock();
+        let mut signalled = false;
+        for ((candidate_project, candidate_slot), flag) in active.iter() {
+            if *candidate_project == project_id && (slot.is_none() || slot == Some(*candidate_slot))
+            {
+                flag.store(true, Ordering::SeqCst);
+                signalled = true;
             }
         }
-        Ok(self.runtime_settings())
+        Ok(signalled)
     }
 
     pub fn cancel_refresh_queue(&self) -> AppResult<()> {
-        if let Some(flag) = self.refresh_cancel_token.lock().clone() {
-            flag.store(true, AtomicOrdering::SeqCst);
-        }
+        self.refresh_runs.cancel();
         Ok(())
     }
 }
 
-fn export_csv(path: &Path, rows: &[&PlaceComparisonRow]) -> AppResult<()> {
+/// Prefixes text cells that spreadsheet apps would evaluate as formulas with a single quote,
+/// per OWASP's CSV injection guidance. Numeric columns are written as-is.
+fn csv_text_cell(value: &str, neutralize_formulas: bool) -> String {
+    if neutralize_formulas && value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    }
+}
+
+fn list_names(row: &PlaceComparisonRow, lists: &ComparisonLists) -> Vec<String> {
+    row.lists
+        .iter()
+        .map(|slot| lists.label(*slot).to_string())
+        .collect()
+}
+
+fn export_csv(
+    path: &Path,
+    rows: &[&PlaceComparisonRow],
+    lists: &ComparisonLists,
+    neutralize_formulas: bool,
+) -> AppResult<()> {
+    let text = |value: &str| csv_text_cell(value, neutralize_formulas);
     let mut writer = WriterBuilder::new().from_path(path)?;
     writer.write_record([
         "place_id",
@@ -1047,6 +1940,14 @@ fn export_csv(path: &Path, rows: &[&PlaceComparisonRow]) -> AppResult<()> {
         "lng",
         "types",
         "lists",
+        "last_checked_at",
+        "resolution_source",
+        "rating",
+        "user_rating_count",
+        "phone_number",
+        "website_uri",
+        "opening_hours",
+        "list_names",
     ])?;
     for row in rows {
         let lat = row.lat.to_string();
@@ -1058,21 +1959,46 @@ fn export_csv(path: &Path, rows: &[&PlaceComparisonRow]) -> AppResult<()> {
             .map(|slot| slot.as_tag())
             .collect::<Vec<_>>()
             .join("|");
+        let enrichment = &row.enrichment;
         writer.write_record([
-            row.place_id.as_str(),
-            row.name.as_str(),
-            row.formatted_address.as_deref().unwrap_or(""),
-            lat.as_str(),
-            lng.as_str(),
-            types_joined.as_str(),
-            lists_joined.as_str(),
+            text(&row.place_id),
+            text(&row.name),
+            text(row.formatted_address.as_deref().unwrap_or("")),
+            lat,
+            lng,
+            text(&types_joined),
+            lists_joined,
+            row.last_checked_at.clone().unwrap_or_default(),
+            row.resolution_source.clone().unwrap_or_default(),
+            enrichment
+                .rating
+                .map(|value| value.to_string())
+                .unwrap_or_default(),
+            enrichment
+                .user_rating_count
+                .map(|value| value.to_string())
+                .unwrap_or_default(),
+            text(enrichment.phone_number.as_deref().unwrap_or("")),
+            text(enrichment.website_uri.as_deref().unwrap_or("")),
+            text(
+                &enrichment
+                    .opening_hours
+                    .clone()
+                    .unwrap_or_default()
+                    .join("; "),
+            ),
+            text(&list_names(row, lists).join("|")),
         ])?;
     }
     writer.flush()?;
     Ok(())
 }
 
-fn export_json(path: &Path, rows: &[&PlaceComparisonRow]) -> AppResult<()> {
+fn export_json(
+    path: &Path,
+    rows: &[&PlaceComparisonRow],
+    lists: &ComparisonLists,
+) -> AppResult<()> {
     let payload: Vec<_> = rows
         .iter()
         .map(|row| {
@@ -1084,6 +2010,14 @@ fn export_json(path: &Path, rows: &[&PlaceComparisonRow]) -> AppResult<()> {
                 "lng": row.lng,
                 "types": row.types,
                 "lists": row.lists.iter().map(|slot| slot.as_tag()).collect::<Vec<_>>(),
+                "last_checked_at": row.last_checked_at,
+                "resolution_source": row.resolution_source,
+                "rating": row.enrichment.rating,
+                "user_rating_count": row.enrichment.user_rating_count,
+                "phone_number": row.enrichment.phone_number,
+                "website_uri": row.enrichment.website_uri,
+                "opening_hours": row.enrichment.opening_hours,
+                "list_names": list_names(row, lists),
             })
         })
         .collect();
@@ -1092,9 +2026,24 @@ fn export_json(path: &Path, rows: &[&PlaceComparisonRow]) -> AppResult<()> {
     Ok(())
 }
 
+fn export_geojson(
+    path: &Path,
+    rows: &[&PlaceComparisonRow],
+    segment: ComparisonSegment,
+) -> AppResult<()> {
+    let features = rows
+        .iter()
+        .map(|row| comparison::place_feature(row, segment))
+        .collect();
+    let serialized = serde_json::to_vec_pretty(&comparison::feature_collection(features))?;
+    fs::write(path, serialized)?;
+    Ok(())
+}
+
 enum ExportFormat {
     Csv,
     Json,
+    GeoJson,
 }
 
 impl ExportFormat {
@@ -1102,6 +2051,7 @@ impl ExportFormat {
         match value.to_ascii_lowercase().as_str() {
             "csv" => Ok(Self::Csv),
             "json" => Ok(Self::Json),
+            "geojson" => Ok(Self::GeoJson),
             other => Err(AppError::Config(format!(
                 "unsupported export format: {other}"
             ))),
@@ -1112,6 +2062,7 @@ impl ExportFormat {
         match self {
             ExportFormat::Csv => "csv",
             ExportFormat::Json => "json",
+            ExportFormat::GeoJson => "geojson",
         }
     }
 }
@@ -1250,6 +2201,7 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::foundation_health,
             commands::record_telemetry_event,
+            commands::read_telemetry_events,
             commands::google_start_device_flow,
             commands::google_complete_sign_in,
             commands::google_start_loopback_flow,
@@ -1258,21 +2210,186 @@ pub fn run() {
             commands::google_keepalive,
             commands::google_refresh_status,
             commands::google_sign_out,
+            commands::google_list_accounts,
+            commands::google_switch_account,
             commands::drive_list_kml_files,
             commands::drive_import_kml,
+            commands::import_both_slots,
+            commands::check_drive_freshness,
+            commands::refresh_drive_metadata,
+            commands::import_local_file,
+            commands::import_kml_string,
             commands::drive_save_selection,
+            commands::clear_list_slot,
+            commands::rename_list,
+            commands::swap_list_slots,
+            commands::list_import_history,
+            commands::get_raw_rows,
             commands::refresh_place_details,
+            commands::retry_pending_rows,
+            commands::normalize_slot_dry_run,
             commands::cancel_refresh_queue,
+            commands::cancel_import,
+            commands::purge_stale_cache,
+            commands::repair_schema,
+            commands::checkpoint_wal,
+            commands::clear_normalization_cache,
             commands::compare_lists,
             commands::comparison_segment_page,
+            commands::segment_type_facets,
+            commands::segment_in_bounds,
+            commands::segment_geojson,
+            commands::segment_bounds,
+            commands::segment_nearest,
+            commands::loose_only_sets,
+            commands::place_photo_url,
+            commands::overlap_pairing,
+            commands::set_project_rate_limit,
+            commands::comparison_diff,
+            commands::find_duplicates,
+            commands::compare_slots,
+            commands::list_pending_rows,
             commands::list_comparison_projects,
+            commands::comparison_run_history,
             commands::create_comparison_project,
             commands::rename_comparison_project,
             commands::set_active_comparison_project,
+            commands::ensure_single_active_project,
+            commands::delete_comparison_project,
+            commands::duplicate_comparison_project,
+            commands::export_project,
+            commands::import_project,
             commands::map_style_descriptor,
             commands::export_comparison_segment,
-            commands::update_runtime_settings
+            commands::update_runtime_settings,
+            commands::reset_settings
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                if let Some(state) = handle.try_state::<AppState>() {
+                    state.shutdown();
+                }
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn place(name: &str) -> PlaceComparisonRow {
+        PlaceComparisonRow {
+            place_id: "place-1".into(),
+            name: name.into(),
+            formatted_address: Some("@home".into()),
+            lat: -33.5,
+            lng: 151.2,
+            types: vec!["park".into()],
+            lists: vec![ListSlot::A],
+            fuzzy_match: None,
+            last_checked_at: Some("2026-01-02 03:04:05".into()),
+            resolution_source: Some("synthetic".into()),
+            enrichment: PlaceEnrichment::default(),
+        }
+    }
+
+    fn lists() -> ComparisonLists {
+        ComparisonLists {
+            list_a_id: Some(1),
+            list_b_id: None,
+            list_a_name: "My picks".into(),
+            list_b_name: ListSlot::B.display_name().into(),
+        }
+    }
+
+    #[test]
+    fn csv_export_neutralizes_formula_cells() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.csv");
+        let row = place("=HYPERLINK(\"https://evil.example\",\"Click\")");
+
+        export_csv(&path, &[&row], &lists(), true).unwrap();
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        let record = reader.records().next().unwrap().unwrap();
+        assert_eq!(
+            &record[1],
+            "'=HYPERLINK(\"https://evil.example\",\"Click\")"
+        );
+        assert_eq!(&record[2], "'@home");
+        assert_eq!(&record[3], "-33.5");
+        assert_eq!(&record[7], "2026-01-02 03:04:05");
+        assert_eq!(&record[8], "synthetic");
+        assert_eq!(&record[14], "My picks");
+
+        export_csv(&path, &[&row], &lists(), false).unwrap();
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        let record = reader.records().next().unwrap().unwrap();
+        assert_eq!(&record[1], "=HYPERLINK(\"https://evil.example\",\"Click\")");
+    }
+
+    #[tokio::test]
+    async fn http_timeouts_serialize_as_network_command_errors() {
+        use httptest::responders::{delay_and_then, status_code};
+        use httptest::{matchers::request, Expectation, Server};
+
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/slow"))
+                .respond_with(delay_and_then(Duration::from_millis(500), status_code(200))),
+        );
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let err = client
+            .get(server.url_str("/slow"))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout());
+
+        let payload = serde_json::to_value(CommandError::from(AppError::Http(err))).unwrap();
+        assert_eq!(payload["kind"], "network");
+        assert!(!payload["message"].as_str().unwrap().is_empty());
+        let details: Vec<&str> = payload["details"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        assert_eq!(
+            details,
+            vec!["The request timed out before the server responded."]
+        );
+
+        let config = serde_json::to_value(CommandError::from(AppError::Config(
+            "unsupported comparison segment: nope".into(),
+        )))
+        .unwrap();
+        assert_eq!(config["kind"], "config");
+        assert_eq!(config["message"], "unsupported comparison segment: nope");
+    }
+
+    #[test]
+    fn non_import_errors_serialize_without_import_wording() {
+        let database = serde_json::to_value(CommandError::from(AppError::Database(
+            rusqlite::Error::QueryReturnedNoRows,
+        )))
+        .unwrap();
+        assert_eq!(database["kind"], "database");
+        assert_eq!(
+            database["details"],
+            json!(["SQLite error: Query returned no rows"])
+        );
+
+        let cancelled = serde_json::to_value(CommandError::from(AppError::Cancelled(
+            "refresh cancelled".into(),
+        )))
+        .unwrap();
+        assert_eq!(cancelled["kind"], "cancelled");
+        assert_eq!(cancelled["message"], "refresh cancelled");
+        assert_eq!(cancelled["details"], json!([]));
+    }
 }
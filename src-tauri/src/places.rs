@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -7,7 +8,7 @@ use base64::Engine;
 use parking_lot::Mutex;
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use reqwest::StatusCode;
-use rusqlite::{Connection, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension};
 use secrecy::{ExposeSecret, SecretString};
 use serde::Serialize;
 use sha2::{Digest, Sha256};
@@ -15,13 +16,21 @@ use tokio::sync::Mutex as AsyncMutex;
 use tokio::time::{sleep, Instant};
 use tracing::{trace, warn};
 
+use crate::comparison;
 use crate::config::AppConfig;
+use crate::db;
 use crate::errors::{AppError, AppResult};
 use crate::ingestion::{ListSlot, NormalizedRow, ParsedRow};
 
 const GEO_EPSILON: f64 = 0.00001;
-const MAX_ATTEMPTS: u32 = 5;
-const BASE_BACKOFF_MS: u64 = 250;
+const MATCH_NAME_WEIGHT: f64 = 0.6;
+const MATCH_DISTANCE_CEILING_M: f64 = 2_000.0;
+const PLACES_API_BASE: &str = "https://places.googleapis.com/v1";
+const DEFAULT_PHOTO_MAX_WIDTH_PX: u32 = 400;
+const MAX_PHOTO_WIDTH_PX: u32 = 4_800;
+const SYNTHETIC_PLACE_PREFIX: &str = "synthetic_";
+/// Rows between refresh progress writes; an interrupted run resumes from the last write.
+const REFRESH_STATE_CHUNK_ROWS: usize = 25;
 
 fn cache_ttl_from_hours(hours: u64) -> Option<Duration> {
     if hours == 0 {
@@ -31,6 +40,39 @@ fn cache_ttl_from_hours(hours: u64) -> Option<Duration> {
     }
 }
 
+/// How often and how patiently a failed Places lookup is retried.
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    max_attempts: u32,
+    base_backoff_ms: u64,
+    max_backoff_ms: u64,
+}
+
+impl RetryPolicy {
+    fn from_config(config: &AppConfig) -> Self {
+        Self {
+            max_attempts: config.places_retry_max_attempts.max(1),
+            base_backoff_ms: config.places_retry_base_backoff_ms,
+            max_backoff_ms: config.places_retry_max_backoff_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        use crate::config::{
+            DEFAULT_PLACES_RETRY_BASE_BACKOFF_MS, DEFAULT_PLACES_RETRY_MAX_ATTEMPTS,
+            DEFAULT_PLACES_RETRY_MAX_BACKOFF_MS,
+        };
+        Self {
+            max_attempts: DEFAULT_PLACES_RETRY_MAX_ATTEMPTS,
+            base_backoff_ms: DEFAULT_PLACES_RETRY_BASE_BACKOFF_MS,
+            max_backoff_ms: DEFAULT_PLACES_RETRY_MAX_BACKOFF_MS,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct RawRow {
     source_hash: String,
@@ -48,6 +90,10 @@ pub struct NormalizationStats {
     pub resolved: usize,
     pub unresolved: usize,
     pub places_counters: PlacesCountersSnapshot,
+    /// Places QPS in effect for this run, including any project override.
+    pub rate_limit_qps: u32,
+    /// Rows processed by an earlier run of this slot that was cancelled or interrupted.
+    pub resumed_from: Option<usize>,
 }
 
 impl NormalizationStats {
@@ -62,6 +108,8 @@ impl NormalizationStats {
             resolved: 0,
             unresolved: 0,
             places_counters: PlacesCountersSnapshot::default(),
+            rate_limit_qps: 0,
+            resumed_from: None,
         }
     }
 
@@ -96,6 +144,116 @@ pub struct NormalizationProgress {
     pub total_rows: usize,
     pub processed: usize,
     pub resolved: usize,
+    pub resumed_from: Option<usize>,
+}
+
+pub type ProgressObserver = Arc<dyn Fn(NormalizationProgress) + Send + Sync>;
+
+/// Which rows of a slot a normalization pass visits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshScope {
+    All,
+    /// Only rows without a cached place, leaving existing assignments untouched.
+    Pending,
+}
+
+/// Coalesces per-row progress: forwards a tick once `every_rows` rows or `interval` have passed
+/// since the last forwarded one, and always forwards a slot's final row.
+pub fn throttle_progress(
+    inner: ProgressObserver,
+    every_rows: usize,
+    interval: Duration,
+) -> ProgressObserver {
+    let last: Mutex<Option<(ListSlot, usize, Instant)>> = Mutex::new(None);
+    Arc::new(move |progress: NormalizationProgress| {
+        let now = Instant::now();
+        let forward = {
+            let mut guard = last.lock();
+            let (last_processed, last_at) = match *guard {
+                Some((slot, processed, at))
+                    if slot == progress.slot && processed <= progress.processed =>
+                {
+                    (processed, at)
+                }
+                _ => (0, now),
+            };
+            let forward = progress.processed >= progress.total_rows
+                || progress.processed - last_processed >= every_rows
+                || now.duration_since(last_at) >= interval;
+            *guard = Some(if forward {
+                (progress.slot, progress.processed, now)
+            } else {
+                (progress.slot, last_processed, last_at)
+            });
+            forward
+        };
+        if forward {
+            inner(progress);
+        }
+    })
+}
+
+/// Tracks the one place refresh allowed to run at a time, so cancellation always reaches the
+/// run that is actually in flight. Clones share the same slot.
+#[derive(Clone, Default)]
+pub struct RefreshRegistry {
+    active: Arc<Mutex<Option<Arc<AtomicBool>>>>,
+}
+
+impl RefreshRegistry {
+    pub fn begin(&self) -> AppResult<RefreshRun> {
+        let mut guard = self.active.lock();
+        if guard.is_some() {
+            return Err(AppError::Config(
+                "a place refresh is already running; cancel it before starting another".into(),
+            ));
+        }
+        let flag = Arc::new(AtomicBool::new(false));
+        *guard = Some(flag.clone());
+        Ok(RefreshRun {
+            flag,
+            active: Arc::clone(&self.active),
+        })
+    }
+
+    /// Returns whether a running refresh was signalled.
+    pub fn cancel(&self) -> bool {
+        match self.active.lock().as_ref() {
+            Some(flag) => {
+                flag.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Handle for an active refresh; dropping it frees the registry for the next run.
+pub struct RefreshRun {
+    flag: Arc<AtomicBool>,
+    active: Arc<Mutex<Option<Arc<AtomicBool>>>>,
+}
+
+impl RefreshRun {
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.flag)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for RefreshRun {
+    fn drop(&mut self) {
+        let mut guard = self.active.lock();
+        if guard
+            .as_ref()
+            .is_some_and(|flag| Arc::ptr_eq(flag, &self.flag))
+        {
+            guard.take();
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -123,6 +281,34 @@ enum ResolutionSource {
     Cache,
     PlacesTable,
     Api,
+    /// Resolved to a place minted by the offline fallback rather than the Places API.
+    Synthetic,
+}
+
+impl ResolutionSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ResolutionSource::Provided => "provided",
+            ResolutionSource::Cache => "cache",
+            ResolutionSource::PlacesTable => "places_table",
+            ResolutionSource::Api => "api",
+            ResolutionSource::Synthetic => "synthetic",
+        }
+    }
+}
+
+/// A resolved row waiting to be written by `commit_assignments`.
+struct Assignment {
+    source_hash: String,
+    details: PlaceDetails,
+    source: ResolutionSource,
+}
+
+/// Outcome of the offline part of resolving a row.
+enum LocalResolution {
+    Resolved(NormalizationResult),
+    /// Carries the cache lookup so an API result can still be counted as a miss or stale hit.
+    NeedsApi(CacheOutcome),
 }
 
 #[derive(Debug, Clone)]
@@ -138,17 +324,58 @@ pub struct PlaceDetails {
     pub place_id: String,
     pub name: String,
     pub formatted_address: Option<String>,
-    pub lat: f64,
-    pub lng: f64,
+    /// `(lat, lng)`; `None` when the source did not report a location, so a real place at
+    /// 0,0 is never mistaken for a missing one.
+    pub location: Option<(f64, f64)>,
     pub types: Vec<String>,
+    /// How well a text-search candidate matches the queried row, from 0 to 1; `None` when the
+    /// place did not come from a search.
+    pub confidence: Option<f64>,
+    /// Places API v1 resource name of the first photo, e.g. `places/{id}/photos/{ref}`.
+    pub photo_name: Option<String>,
+    pub enrichment: PlaceEnrichment,
+}
+
+/// Optional Places details beyond identity and location. Each field stays `None` when the API
+/// did not report it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PlaceEnrichment {
+    pub rating: Option<f64>,
+    pub user_rating_count: Option<u32>,
+    pub phone_number: Option<String>,
+    pub website_uri: Option<String>,
+    /// One line per weekday, from `regularOpeningHours.weekdayDescriptions`.
+    pub opening_hours: Option<Vec<String>>,
+}
+
+/// `places` columns read by [`PlaceEnrichment::from_row`]; unqualified so they can follow any
+/// query that joins `places` or selects from the comparison views.
+pub(crate) const PLACE_ENRICHMENT_COLUMNS: &str =
+    "rating, user_rating_count, phone_number, website_uri, opening_hours";
+
+impl PlaceEnrichment {
+    pub(crate) fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
+        let opening_hours: Option<String> = row.get("opening_hours")?;
+        Ok(Self {
+            rating: row.get("rating")?,
+            user_rating_count: row.get("user_rating_count")?,
+            phone_number: row.get("phone_number")?,
+            website_uri: row.get("website_uri")?,
+            opening_hours: opening_hours
+                .and_then(|text| serde_json::from_str::<Vec<String>>(&text).ok()),
+        })
+    }
+
+    fn opening_hours_json(&self) -> Option<String> {
+        self.opening_hours
+            .as_ref()
+            .map(|lines| serde_json::to_string(lines).unwrap_or_default())
+    }
 }
 
 impl PlaceDetails {
     fn ensure_coordinates(mut self, row: &NormalizedRow) -> Self {
-        if self.lat == 0.0 && self.lng == 0.0 {
-            self.lat = row.latitude;
-            self.lng = row.longitude;
-        }
+        self.location.get_or_insert((row.latitude, row.longitude));
         self
     }
 }
@@ -205,9 +432,12 @@ pub struct PlaceNormalizer {
     db: Arc<Mutex<Connection>>,
     lookup: PlacesService,
     rate_limiter: RateLimiter,
+    retry: RetryPolicy,
     jitter_rng: Arc<Mutex<StdRng>>,
-    cache_ttl: Option<Duration>,
-    guard: Arc<AsyncMutex<()>>,
+    cache_ttl: Mutex<Option<Duration>>,
+    offline: AtomicBool,
+    /// One lock per project so runs on the same project serialize while other projects proceed.
+    guards: Mutex<HashMap<i64, Arc<AsyncMutex<()>>>>,
 }
 
 impl PlaceNormalizer {
@@ -219,9 +449,11 @@ impl PlaceNormalizer {
             db,
             lookup,
             rate_limiter,
+            retry: RetryPolicy::from_config(config),
             jitter_rng: Arc::new(Mutex::new(StdRng::from_entropy())),
-            cache_ttl,
-            guard: Arc::new(AsyncMutex::new(())),
+            cache_ttl: Mutex::new(cache_ttl),
+            offline: AtomicBool::new(false),
+            guards: Mutex::new(HashMap::new()),
         }
     }
 
@@ -237,9 +469,11 @@ impl PlaceNormalizer {
             db,
             lookup,
             rate_limiter: RateLimiter::new(qps.max(1)),
+            retry: RetryPolicy::default(),
             jitter_rng: Arc::new(Mutex::new(rng)),
-            cache_ttl: Some(cache_ttl),
-            guard: Arc::new(AsyncMutex::new(())),
+            cache_ttl: Mutex::new(Some(cache_ttl)),
+            offline: AtomicBool::new(false),
+            guards: Mutex::new(HashMap::new()),
         }
     }
 
@@ -251,15 +485,133 @@ impl PlaceNormalizer {
         self.rate_limiter.qps()
     }
 
+    /// While offline, rows that would need the Places API are left unresolved instead.
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
+    /// Applies to lookups made after the call; `0` disables expiry.
+    pub fn set_cache_ttl(&self, hours: u64) {
+        *self.cache_ttl.lock() = cache_ttl_from_hours(hours);
+    }
+
+    /// Drops cached resolutions for one project's rows (or every row when `project_id` is
+    /// `None`) so the next normalization asks the Places API again. The places those entries
+    /// pointed at stop matching the coordinate shortcut until they are re-fetched.
+    pub fn clear_cache(&self, project_id: Option<i64>) -> AppResult<usize> {
+        const PROJECT_HASHES: &str = "SELECT r.source_row_hash FROM raw_items r
+            JOIN lists l ON l.id = r.list_id
+            WHERE l.project_id = ?1";
+        let mut conn = self.db.lock();
+        let tx = conn.transaction()?;
+        let cleared = match project_id {
+            Some(project_id) => {
+                tx.execute(
+                    &format!(
+                        "UPDATE places SET last_checked_at = NULL WHERE place_id IN (
+                            SELECT place_id FROM normalization_cache
+                            WHERE source_row_hash IN ({PROJECT_HASHES}))"
+                    ),
+                    [project_id],
+                )?;
+                tx.execute(
+                    &format!(
+                        "DELETE FROM normalization_cache WHERE source_row_hash IN ({PROJECT_HASHES})"
+                    ),
+                    [project_id],
+                )?
+            }
+            None => {
+                tx.execute(
+                    "UPDATE places SET last_checked_at = NULL
+                    WHERE place_id IN (SELECT place_id FROM normalization_cache)",
+                    [],
+                )?;
+                tx.execute("DELETE FROM normalization_cache", [])?
+            }
+        };
+        tx.commit()?;
+        Ok(cleared)
+    }
+
+    /// Deletes cache entries older than the TTL and returns how many were removed. Nothing is
+    /// purged while expiry is disabled.
+    pub fn purge_stale_cache(&self) -> AppResult<usize> {
+        let Some(ttl) = *self.cache_ttl.lock() else {
+            return Ok(0);
+        };
+        let conn = self.db.lock();
+        let purged = conn.execute(
+            "DELETE FROM normalization_cache WHERE created_at < DATETIME('now', ?1)",
+            [format!("-{} seconds", ttl.as_secs())],
+        )?;
+        Ok(purged)
+    }
+
+    /// Normalizes a slot, throttled by the project's own QPS when one is set. Either way the
+    /// global limit caps the rate, across every project.
     pub async fn normalize_slot(
         &self,
         project_id: i64,
         slot: ListSlot,
-        observer: Option<Arc<dyn Fn(NormalizationProgress) + Send + Sync>>,
+        observer: Option<ProgressObserver>,
+        cancel_flag: Option<Arc<AtomicBool>>,
+    ) -> AppResult<NormalizationStats> {
+        self.normalize_slot_in(project_id, slot, RefreshScope::All, observer, cancel_flag)
+            .await
+    }
+
+    async fn normalize_slot_in(
+        &self,
+        project_id: i64,
+        slot: ListSlot,
+        scope: RefreshScope,
+        observer: Option<ProgressObserver>,
+        cancel_flag: Option<Arc<AtomicBool>>,
+    ) -> AppResult<NormalizationStats> {
+        let guard = self.project_guard(project_id);
+        let _lock = guard.lock().await;
+        let project_qps = self.project_rate_limit(project_id)?;
+        let _pace = self.rate_limiter.pace_project(project_id, project_qps);
+        let effective_qps = self.rate_limiter.capped_qps(project_qps);
+        let mut stats = self
+            .normalize_slot_locked(project_id, slot, scope, observer, cancel_flag)
+            .await?;
+        stats.rate_limit_qps = effective_qps;
+        Ok(stats)
+    }
+
+    fn project_guard(&self, project_id: i64) -> Arc<AsyncMutex<()>> {
+        Arc::clone(self.guards.lock().entry(project_id).or_default())
+    }
+
+    /// Drops the per-project state kept for a deleted project. A run still holding its lock
+    /// keeps it until that run finishes.
+    pub fn forget_project(&self, project_id: i64) {
+        self.guards.lock().remove(&project_id);
+    }
+
+    fn project_rate_limit(&self, project_id: i64) -> AppResult<Option<u32>> {
+        let conn = self.db.lock();
+        let qps: Option<Option<u32>> = conn
+            .query_row(
+                "SELECT places_rate_limit_qps FROM comparison_projects WHERE id = ?1",
+                [project_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(qps.flatten())
+    }
+
+    async fn normalize_slot_locked(
+        &self,
+        project_id: i64,
+        slot: ListSlot,
+        scope: RefreshScope,
+        observer: Option<ProgressObserver>,
         cancel_flag: Option<Arc<AtomicBool>>,
     ) -> AppResult<NormalizationStats> {
-        let _lock = self.guard.lock().await;
-        let Some((list_id, rows)) = self.load_rows(project_id, slot)? else {
+        let Some((list_id, rows)) = self.load_rows(project_id, slot, scope).await? else {
             let mut empty = NormalizationStats::empty(slot);
             empty.places_counters = self.lookup.counters_snapshot();
             return Ok(empty);
@@ -271,18 +623,29 @@ impl PlaceNormalizer {
             return Ok(empty);
         }
 
-        self.clear_assignments(list_id)?;
-
+        // Resume bookkeeping tracks full passes; a pending-only retry neither resumes one nor
+        // overwrites its progress.
+        let full_pass = scope == RefreshScope::All;
+        // Offline passes skip rows that need the API, so they must not drop the assignments
+        // those rows already have.
+        let replaces_assignments = full_pass && !self.offline.load(Ordering::SeqCst);
         let mut stats = NormalizationStats::with_total(slot, rows.len());
+        if full_pass {
+            stats.resumed_from = self.interrupted_progress(list_id)?;
+        }
         let total_rows = rows.len();
         let mut processed = 0;
+        let mut assignments = Vec::with_capacity(total_rows);
+        if full_pass {
+            self.record_refresh_state(list_id, "running", 0, total_rows, 0)?;
+        }
         for entry in rows {
             if let Some(flag) = &cancel_flag {
                 if flag.load(Ordering::SeqCst) {
                     break;
                 }
             }
-            match self.normalize_row(&entry).await {
+            match self.normalize_row(project_id, &entry).await {
                 Ok(Some(result)) => {
                     match result.cache_outcome {
                         CacheOutcome::Fresh(_) => {
@@ -300,7 +663,7 @@ impl PlaceNormalizer {
                     if matches!(result.source, ResolutionSource::Api) {
                         stats.places_calls += 1;
                     }
-                    self.persist_assignment(list_id, &entry, result.details)?;
+                    assignments.push(prepare_assignment(entry, result.details, result.source));
                     stats.resolved += 1;
                 }
                 Ok(None) => {
@@ -312,12 +675,23 @@ impl PlaceNormalizer {
                 }
             }
             processed += 1;
+            // The final write after the loop covers the tail of the run.
+            if full_pass && processed % REFRESH_STATE_CHUNK_ROWS == 0 && processed < total_rows {
+                self.record_refresh_state(
+                    list_id,
+                    "running",
+                    processed,
+                    total_rows,
+                    stats.resolved,
+                )?;
+            }
             if let Some(callback) = &observer {
                 callback(NormalizationProgress {
                     slot,
                     total_rows,
                     processed,
                     resolved: stats.resolved,
+                    resumed_from: stats.resumed_from,
                 });
             }
         }
@@ -327,31 +701,104 @@ impl PlaceNormalizer {
                 stats.unresolved += total_rows - processed;
             }
         }
+        let status = if processed < total_rows {
+            "cancelled"
+        } else {
+            "complete"
+        };
+        self.commit_assignments(
+            list_id,
+            assignments,
+            replaces_assignments && status == "complete",
+        )
+        .await?;
+        if full_pass {
+            self.record_refresh_state(list_id, status, processed, total_rows, stats.resolved)?;
+        }
         stats.places_counters = self.lookup.counters_snapshot();
 
         Ok(stats)
     }
 
+    /// Estimates a full normalization of a slot without calling the Places API or writing
+    /// anything: `places_calls` counts the rows a real run would send to the API.
+    pub async fn normalize_slot_dry_run(
+        &self,
+        project_id: i64,
+        slot: ListSlot,
+    ) -> AppResult<NormalizationStats> {
+        let mut stats = NormalizationStats::empty(slot);
+        stats.places_counters = self.lookup.counters_snapshot();
+        stats.rate_limit_qps = self
+            .rate_limiter
+            .capped_qps(self.project_rate_limit(project_id)?);
+        let Some((list_id, rows)) = self.load_rows(project_id, slot, RefreshScope::All).await?
+        else {
+            return Ok(stats);
+        };
+        stats.total_rows = rows.len();
+        stats.resumed_from = self.interrupted_progress(list_id)?;
+        let offline = self.offline.load(Ordering::SeqCst);
+        for entry in &rows {
+            let cache_outcome = match self.resolve_locally(entry)? {
+                LocalResolution::Resolved(result) => {
+                    stats.resolved += 1;
+                    result.cache_outcome
+                }
+                LocalResolution::NeedsApi(_) if offline => {
+                    stats.unresolved += 1;
+                    continue;
+                }
+                LocalResolution::NeedsApi(cache_marker) => {
+                    stats.places_calls += 1;
+                    stats.resolved += 1;
+                    cache_marker
+                }
+            };
+            match cache_outcome {
+                CacheOutcome::Fresh(_) => stats.cache_hits += 1,
+                CacheOutcome::Stale(_) => {
+                    stats.cache_misses += 1;
+                    stats.stale_cache += 1;
+                }
+                CacheOutcome::Miss => stats.cache_misses += 1,
+                CacheOutcome::Skipped => {}
+            }
+        }
+        Ok(stats)
+    }
+
     pub async fn refresh_slots(
         &self,
         project_id: i64,
         slots: &[ListSlot],
-        observer: Option<Arc<dyn Fn(NormalizationProgress) + Send + Sync>>,
+        scope: RefreshScope,
+        observer: Option<ProgressObserver>,
         cancel_flag: Option<Arc<AtomicBool>>,
     ) -> AppResult<Vec<NormalizationStats>> {
         let mut results = Vec::new();
         for slot in slots {
             results.push(
-                self.normalize_slot(project_id, *slot, observer.clone(), cancel_flag.clone())
-                    .await?,
+                self.normalize_slot_in(
+                    project_id,
+                    *slot,
+                    scope,
+                    observer.clone(),
+                    cancel_flag.clone(),
+                )
+                .await?,
             );
         }
         Ok(results)
     }
 
-    fn load_rows(&self, project_id: i64, slot: ListSlot) -> AppResult<Option<(i64, Vec<RawRow>)>> {
-        let (list_id, raw_rows) = {
-            let conn = self.db.lock();
+    async fn load_rows(
+        &self,
+        project_id: i64,
+        slot: ListSlot,
+        scope: RefreshScope,
+    ) -> AppResult<Option<(i64, Vec<RawRow>)>> {
+        let loaded = db::run_blocking(&self.db, move |conn| {
             let list_id: Option<i64> = conn
                 .query_row(
                     "SELECT id FROM lists WHERE project_id = ?1 AND slot = ?2 LIMIT 1",
@@ -364,16 +811,27 @@ impl PlaceNormalizer {
             };
 
             let mut stmt = conn.prepare(
-                "SELECT source_row_hash, raw_json FROM raw_items WHERE list_id = ?1 ORDER BY id ASC",
+                "SELECT ri.source_row_hash, ri.raw_json
+                FROM raw_items ri
+                WHERE ri.list_id = ?1 AND (?2 = 0 OR NOT EXISTS (
+                    SELECT 1 FROM normalization_cache nc
+                    WHERE nc.source_row_hash = ri.source_row_hash AND nc.place_id IS NOT NULL
+                ))
+                ORDER BY ri.id ASC",
             )?;
+            let pending_only = scope == RefreshScope::Pending;
             let rows = stmt
-                .query_map([list_id], |row| {
+                .query_map(params![list_id, pending_only], |row| {
                     let hash: String = row.get(0)?;
                     let payload: String = row.get(1)?;
                     Ok((hash, payload))
                 })?
                 .collect::<Result<Vec<_>, _>>()?;
-            (list_id, rows)
+            Ok(Some((list_id, rows)))
+        })
+        .await?;
+        let Some((list_id, raw_rows)) = loaded else {
+            return Ok(None);
         };
 
         let mut rows = Vec::with_capacity(raw_rows.len());
@@ -399,18 +857,110 @@ impl PlaceNormalizer {
         Ok(Some((list_id, rows)))
     }
 
-    fn clear_assignments(&self, list_id: i64) -> AppResult<()> {
+    /// Processed count left behind by a run that never reached `complete`.
+    fn interrupted_progress(&self, list_id: i64) -> AppResult<Option<usize>> {
+        let conn = self.db.lock();
+        let processed: Option<i64> = conn
+            .query_row(
+                "SELECT processed FROM refresh_state WHERE list_id = ?1 AND status <> 'complete'",
+                [list_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(processed.map(|value| value.max(0) as usize))
+    }
+
+    fn record_refresh_state(
+        &self,
+        list_id: i64,
+        status: &str,
+        processed: usize,
+        total_rows: usize,
+        resolved: usize,
+    ) -> AppResult<()> {
         let conn = self.db.lock();
-        conn.execute("DELETE FROM list_places WHERE list_id = ?1", [list_id])?;
+        conn.prepare_cached(
+            "INSERT INTO refresh_state (list_id, status, processed, total_rows, resolved, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, DATETIME('now'))
+            ON CONFLICT(list_id) DO UPDATE SET
+                status = excluded.status,
+                processed = excluded.processed,
+                total_rows = excluded.total_rows,
+                resolved = excluded.resolved,
+                updated_at = excluded.updated_at",
+        )?
+        .execute(params![
+            list_id,
+            status,
+            processed as i64,
+            total_rows as i64,
+            resolved as i64
+        ])?;
         Ok(())
     }
 
-    async fn normalize_row(&self, entry: &RawRow) -> AppResult<Option<NormalizationResult>> {
+    /// Writes a run's assignments in one transaction. A complete run replaces the list's
+    /// assignments; a cancelled one only replaces those of the rows it reached.
+    async fn commit_assignments(
+        &self,
+        list_id: i64,
+        assignments: Vec<Assignment>,
+        replace_all: bool,
+    ) -> AppResult<()> {
+        let count = assignments.len();
+        db::run_blocking(&self.db, move |conn| {
+            let tx = conn.transaction()?;
+            if replace_all {
+                tx.execute("DELETE FROM list_places WHERE list_id = ?1", [list_id])?;
+            }
+            for assignment in &assignments {
+                if !replace_all {
+                    tx.prepare_cached(
+                        "DELETE FROM list_places WHERE list_id = ?1 AND source_row_hash = ?2",
+                    )?
+                    .execute((list_id, &assignment.source_hash))?;
+                }
+                write_assignment(&tx, list_id, assignment)?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await?;
+        trace!(list_id, count, "normalized place assignments committed");
+        Ok(())
+    }
+
+    async fn normalize_row(
+        &self,
+        project_id: i64,
+        entry: &RawRow,
+    ) -> AppResult<Option<NormalizationResult>> {
+        let cache_marker = match self.resolve_locally(entry)? {
+            LocalResolution::Resolved(result) => return Ok(Some(result)),
+            LocalResolution::NeedsApi(cache_marker) => cache_marker,
+        };
+        if self.offline.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+        let details = self.lookup_with_retry(project_id, &entry.row).await?;
+        Ok(Some(NormalizationResult {
+            source: ResolutionSource::Api,
+            details,
+            cache_outcome: match cache_marker {
+                CacheOutcome::Stale(value) => CacheOutcome::Stale(value),
+                _ => CacheOutcome::Miss,
+            },
+        }))
+    }
+
+    /// Resolves a row from its own place id, the cache or the places table, without calling
+    /// the Places API.
+    fn resolve_locally(&self, entry: &RawRow) -> AppResult<LocalResolution> {
         if let Some(place_id) = entry.row.place_id.clone() {
             let details = self
                 .load_place_by_id(&place_id)?
                 .unwrap_or_else(|| details_from_row(&entry.row, place_id));
-            return Ok(Some(NormalizationResult {
+            return Ok(LocalResolution::Resolved(NormalizationResult {
                 source: ResolutionSource::Provided,
                 details,
                 cache_outcome: CacheOutcome::Skipped,
@@ -423,7 +973,7 @@ impl PlaceNormalizer {
             let details = self
                 .load_place_by_id(&place_id)?
                 .unwrap_or_else(|| details_from_row(&entry.row, place_id.clone()));
-            return Ok(Some(NormalizationResult {
+            return Ok(LocalResolution::Resolved(NormalizationResult {
                 source: ResolutionSource::Cache,
                 details,
                 cache_outcome: CacheOutcome::Fresh(place_id),
@@ -434,7 +984,7 @@ impl PlaceNormalizer {
         if allow_coordinate_cache {
             if let Some(details) = self.lookup_coordinates(&entry.row)? {
                 let place_id = details.place_id.clone();
-                return Ok(Some(NormalizationResult {
+                return Ok(LocalResolution::Resolved(NormalizationResult {
                     source: ResolutionSource::PlacesTable,
                     details,
                     cache_outcome: CacheOutcome::Fresh(place_id),
@@ -442,16 +992,7 @@ impl PlaceNormalizer {
             }
         }
 
-        let details = self.lookup_with_retry(&entry.row).await?;
-        let finalized = details.ensure_coordinates(&entry.row);
-        Ok(Some(NormalizationResult {
-            source: ResolutionSource::Api,
-            details: finalized,
-            cache_outcome: match cache_marker {
-                CacheOutcome::Stale(value) => CacheOutcome::Stale(value),
-                _ => CacheOutcome::Miss,
-            },
-        }))
+        Ok(LocalResolution::NeedsApi(cache_marker))
     }
 
     fn lookup_cache(&self, source_hash: &str) -> AppResult<CacheOutcome> {
@@ -468,7 +1009,8 @@ impl PlaceNormalizer {
             return Ok(CacheOutcome::Miss);
         };
 
-        if let Some(ttl) = self.cache_ttl {
+        let cache_ttl = *self.cache_ttl.lock();
+        if let Some(ttl) = cache_ttl {
             let ttl_secs = ttl.as_secs() as f64;
             let age_secs: f64 = conn
                 .query_row(
@@ -486,12 +1028,20 @@ impl PlaceNormalizer {
     }
 
     fn lookup_coordinates(&self, row: &NormalizedRow) -> AppResult<Option<PlaceDetails>> {
+        // Exactly 0,0 is what older builds stored for places without a location, so a match
+        // there says nothing about the row.
+        if row.latitude == 0.0 && row.longitude == 0.0 {
+            return Ok(None);
+        }
         let conn = self.db.lock();
         conn.query_row(
-            "SELECT place_id, name, formatted_address, lat, lng, types
-            FROM places
-            WHERE ABS(lat - ?1) <= ?3 AND ABS(lng - ?2) <= ?3
-            LIMIT 1",
+            &format!(
+                "SELECT place_id, name, formatted_address, lat, lng, types, photo_name,
+                    {PLACE_ENRICHMENT_COLUMNS}
+                FROM places
+                WHERE ABS(lat - ?1) <= ?3 AND ABS(lng - ?2) <= ?3 AND last_checked_at IS NOT NULL
+                LIMIT 1"
+            ),
             (row.latitude, row.longitude, GEO_EPSILON),
             |row| parse_place_details(row),
         )
@@ -502,9 +1052,12 @@ impl PlaceNormalizer {
     fn load_place_by_id(&self, place_id: &str) -> AppResult<Option<PlaceDetails>> {
         let conn = self.db.lock();
         conn.query_row(
-            "SELECT place_id, name, formatted_address, lat, lng, types
-            FROM places
-            WHERE place_id = ?1",
+            &format!(
+                "SELECT place_id, name, formatted_address, lat, lng, types, photo_name,
+                    {PLACE_ENRICHMENT_COLUMNS}
+                FROM places
+                WHERE place_id = ?1"
+            ),
             [place_id],
             |row| parse_place_details(row),
         )
@@ -512,14 +1065,18 @@ impl PlaceNormalizer {
         .map_err(AppError::from)
     }
 
-    async fn lookup_with_retry(&self, row: &NormalizedRow) -> AppResult<PlaceDetails> {
+    async fn lookup_with_retry(
+        &self,
+        project_id: i64,
+        row: &NormalizedRow,
+    ) -> AppResult<PlaceDetails> {
         let mut attempt = 0;
         loop {
             attempt += 1;
-            self.rate_limiter.wait().await;
+            self.rate_limiter.wait(project_id).await;
             match self.lookup.lookup_place(row).await {
                 Ok(details) => return Ok(details),
-                Err(err) if attempt < MAX_ATTEMPTS => {
+                Err(err) if attempt < self.retry.max_attempts => {
                     let kind = classify_places_error(&err);
                     if matches!(kind, PlacesErrorKind::InvalidKey) {
                         return Err(err);
@@ -540,78 +1097,168 @@ impl PlaceNormalizer {
     }
 
     fn backoff_delay(&self, attempt: u32) -> Duration {
-        let exponent = (attempt - 1).min(6);
-        let base = Duration::from_millis(BASE_BACKOFF_MS * (1 << exponent));
+        let RetryPolicy {
+            base_backoff_ms,
+            max_backoff_ms,
+            ..
+        } = self.retry;
+        let exponent = (attempt - 1).min(32);
+        let base = Duration::from_millis(
+            base_backoff_ms
+                .saturating_mul(1 << exponent)
+                .min(max_backoff_ms),
+        );
         let jitter = {
             let mut rng = self.jitter_rng.lock();
-            let jitter_ms = rng.gen_range(0..BASE_BACKOFF_MS);
+            let jitter_ms = rng.gen_range(0..base_backoff_ms.max(1));
             Duration::from_millis(jitter_ms)
         };
         base + jitter
     }
+}
 
-    fn persist_assignment(
-        &self,
-        list_id: i64,
-        entry: &RawRow,
-        mut details: PlaceDetails,
-    ) -> AppResult<()> {
-        details.name = if details.name.trim().is_empty() {
-            entry.row.title.clone()
-        } else {
-            details.name
-        };
-        details.formatted_address = details
-            .formatted_address
-            .or_else(|| entry.row.description.clone());
-
-        {
-            let conn = self.db.lock();
-            conn.execute(
-                "INSERT INTO places (place_id, name, formatted_address, lat, lng, types, last_checked_at)
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, DATETIME('now'))
-                ON CONFLICT(place_id) DO UPDATE SET
-                    name = excluded.name,
-                    formatted_address = COALESCE(excluded.formatted_address, places.formatted_address),
-                    lat = excluded.lat,
-                    lng = excluded.lng,
-                    types = excluded.types,
-                    last_checked_at = DATETIME('now')",
-                (
-                    details.place_id.as_str(),
-                    details.name.as_str(),
-                    details.formatted_address.as_deref(),
-                    details.lat,
-                    details.lng,
-                    serialize_types(&details.types),
-                ),
-            )?;
+fn prepare_assignment(
+    entry: RawRow,
+    details: PlaceDetails,
+    source: ResolutionSource,
+) -> Assignment {
+    let mut details = details.ensure_coordinates(&entry.row);
+    if details.name.trim().is_empty() {
+        details.name = entry.row.title;
+    }
+    details.formatted_address = details.formatted_address.or(entry.row.description);
+    // Cached and coordinate matches can point at synthetic places too, so the id decides.
+    let source = if details.place_id.starts_with(SYNTHETIC_PLACE_PREFIX) {
+        ResolutionSource::Synthetic
+    } else {
+        source
+    };
+    Assignment {
+        source_hash: entry.source_hash,
+        details,
+        source,
+    }
+}
 
-            conn.execute(
-                "INSERT INTO normalization_cache (source_row_hash, place_id, created_at)
-                VALUES (?1, ?2, DATETIME('now'))
-                ON CONFLICT(source_row_hash) DO UPDATE SET
-                    place_id = excluded.place_id,
-                    created_at = DATETIME('now')",
-                (&entry.source_hash, details.place_id.as_str()),
-            )?;
+fn write_assignment(conn: &Connection, list_id: i64, assignment: &Assignment) -> AppResult<()> {
+    let Assignment {
+        source_hash,
+        details,
+        source,
+    } = assignment;
+    let (lat, lng) = details.location.ok_or_else(|| {
+        AppError::Config(format!("place {} has no coordinates", details.place_id))
+    })?;
+    conn.prepare_cached(
+        "INSERT INTO places (
+            place_id, name, formatted_address, lat, lng, types, photo_name, rating,
+            user_rating_count, phone_number, website_uri, opening_hours, last_checked_at
+        )
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, DATETIME('now'))
+        ON CONFLICT(place_id) DO UPDATE SET
+            name = excluded.name,
+            formatted_address = COALESCE(excluded.formatted_address, places.formatted_address),
+            lat = excluded.lat,
+            lng = excluded.lng,
+            types = excluded.types,
+            photo_name = COALESCE(excluded.photo_name, places.photo_name),
+            rating = COALESCE(excluded.rating, places.rating),
+            user_rating_count = COALESCE(excluded.user_rating_count, places.user_rating_count),
+            phone_number = COALESCE(excluded.phone_number, places.phone_number),
+            website_uri = COALESCE(excluded.website_uri, places.website_uri),
+            opening_hours = COALESCE(excluded.opening_hours, places.opening_hours),
+            last_checked_at = DATETIME('now')",
+    )?
+    .execute(params![
+        details.place_id,
+        details.name,
+        details.formatted_address,
+        lat,
+        lng,
+        serialize_types(&details.types),
+        details.photo_name,
+        details.enrichment.rating,
+        details.enrichment.user_rating_count,
+        details.enrichment.phone_number,
+        details.enrichment.website_uri,
+        details.enrichment.opening_hours_json(),
+    ])?;
+
+    conn.prepare_cached(
+        "INSERT INTO normalization_cache (source_row_hash, place_id, created_at, confidence)
+        VALUES (?1, ?2, DATETIME('now'), ?3)
+        ON CONFLICT(source_row_hash) DO UPDATE SET
+            confidence = CASE
+                WHEN excluded.place_id = normalization_cache.place_id
+                    THEN COALESCE(excluded.confidence, normalization_cache.confidence)
+                ELSE excluded.confidence
+            END,
+            place_id = excluded.place_id,
+            created_at = DATETIME('now')",
+    )?
+    .execute((source_hash, details.place_id.as_str(), details.confidence))?;
+
+    conn.prepare_cached(
+        "INSERT INTO list_places (list_id, place_id, assigned_at, source_row_hash, resolution_source)
+        VALUES (?1, ?2, DATETIME('now'), ?3, ?4)
+        ON CONFLICT(list_id, place_id) DO UPDATE SET
+            assigned_at = excluded.assigned_at,
+            source_row_hash = excluded.source_row_hash,
+            resolution_source = excluded.resolution_source",
+    )?
+    .execute((
+        list_id,
+        details.place_id.as_str(),
+        source_hash,
+        source.as_str(),
+    ))?;
+    Ok(())
+}
 
-            conn.execute(
-                "INSERT INTO list_places (list_id, place_id, assigned_at)
-                VALUES (?1, ?2, DATETIME('now'))
-                ON CONFLICT(list_id, place_id) DO UPDATE SET
-                    assigned_at = excluded.assigned_at",
-                (list_id, details.place_id.as_str()),
-            )?;
-        }
+/// Scores a search candidate by name similarity to the row title and by how far the candidate
+/// lies from the queried coordinates; candidates beyond `MATCH_DISTANCE_CEILING_M` get no
+/// proximity credit.
+fn match_confidence(row: &NormalizedRow, name: Option<&str>, location: Option<(f64, f64)>) -> f64 {
+    let name_score = name.map_or(0.0, |name| comparison::name_similarity(&row.title, name));
+    let proximity = location.map_or(0.0, |(lat, lng)| {
+        let distance = comparison::distance_m(row.latitude, row.longitude, lat, lng);
+        (1.0 - distance / MATCH_DISTANCE_CEILING_M).clamp(0.0, 1.0)
+    });
+    MATCH_NAME_WEIGHT * name_score + (1.0 - MATCH_NAME_WEIGHT) * proximity
+}
 
-        trace!(
-            list_id,
-            place_id = details.place_id,
-            "normalized place assignment recorded"
-        );
-        Ok(())
-    }
+/// Builds the Places photo media URL for a stored place; `None` when the place has no photo.
+pub fn place_photo_url(
+    conn: &Connection,
+    api_key: Option<&SecretString>,
+    place_id: &str,
+    max_width: Option<u32>,
+) -> AppResult<Option<String>> {
+    let photo_name: Option<String> = conn
+        .query_row(
+            "SELECT photo_name FROM places WHERE place_id = ?1",
+            [place_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::Config(format!("place {place_id} not found")))?;
+    let Some(photo_name) = photo_name else {
+        return Ok(None);
+    };
+    let api_key =
+        api_key.ok_or_else(|| AppError::Config("Places API key is not configured".into()))?;
+    let max_width = max_width
+        .unwrap_or(DEFAULT_PHOTO_MAX_WIDTH_PX)
+        .clamp(1, MAX_PHOTO_WIDTH_PX);
+    let url = reqwest::Url::parse_with_params(
+        &format!("{PLACES_API_BASE}/{photo_name}/media"),
+        [
+            ("maxWidthPx", max_width.to_string()),
+            ("key", api_key.expose_secret().to_string()),
+        ],
+    )
+    .map_err(|err| AppError::Config(format!("invalid photo name {photo_name}: {err}")))?;
+    Ok(Some(url.into()))
 }
 
 fn details_from_row(row: &NormalizedRow, place_id: String) -> PlaceDetails {
@@ -619,9 +1266,11 @@ fn details_from_row(row: &NormalizedRow, place_id: String) -> PlaceDetails {
         place_id,
         name: row.title.clone(),
         formatted_address: row.description.clone(),
-        lat: row.latitude,
-        lng: row.longitude,
+        location: Some((row.latitude, row.longitude)),
         types: Vec::new(),
+        confidence: None,
+        photo_name: None,
+        enrichment: PlaceEnrichment::default(),
     }
 }
 
@@ -643,16 +1292,18 @@ fn parse_place_details(row: &rusqlite::Row<'_>) -> rusqlite::Result<PlaceDetails
     let place_id: String = row.get(0)?;
     let name: String = row.get(1)?;
     let formatted_address: Option<String> = row.get(2)?;
-    let lat: f64 = row.get(3)?;
-    let lng: f64 = row.get(4)?;
+    let location: (f64, f64) = (row.get(3)?, row.get(4)?);
     let types: Option<String> = row.get(5)?;
+    let photo_name: Option<String> = row.get(6)?;
     Ok(PlaceDetails {
         place_id,
         name,
         formatted_address,
-        lat,
-        lng,
+        location: Some(location),
         types: parse_types(types),
+        confidence: None,
+        photo_name,
+        enrichment: PlaceEnrichment::from_row(row)?,
     })
 }
 
@@ -666,9 +1317,12 @@ impl PlacesService {
     pub fn new(config: &AppConfig) -> Self {
         let counters = Arc::new(PlacesClientCounters::default());
         if let Some(key) = config.google_places_api_key.clone() {
-            let http = HttpPlacesClient::new(key, Arc::clone(&counters));
-            let synthetic = SyntheticPlacesClient::default();
-            let client = HybridPlacesClient::new(http, synthetic);
+            let builder = config.http_client_builder().unwrap_or_else(|err| {
+                warn!(?err, "ignoring proxy settings for Places requests");
+                reqwest::Client::builder()
+            });
+            let http = HttpPlacesClient::new(key, PLACES_API_BASE, builder, Arc::clone(&counters));
+            let client = HybridPlacesClient::new(Box::new(http), config.allow_synthetic_fallback);
             Self {
                 inner: Arc::new(client),
                 counters,
@@ -705,13 +1359,22 @@ pub trait PlaceLookup: Send + Sync {
 
 struct RateLimiter {
     min_interval_ms: AtomicU64,
+    /// Projects normalizing under their own, slower QPS. Each is paced separately on top of
+    /// the global limit, which stays the ceiling because every project shares one API key.
+    project_paces: Mutex<HashMap<i64, ProjectPace>>,
     last_tick: AsyncMutex<Option<Instant>>,
 }
 
+struct ProjectPace {
+    interval_ms: u64,
+    last_tick: Option<Instant>,
+}
+
 impl RateLimiter {
     fn new(qps: u32) -> Self {
         Self {
             min_interval_ms: AtomicU64::new(Self::interval_ms(qps)),
+            project_paces: Mutex::new(HashMap::new()),
             last_tick: AsyncMutex::new(None),
         }
     }
@@ -721,9 +1384,57 @@ impl RateLimiter {
             .store(Self::interval_ms(qps), Ordering::SeqCst);
     }
 
+    /// Applies a project's QPS override until the returned guard drops, so a run that fails
+    /// or is dropped mid-await cannot leave its project throttled.
+    fn pace_project(&self, project_id: i64, qps: Option<u32>) -> ProjectPaceGuard<'_> {
+        self.set_override(project_id, qps);
+        ProjectPaceGuard {
+            limiter: self,
+            project_id,
+        }
+    }
+
+    fn set_override(&self, project_id: i64, qps: Option<u32>) {
+        let mut paces = self.project_paces.lock();
+        match qps {
+            Some(qps) => {
+                paces.insert(
+                    project_id,
+                    ProjectPace {
+                        interval_ms: Self::interval_ms(qps),
+                        last_tick: None,
+                    },
+                );
+            }
+            None => {
+                paces.remove(&project_id);
+            }
+        }
+    }
+
     fn qps(&self) -> u32 {
-        let interval = self.min_interval_ms.load(Ordering::SeqCst).max(1);
-        let qps = (1000_f64 / interval as f64).round() as u32;
+        Self::qps_for(self.min_interval_ms.load(Ordering::SeqCst))
+    }
+
+    /// The QPS a project with the given override actually gets: never faster than the global
+    /// limit.
+    fn capped_qps(&self, project_qps: Option<u32>) -> u32 {
+        let global_ms = self.min_interval_ms.load(Ordering::SeqCst);
+        let interval_ms =
+            project_qps.map_or(global_ms, |qps| Self::interval_ms(qps).max(global_ms));
+        Self::qps_for(interval_ms)
+    }
+
+    fn effective_interval_ms(&self, project_id: i64) -> u64 {
+        let global_ms = self.min_interval_ms.load(Ordering::SeqCst);
+        self.project_paces
+            .lock()
+            .get(&project_id)
+            .map_or(global_ms, |pace| pace.interval_ms.max(global_ms))
+    }
+
+    fn qps_for(interval_ms: u64) -> u32 {
+        let qps = (1000_f64 / interval_ms.max(1) as f64).round() as u32;
         qps.max(1)
     }
 
@@ -733,12 +1444,23 @@ impl RateLimiter {
         interval_ms.max(50)
     }
 
-    fn interval_duration(&self) -> Duration {
-        Duration::from_millis(self.min_interval_ms.load(Ordering::SeqCst))
+    fn interval_duration(&self, project_id: i64) -> Duration {
+        Duration::from_millis(self.effective_interval_ms(project_id))
     }
 
-    async fn wait(&self) {
-        let interval = self.interval_duration();
+    async fn wait(&self, project_id: i64) {
+        let project_wait = self.project_paces.lock().get(&project_id).and_then(|pace| {
+            let interval = Duration::from_millis(pace.interval_ms);
+            pace.last_tick
+                .map(|prev| prev.elapsed())
+                .filter(|elapsed| *elapsed < interval)
+                .map(|elapsed| interval - elapsed)
+        });
+        if let Some(delay) = project_wait {
+            sleep(delay).await;
+        }
+
+        let interval = Duration::from_millis(self.min_interval_ms.load(Ordering::SeqCst));
         let mut guard = self.last_tick.lock().await;
         if let Some(prev) = *guard {
             let elapsed = prev.elapsed();
@@ -746,12 +1468,27 @@ impl RateLimiter {
                 sleep(interval - elapsed).await;
             }
         }
-        *guard = Some(Instant::now());
+        let now = Instant::now();
+        *guard = Some(now);
+        if let Some(pace) = self.project_paces.lock().get_mut(&project_id) {
+            pace.last_tick = Some(now);
+        }
     }
 }
 
-fn classify_places_error(err: &AppError) -> PlacesErrorKind {
-    match err {
+struct ProjectPaceGuard<'a> {
+    limiter: &'a RateLimiter,
+    project_id: i64,
+}
+
+impl Drop for ProjectPaceGuard<'_> {
+    fn drop(&mut self) {
+        self.limiter.set_override(self.project_id, None);
+    }
+}
+
+fn classify_places_error(err: &AppError) -> PlacesErrorKind {
+    match err {
         AppError::Http(http_err) => {
             if http_err.is_timeout() || http_err.is_connect() {
                 return PlacesErrorKind::Network;
@@ -776,13 +1513,18 @@ fn classify_places_error(err: &AppError) -> PlacesErrorKind {
 }
 
 struct HybridPlacesClient {
-    primary: HttpPlacesClient,
+    primary: Box<dyn PlaceLookup>,
     fallback: SyntheticPlacesClient,
+    allow_fallback: bool,
 }
 
 impl HybridPlacesClient {
-    fn new(primary: HttpPlacesClient, fallback: SyntheticPlacesClient) -> Self {
-        Self { primary, fallback }
+    fn new(primary: Box<dyn PlaceLookup>, allow_fallback: bool) -> Self {
+        Self {
+            primary,
+            fallback: SyntheticPlacesClient,
+            allow_fallback,
+        }
     }
 }
 
@@ -791,6 +1533,7 @@ impl PlaceLookup for HybridPlacesClient {
     async fn lookup_place(&self, row: &NormalizedRow) -> AppResult<PlaceDetails> {
         match self.primary.lookup_place(row).await {
             Ok(details) => Ok(details),
+            Err(err) if !self.allow_fallback => Err(err),
             Err(err) => {
                 warn!(
                     ?err,
@@ -805,18 +1548,111 @@ impl PlaceLookup for HybridPlacesClient {
 struct HttpPlacesClient {
     http: reqwest::Client,
     api_key: SecretString,
+    api_base: String,
     counters: Arc<PlacesClientCounters>,
 }
 
+const PLACES_FIELD_MASK: &str = "places.id,places.placeId,places.displayName,places.formattedAddress,places.location,places.types,places.photos,places.rating,places.userRatingCount,places.internationalPhoneNumber,places.websiteUri,places.regularOpeningHours";
+const NEARBY_RADIUS_M: f64 = 500.0;
+const NEARBY_MAX_RESULTS: u8 = 10;
+
+#[derive(serde::Serialize)]
+struct SearchCircle<'a> {
+    center: SearchCenter<'a>,
+    radius: f64,
+}
+
+#[derive(serde::Serialize)]
+struct SearchCenter<'a> {
+    latitude: &'a f64,
+    longitude: &'a f64,
+}
+
+impl<'a> SearchCircle<'a> {
+    fn around(row: &'a NormalizedRow) -> Self {
+        Self {
+            center: SearchCenter {
+                latitude: &row.latitude,
+                longitude: &row.longitude,
+            },
+            radius: NEARBY_RADIUS_M,
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct SearchResponse {
+    places: Option<Vec<ResponsePlace>>,
+}
+
+#[derive(serde::Deserialize)]
+struct ResponsePlace {
+    #[serde(rename = "placeId")]
+    place_id: Option<String>,
+    #[serde(rename = "id")]
+    legacy_id: Option<String>,
+    #[serde(rename = "displayName")]
+    display_name: Option<ResponseText>,
+    #[serde(rename = "formattedAddress")]
+    formatted_address: Option<String>,
+    location: Option<ResponseLocation>,
+    types: Option<Vec<String>>,
+    photos: Option<Vec<ResponsePhoto>>,
+    rating: Option<f64>,
+    #[serde(rename = "userRatingCount")]
+    user_rating_count: Option<u32>,
+    #[serde(rename = "internationalPhoneNumber")]
+    international_phone_number: Option<String>,
+    #[serde(rename = "websiteUri")]
+    website_uri: Option<String>,
+    #[serde(rename = "regularOpeningHours")]
+    regular_opening_hours: Option<ResponseOpeningHours>,
+}
+
+impl ResponsePlace {
+    fn coordinates(&self) -> Option<(f64, f64)> {
+        let location = self.location.as_ref()?;
+        Some((location.latitude?, location.longitude?))
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct ResponsePhoto {
+    name: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct ResponseOpeningHours {
+    #[serde(rename = "weekdayDescriptions")]
+    weekday_descriptions: Option<Vec<String>>,
+}
+
+#[derive(serde::Deserialize)]
+struct ResponseText {
+    text: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct ResponseLocation {
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+}
+
 impl HttpPlacesClient {
-    fn new(api_key: SecretString, counters: Arc<PlacesClientCounters>) -> Self {
-        let http = reqwest::Client::builder()
+    fn new(
+        api_key: SecretString,
+        api_base: &str,
+        http: reqwest::ClientBuilder,
+        counters: Arc<PlacesClientCounters>,
+    ) -> Self {
+        let http = http
             .timeout(Duration::from_secs(10))
             .build()
             .expect("places http client");
         Self {
             http,
             api_key,
+            api_base: api_base.trim_end_matches('/').to_string(),
             counters,
         }
     }
@@ -827,6 +1663,61 @@ impl HttpPlacesClient {
         self.counters.record_error(kind);
         app_err
     }
+
+    async fn search<B: Serialize>(&self, method: &str, body: &B) -> AppResult<Vec<ResponsePlace>> {
+        self.counters.record_attempt();
+        let response = self
+            .http
+            .post(format!("{}/places:{method}", self.api_base))
+            .header("X-Goog-Api-Key", self.api_key.expose_secret())
+            .header("X-Goog-FieldMask", PLACES_FIELD_MASK)
+            .json(body)
+            .send()
+            .await
+            .map_err(|err| self.record_http_error(err))?
+            .error_for_status()
+            .map_err(|err| self.record_http_error(err))?;
+
+        let parsed: SearchResponse = response.json().await.map_err(|err| {
+            self.counters.record_error(PlacesErrorKind::Other);
+            AppError::from(err)
+        })?;
+        self.counters.record_success();
+        Ok(parsed.places.unwrap_or_default())
+    }
+
+    /// Looks around the row's coordinates for rows whose name text search cannot match, and
+    /// keeps the candidate closest to the row.
+    async fn search_nearby(&self, row: &NormalizedRow) -> AppResult<Option<ResponsePlace>> {
+        #[derive(serde::Serialize)]
+        struct RequestBody<'a> {
+            #[serde(rename = "maxResultCount")]
+            max_result_count: u8,
+            #[serde(rename = "locationRestriction")]
+            location_restriction: LocationRestriction<'a>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct LocationRestriction<'a> {
+            circle: SearchCircle<'a>,
+        }
+
+        let body = RequestBody {
+            max_result_count: NEARBY_MAX_RESULTS,
+            location_restriction: LocationRestriction {
+                circle: SearchCircle::around(row),
+            },
+        };
+        let candidates = self.search("searchNearby", &body).await?;
+        let distance = |place: &ResponsePlace| {
+            place.coordinates().map_or(f64::INFINITY, |(lat, lng)| {
+                comparison::distance_m(row.latitude, row.longitude, lat, lng)
+            })
+        };
+        Ok(candidates
+            .into_iter()
+            .min_by(|left, right| distance(left).total_cmp(&distance(right))))
+    }
 }
 
 #[async_trait]
@@ -844,117 +1735,53 @@ impl PlaceLookup for HttpPlacesClient {
 
         #[derive(serde::Serialize)]
         struct LocationBias<'a> {
-            circle: BiasCircle<'a>,
-        }
-
-        #[derive(serde::Serialize)]
-        struct BiasCircle<'a> {
-            center: BiasCenter<'a>,
-            radius: u32,
-        }
-
-        #[derive(serde::Serialize)]
-        struct BiasCenter<'a> {
-            latitude: &'a f64,
-            longitude: &'a f64,
-        }
-
-        #[derive(serde::Deserialize)]
-        struct Response {
-            places: Option<Vec<ResponsePlace>>,
-        }
-
-        #[derive(serde::Deserialize)]
-        struct ResponsePlace {
-            #[serde(rename = "placeId")]
-            place_id: Option<String>,
-            #[serde(rename = "id")]
-            legacy_id: Option<String>,
-            #[serde(rename = "displayName")]
-            display_name: Option<ResponseText>,
-            #[serde(rename = "formattedAddress")]
-            formatted_address: Option<String>,
-            location: Option<ResponseLocation>,
-            types: Option<Vec<String>>,
-        }
-
-        #[derive(serde::Deserialize)]
-        struct ResponseText {
-            text: Option<String>,
-        }
-
-        #[derive(serde::Deserialize)]
-        struct ResponseLocation {
-            latitude: Option<f64>,
-            longitude: Option<f64>,
+            circle: SearchCircle<'a>,
         }
 
         let body = RequestBody {
             text_query: &row.title,
             max_result_count: 1,
             location_bias: LocationBias {
-                circle: BiasCircle {
-                    center: BiasCenter {
-                        latitude: &row.latitude,
-                        longitude: &row.longitude,
-                    },
-                    radius: 500,
-                },
+                circle: SearchCircle::around(row),
             },
         };
 
-        self.counters.record_attempt();
-        let response = self
-            .http
-            .post("https://places.googleapis.com/v1/places:searchText")
-            .header("X-Goog-Api-Key", self.api_key.expose_secret())
-            .header(
-                "X-Goog-FieldMask",
-                "places.id,places.placeId,places.displayName,places.formattedAddress,places.location,places.types",
-            )
-            .json(&body)
-            .send()
-            .await
-            .map_err(|err| self.record_http_error(err))?
-            .error_for_status()
-            .map_err(|err| self.record_http_error(err))?;
-
-        let parsed: Response = response.json().await.map_err(|err| {
-            self.counters.record_error(PlacesErrorKind::Other);
-            AppError::from(err)
-        })?;
-        self.counters.record_success();
-        let place = parsed
-            .places
-            .and_then(|mut list| list.pop())
-            .ok_or_else(|| AppError::Config("Places API returned no candidates".into()))?;
+        let place = match self.search("searchText", &body).await?.pop() {
+            Some(place) => place,
+            None => self
+                .search_nearby(row)
+                .await?
+                .ok_or_else(|| AppError::Config("Places API returned no candidates".into()))?,
+        };
 
+        let location = place.coordinates();
         let place_id = place
             .place_id
             .or(place.legacy_id)
             .ok_or_else(|| AppError::Config("Places API response missing place_id".into()))?;
-
-        let mut lat = row.latitude;
-        let mut lng = row.longitude;
-        if let Some(loc) = place.location {
-            if let Some(value) = loc.latitude {
-                lat = value;
-            }
-            if let Some(value) = loc.longitude {
-                lng = value;
-            }
-        }
+        let display_name = place.display_name.and_then(|text| text.text);
+        let confidence = match_confidence(row, display_name.as_deref(), location);
+        let photo_name = place
+            .photos
+            .and_then(|photos| photos.into_iter().find_map(|photo| photo.name));
 
         Ok(PlaceDetails {
             place_id,
-            name: place
-                .display_name
-                .and_then(|text| text.text)
-                .unwrap_or_else(|| row.title.clone()),
+            name: display_name.unwrap_or_else(|| row.title.clone()),
             formatted_address: place.formatted_address.or_else(|| row.description.clone()),
-            lat,
-            lng,
+            location,
             types: place.types.unwrap_or_default(),
+            confidence: Some(confidence),
+            photo_name,
+            enrichment: PlaceEnrichment {
+                rating: place.rating,
+                user_rating_count: place.user_rating_count,
+                phone_number: place.international_phone_number,
+                website_uri: place.website_uri,
+                opening_hours: place
+                    .regular_opening_hours
+                    .and_then(|hours| hours.weekday_descriptions),
+            },
         })
     }
 }
@@ -971,12 +1798,14 @@ impl PlaceLookup for SyntheticPlacesClient {
         hasher.update(row.longitude.to_le_bytes());
         let id = base64::engine::general_purpose::STANDARD_NO_PAD.encode(hasher.finalize());
         Ok(PlaceDetails {
-            place_id: format!("synthetic_{id}"),
+            place_id: format!("{SYNTHETIC_PLACE_PREFIX}{id}"),
             name: row.title.clone(),
             formatted_address: row.description.clone(),
-            lat: row.latitude,
-            lng: row.longitude,
+            location: Some((row.latitude, row.longitude)),
             types: vec!["synthetic".into()],
+            confidence: None,
+            photo_name: None,
+            enrichment: PlaceEnrichment::default(),
         })
     }
 }
@@ -986,10 +1815,12 @@ mod tests {
     use std::sync::Arc;
 
     use rand::SeedableRng;
+    use tempfile::TempDir;
 
     use crate::db::bootstrap;
     use crate::ingestion::{ListSlot, NormalizedRow};
     use crate::secrets::SecretVault;
+    use crate::settings::{UpdateRuntimeSettingsPayload, UserSettings};
 
     use super::*;
 
@@ -1016,15 +1847,80 @@ mod tests {
                         place_id: format!("fallback_{}", row.title),
                         name: row.title.clone(),
                         formatted_address: row.description.clone(),
-                        lat: row.latitude,
-                        lng: row.longitude,
+                        location: Some((row.latitude, row.longitude)),
                         types: Vec::new(),
+                        confidence: None,
+                        photo_name: None,
+                        enrichment: PlaceEnrichment::default(),
                     })
                 })
                 .map_err(|err| err)
         }
     }
 
+    fn row(title: &str, lat: f64, lng: f64, place_id: Option<&str>) -> NormalizedRow {
+        NormalizedRow {
+            title: title.into(),
+            description: None,
+            longitude: lng,
+            latitude: lat,
+            altitude: None,
+            place_id: place_id.map(str::to_string),
+            raw_coordinates: format!("{lng},{lat},0"),
+            layer_path: None,
+            derived_from: None,
+        }
+    }
+
+    /// Seeds List A of the active project with `rows`, stored under the hashes
+    /// `hash0`, `hash1`, …, and builds a normalizer that resolves them via `lookup`.
+    fn normalizer_fixture(
+        rows: &[NormalizedRow],
+        lookup: impl PlaceLookup + 'static,
+    ) -> (TempDir, Arc<Mutex<Connection>>, i64, PlaceNormalizer) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut conn = bootstrap(dir.path(), "places.db", &SecretVault::in_memory())
+            .unwrap()
+            .context
+            .connection;
+        let project_id: i64 = conn
+            .query_row(
+                "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        let tx = conn.transaction().unwrap();
+        tx.execute(
+            "INSERT INTO lists (project_id, slot, name, source) VALUES (?1, 'A', 'List A', 'test')",
+            [project_id],
+        )
+        .unwrap();
+        let list_id = tx.last_insert_rowid();
+        for (index, row) in rows.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO raw_items (list_id, source_row_hash, raw_json) VALUES (?1, ?2, ?3)",
+                params![
+                    list_id,
+                    format!("hash{index}"),
+                    serde_json::to_string(row).unwrap()
+                ],
+            )
+            .unwrap();
+        }
+        tx.commit().unwrap();
+
+        let db = Arc::new(Mutex::new(conn));
+        let normalizer = PlaceNormalizer::with_lookup(
+            db.clone(),
+            PlacesService::from_lookup(Arc::new(lookup)),
+            10,
+            StdRng::seed_from_u64(1),
+            Duration::from_secs(3600),
+        );
+        (dir, db, project_id, normalizer)
+    }
+
     #[tokio::test]
     async fn uses_cache_before_api_call() {
         let dir = tempfile::tempdir().unwrap();
@@ -1057,6 +1953,7 @@ mod tests {
                     place_id: None,
                     raw_coordinates: "1,2,0".into(),
                     layer_path: None,
+                    derived_from: None,
                 })
                 .unwrap()],
             )
@@ -1128,6 +2025,7 @@ mod tests {
                     place_id: None,
                     raw_coordinates: "1,2,0".into(),
                     layer_path: None,
+                    derived_from: None,
                 })
                 .unwrap()],
             )
@@ -1145,9 +2043,11 @@ mod tests {
                 place_id: "fresh_place".into(),
                 name: "Refreshed".into(),
                 formatted_address: None,
-                lat: 2.0,
-                lng: 1.0,
+                location: Some((2.0, 1.0)),
                 types: Vec::new(),
+                confidence: None,
+                photo_name: None,
+                enrichment: PlaceEnrichment::default(),
             })])));
 
         let normalizer = PlaceNormalizer::with_lookup(
@@ -1232,6 +2132,7 @@ mod tests {
                     place_id: None,
                     raw_coordinates: "1,2,0".into(),
                     layer_path: None,
+                    derived_from: None,
                 })
                 .unwrap()],
             )
@@ -1244,9 +2145,11 @@ mod tests {
                 place_id: "success".into(),
                 name: "Resolved".into(),
                 formatted_address: None,
-                lat: 2.0,
-                lng: 1.0,
+                location: Some((2.0, 1.0)),
                 types: Vec::new(),
+                confidence: None,
+                photo_name: None,
+                enrichment: PlaceEnrichment::default(),
             }),
             Err(AppError::Config("transient".into())),
         ])));
@@ -1268,4 +2171,1115 @@ mod tests {
         assert_eq!(stats.places_calls, 1);
         assert_eq!(stats.resolved, 1);
     }
+
+    struct FailingPlacesClient {
+        attempts: Arc<AtomicU64>,
+    }
+
+    #[async_trait]
+    impl PlaceLookup for FailingPlacesClient {
+        async fn lookup_place(&self, _row: &NormalizedRow) -> AppResult<PlaceDetails> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            Err(AppError::Config("always failing".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn configured_max_attempts_limits_retries() {
+        let attempts = Arc::new(AtomicU64::new(0));
+        let (_dir, _db, _project_id, mut normalizer) = normalizer_fixture(
+            &[],
+            FailingPlacesClient {
+                attempts: attempts.clone(),
+            },
+        );
+        let mut config = AppConfig::from_env();
+        config.places_retry_max_attempts = 2;
+        config.places_retry_base_backoff_ms = 1;
+        config.places_retry_max_backoff_ms = 1;
+        normalizer.retry = RetryPolicy::from_config(&config);
+
+        let row = row("Unreachable", 2.0, 1.0, None);
+        assert!(normalizer.lookup_with_retry(1, &row).await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(normalizer.backoff_delay(10) < Duration::from_millis(2));
+    }
+
+    #[tokio::test]
+    async fn project_rate_limit_overrides_global_during_normalization() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let bootstrap = bootstrap(dir.path(), "places.db", &vault).unwrap();
+        let db = Arc::new(Mutex::new(bootstrap.context.connection));
+        let project_id: i64 = db
+            .lock()
+            .query_row(
+                "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+
+        let lookup = PlacesService::from_lookup(Arc::new(TestPlacesClient::new(vec![])));
+        let normalizer = PlaceNormalizer::with_lookup(
+            db.clone(),
+            lookup,
+            8,
+            rand::rngs::StdRng::seed_from_u64(1),
+            Duration::from_secs(3600),
+        );
+
+        let stats = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(stats.rate_limit_qps, 8);
+
+        crate::projects::set_project_rate_limit(&db.lock(), project_id, Some(2)).unwrap();
+        let stats = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(stats.rate_limit_qps, 2);
+        assert_eq!(
+            normalizer.rate_limiter.interval_duration(project_id),
+            Duration::from_millis(125)
+        );
+        assert_eq!(normalizer.rate_limit_qps(), 8);
+
+        crate::projects::set_project_rate_limit(&db.lock(), project_id, Some(20)).unwrap();
+        let stats = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(stats.rate_limit_qps, 8);
+    }
+
+    #[test]
+    fn project_rate_limit_only_slows_its_own_project() {
+        let limiter = RateLimiter::new(8);
+        let pace = limiter.pace_project(1, Some(2));
+        assert_eq!(limiter.interval_duration(1), Duration::from_millis(500));
+        assert_eq!(limiter.interval_duration(2), Duration::from_millis(125));
+
+        limiter.set_qps(1);
+        assert_eq!(limiter.interval_duration(1), Duration::from_millis(1000));
+        assert_eq!(limiter.interval_duration(2), Duration::from_millis(1000));
+
+        limiter.set_qps(8);
+        drop(pace);
+        assert_eq!(limiter.interval_duration(1), Duration::from_millis(125));
+    }
+
+    #[tokio::test]
+    async fn dropped_run_clears_its_project_rate_limit() {
+        let rows = [row("First", 3.0, 4.0, None), row("Second", 5.0, 6.0, None)];
+        let (_dir, db, project_id, normalizer) =
+            normalizer_fixture(&rows, TestPlacesClient::new(vec![]));
+        crate::projects::set_project_rate_limit(&db.lock(), project_id, Some(2)).unwrap();
+
+        let run = normalizer.normalize_slot(project_id, ListSlot::A, None, None);
+        // The second lookup waits out the project's 500ms pace, so the run is still going.
+        assert!(tokio::time::timeout(Duration::from_millis(100), run)
+            .await
+            .is_err());
+        assert_eq!(
+            normalizer.rate_limiter.interval_duration(project_id),
+            Duration::from_millis(125)
+        );
+
+        normalizer.forget_project(project_id);
+        assert!(normalizer.guards.lock().is_empty());
+    }
+
+    #[test]
+    fn cache_ttl_updates_change_staleness() {
+        let (dir, db, _project_id, normalizer) =
+            normalizer_fixture(&[], TestPlacesClient::new(vec![]));
+        db.lock()
+            .execute(
+                "INSERT INTO normalization_cache (source_row_hash, place_id, created_at)
+                VALUES ('hash', 'aged_place', DATETIME('now', '-2 hours'))",
+                [],
+            )
+            .unwrap();
+        assert!(matches!(
+            normalizer.lookup_cache("hash").unwrap(),
+            CacheOutcome::Stale(_)
+        ));
+
+        let mut settings = UserSettings::load(
+            &crate::settings::settings_path(dir.path()),
+            &AppConfig::from_env(),
+        )
+        .unwrap();
+        for (hours, stale) in [(3, false), (0, false), (1, true)] {
+            settings.apply_patch(&UpdateRuntimeSettingsPayload {
+                telemetry_enabled: None,
+                places_rate_limit_qps: None,
+                map_style: None,
+                normalization_cache_ttl_hours: Some(hours),
+                offline: None,
+            });
+            normalizer.set_cache_ttl(settings.cache_ttl_hours());
+            let outcome = normalizer.lookup_cache("hash").unwrap();
+            assert_eq!(matches!(outcome, CacheOutcome::Stale(_)), stale, "{hours}h");
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelled_refresh_records_progress_for_resume() {
+        let rows: Vec<_> = (0..3)
+            .map(|index| row(&format!("Row {index}"), 2.0, 1.0 + index as f64, None))
+            .collect();
+        let (_dir, db, project_id, normalizer) =
+            normalizer_fixture(&rows, TestPlacesClient::new(vec![]));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let trigger = cancel.clone();
+        let observer: ProgressObserver = Arc::new(move |_| trigger.store(true, Ordering::SeqCst));
+        let stats = normalizer
+            .normalize_slot(project_id, ListSlot::A, Some(observer), Some(cancel))
+            .await
+            .unwrap();
+        assert_eq!(stats.resumed_from, None);
+        assert_eq!(stats.unresolved, 2);
+        let state: (String, i64, i64, i64) = db
+            .lock()
+            .query_row(
+                "SELECT status, processed, total_rows, resolved FROM refresh_state",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(state, ("cancelled".to_string(), 1, 3, 1));
+
+        let resumed = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(resumed.resumed_from, Some(1));
+        assert_eq!(resumed.resolved, 3);
+        assert_eq!(resumed.cache_hits, 1);
+
+        let again = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(again.resumed_from, None);
+    }
+
+    #[tokio::test]
+    async fn refresh_progress_is_written_once_per_chunk() {
+        let rows: Vec<_> = (0..30)
+            .map(|index| row(&format!("Row {index}"), 2.0, 1.0 + index as f64, None))
+            .collect();
+        let (_dir, db, project_id, normalizer) =
+            normalizer_fixture(&rows, TestPlacesClient::new(vec![]));
+        normalizer.set_rate_limit(20);
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let sink = recorded.clone();
+        let reader = db.clone();
+        let observer: ProgressObserver = Arc::new(move |_| {
+            let processed: i64 = reader
+                .lock()
+                .query_row("SELECT processed FROM refresh_state", [], |row| row.get(0))
+                .unwrap();
+            sink.lock().push(processed);
+        });
+        normalizer
+            .normalize_slot(project_id, ListSlot::A, Some(observer), None)
+            .await
+            .unwrap();
+
+        let mut expected = vec![0; 24];
+        expected.extend([25; 6]);
+        assert_eq!(*recorded.lock(), expected);
+        let (status, processed): (String, i64) = db
+            .lock()
+            .query_row("SELECT status, processed FROM refresh_state", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((status.as_str(), processed), ("complete", 30));
+    }
+
+    #[tokio::test]
+    async fn retrying_pending_rows_skips_resolved_ones() {
+        let rows: Vec<_> = (0..3)
+            .map(|index| row(&format!("Row {index}"), 2.0, 1.0 + index as f64, None))
+            .collect();
+        let (_dir, db, project_id, normalizer) =
+            normalizer_fixture(&rows, TestPlacesClient::new(vec![]));
+        // The first pass stops after one row, as it would when a quota outage aborts it.
+        let cancel = Arc::new(AtomicBool::new(false));
+        let trigger = cancel.clone();
+        let observer: ProgressObserver = Arc::new(move |_| trigger.store(true, Ordering::SeqCst));
+        let first = normalizer
+            .normalize_slot(project_id, ListSlot::A, Some(observer), Some(cancel))
+            .await
+            .unwrap();
+        assert_eq!(first.resolved, 1);
+        assert_eq!(first.unresolved, 2);
+
+        let totals = Arc::new(Mutex::new(Vec::new()));
+        let sink = totals.clone();
+        let observer: ProgressObserver =
+            Arc::new(move |progress: NormalizationProgress| sink.lock().push(progress.total_rows));
+        let retried = normalizer
+            .refresh_slots(
+                project_id,
+                &[ListSlot::A],
+                RefreshScope::Pending,
+                Some(observer),
+                None,
+            )
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(retried.total_rows, 2);
+        assert_eq!(retried.resolved, 2);
+        assert_eq!(retried.cache_hits, 0);
+        assert_eq!(retried.places_calls, 2);
+        assert_eq!(*totals.lock(), vec![2, 2]);
+
+        let conn = db.lock();
+        let assigned: i64 = conn
+            .query_row("SELECT COUNT(*) FROM list_places", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(assigned, 3);
+        let status: String = conn
+            .query_row("SELECT status FROM refresh_state", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(status, "cancelled");
+        drop(conn);
+
+        let nothing_left = normalizer
+            .refresh_slots(
+                project_id,
+                &[ListSlot::A],
+                RefreshScope::Pending,
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(nothing_left.total_rows, 0);
+        assert_eq!(nothing_left.places_calls, 0);
+    }
+
+    #[test]
+    fn throttled_progress_keeps_the_final_tick() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let observer = throttle_progress(
+            Arc::new(move |progress: NormalizationProgress| {
+                sink.lock().push((progress.slot, progress.processed))
+            }),
+            3,
+            Duration::from_secs(3600),
+        );
+        for slot in [ListSlot::A, ListSlot::B] {
+            for processed in 1..=7 {
+                observer(NormalizationProgress {
+                    slot,
+                    total_rows: 7,
+                    processed,
+                    resolved: processed,
+                    resumed_from: None,
+                });
+            }
+        }
+        assert_eq!(
+            *seen.lock(),
+            vec![
+                (ListSlot::A, 3),
+                (ListSlot::A, 6),
+                (ListSlot::A, 7),
+                (ListSlot::B, 3),
+                (ListSlot::B, 6),
+                (ListSlot::B, 7),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn thousand_row_normalize_records_every_assignment() {
+        let rows: Vec<_> = (0..1_000)
+            .map(|index| {
+                let place_id = format!("pid{index}");
+                row(
+                    &format!("Row {index}"),
+                    1.0,
+                    index as f64 / 100.0,
+                    Some(&place_id),
+                )
+            })
+            .collect();
+        let (_dir, db, project_id, normalizer) =
+            normalizer_fixture(&rows, TestPlacesClient::new(vec![]));
+        let assignments = || -> Vec<(String, String)> {
+            db.lock()
+                .prepare(
+                    "SELECT place_id, source_row_hash FROM list_places
+                    ORDER BY CAST(SUBSTR(place_id, 4) AS INTEGER)",
+                )
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+        let expected: Vec<(String, String)> = (0..1_000)
+            .map(|index| (format!("pid{index}"), format!("hash{index}")))
+            .collect();
+
+        let stats = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(stats.resolved, 1_000);
+        assert_eq!(assignments(), expected);
+
+        normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(assignments(), expected);
+    }
+
+    #[tokio::test]
+    async fn failed_normalization_keeps_previous_assignments() {
+        let rows: Vec<_> = (0..3)
+            .map(|index| {
+                let place_id = format!("pid{index}");
+                row(&format!("Row {index}"), index as f64, 1.0, Some(&place_id))
+            })
+            .collect();
+        let (_dir, db, project_id, normalizer) =
+            normalizer_fixture(&rows, TestPlacesClient::new(vec![]));
+        let assigned = || -> Vec<String> {
+            db.lock()
+                .prepare("SELECT place_id FROM list_places ORDER BY place_id")
+                .unwrap()
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(assigned(), vec!["pid0", "pid1", "pid2"]);
+
+        db.lock()
+            .execute_batch(
+                "CREATE TRIGGER fail_last_assignment BEFORE INSERT ON list_places
+                WHEN NEW.place_id = 'pid2'
+                BEGIN SELECT RAISE(ABORT, 'simulated failure'); END;",
+            )
+            .unwrap();
+        let err = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("simulated failure"));
+        assert_eq!(assigned(), vec!["pid0", "pid1", "pid2"]);
+    }
+
+    #[test]
+    fn purge_stale_cache_removes_only_expired_entries() {
+        let (_dir, db, _project_id, normalizer) =
+            normalizer_fixture(&[], TestPlacesClient::new(vec![]));
+        normalizer.set_cache_ttl(2);
+        db.lock()
+            .execute_batch(
+                "INSERT INTO normalization_cache (source_row_hash, place_id, created_at) VALUES
+                    ('old_a', 'p1', DATETIME('now', '-5 hours')),
+                    ('old_b', 'p2', DATETIME('now', '-3 hours')),
+                    ('fresh', 'p3', DATETIME('now', '-10 minutes'));",
+            )
+            .unwrap();
+
+        let plan: String = db
+            .lock()
+            .query_row(
+                "EXPLAIN QUERY PLAN DELETE FROM normalization_cache
+                WHERE created_at < DATETIME('now', '-1 seconds')",
+                [],
+                |row| row.get(3),
+            )
+            .unwrap();
+        assert!(plan.contains("idx_norm_cache_created"), "{plan}");
+
+        assert_eq!(normalizer.purge_stale_cache().unwrap(), 2);
+        let remaining: Vec<String> = db
+            .lock()
+            .prepare("SELECT source_row_hash FROM normalization_cache")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(remaining, vec!["fresh"]);
+
+        normalizer.set_cache_ttl(0);
+        assert_eq!(normalizer.purge_stale_cache().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn clearing_project_cache_forces_api_lookups() {
+        let rows: Vec<_> = (0..2)
+            .map(|index| row(&format!("Row {index}"), 10.0 + index as f64, 5.0, None))
+            .collect();
+        let (_dir, db, project_id, normalizer) =
+            normalizer_fixture(&rows, TestPlacesClient::new(vec![]));
+        db.lock()
+            .execute(
+                "INSERT INTO normalization_cache (source_row_hash, place_id) VALUES ('elsewhere', 'other')",
+                [],
+            )
+            .unwrap();
+
+        let first = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(first.places_calls, 2);
+        let cached = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!((cached.cache_hits, cached.places_calls), (2, 0));
+
+        assert_eq!(normalizer.clear_cache(Some(project_id)).unwrap(), 2);
+        let refreshed = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!((refreshed.cache_hits, refreshed.places_calls), (0, 2));
+
+        assert_eq!(normalizer.clear_cache(None).unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn dry_run_projects_the_real_runs_places_calls() {
+        let rows: Vec<_> = (0..3)
+            .map(|index| row(&format!("Row {index}"), 8.0, 7.0 + index as f64, None))
+            .collect();
+        let (_dir, db, project_id, normalizer) =
+            normalizer_fixture(&rows, TestPlacesClient::new(vec![]));
+        db.lock()
+            .execute_batch(
+                "INSERT INTO normalization_cache (source_row_hash, place_id) VALUES ('hash0', 'cached_place');
+                INSERT INTO normalization_cache (source_row_hash, place_id, created_at)
+                    VALUES ('hash1', 'stale_place', DATETIME('now', '-2 hours'));",
+            )
+            .unwrap();
+
+        let projected = normalizer
+            .normalize_slot_dry_run(project_id, ListSlot::A)
+            .await
+            .unwrap();
+        assert_eq!(projected.total_rows, 3);
+        assert_eq!(
+            (
+                projected.cache_hits,
+                projected.stale_cache,
+                projected.cache_misses
+            ),
+            (1, 1, 2)
+        );
+        assert_eq!(projected.places_counters.total_requests, 0);
+        let (assigned, cached): (i64, i64) = db
+            .lock()
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM list_places), (SELECT COUNT(*) FROM normalization_cache)",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((assigned, cached), (0, 2));
+
+        let actual = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(projected.places_calls, actual.places_calls);
+        assert_eq!(projected.places_calls, 2);
+        assert_eq!(
+            (projected.cache_hits, projected.resolved),
+            (actual.cache_hits, actual.resolved)
+        );
+    }
+
+    #[tokio::test]
+    async fn places_at_null_island_keep_their_coordinates() {
+        let (_dir, db, project_id, normalizer) = normalizer_fixture(
+            &[
+                row("Soul Buoy", 0.0005, 0.0, None),
+                row("Origin", 0.0, 0.0, None),
+            ],
+            TestPlacesClient::new(vec![
+                Ok(PlaceDetails {
+                    place_id: "origin_place".into(),
+                    name: "Origin".into(),
+                    formatted_address: None,
+                    location: None,
+                    types: Vec::new(),
+                    confidence: None,
+                    photo_name: None,
+                    enrichment: PlaceEnrichment::default(),
+                }),
+                Ok(PlaceDetails {
+                    place_id: "soul_buoy".into(),
+                    name: "Soul Buoy".into(),
+                    formatted_address: None,
+                    location: Some((0.0, 0.0)),
+                    types: Vec::new(),
+                    confidence: None,
+                    photo_name: None,
+                    enrichment: PlaceEnrichment::default(),
+                }),
+            ]),
+        );
+
+        let stats = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(stats.places_calls, 2);
+        let stored = |place_id: &str| -> (f64, f64) {
+            db.lock()
+                .query_row(
+                    "SELECT lat, lng FROM places WHERE place_id = ?1",
+                    [place_id],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap()
+        };
+        assert_eq!(stored("soul_buoy"), (0.0, 0.0));
+        assert_eq!(stored("origin_place"), (0.0, 0.0));
+    }
+
+    #[test]
+    fn refresh_registry_rejects_overlapping_runs() {
+        let registry = RefreshRegistry::default();
+        assert!(!registry.cancel());
+
+        let first = registry.begin().expect("first refresh");
+        let second = registry.begin();
+        assert!(matches!(second, Err(AppError::Config(_))));
+
+        assert!(registry.cancel());
+        assert!(first.is_cancelled());
+        drop(first);
+        assert!(!registry.cancel());
+
+        let next = registry.begin().expect("refresh after first finished");
+        assert!(!next.is_cancelled());
+        assert!(!next.cancel_flag().load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn normalized_places_expose_last_checked_at_in_comparisons() {
+        let (_dir, db, project_id, normalizer) = normalizer_fixture(
+            &[row("Shared", 5.0, 4.0, None)],
+            TestPlacesClient::new(vec![]),
+        );
+        {
+            let conn = db.lock();
+            conn.execute(
+                "INSERT INTO lists (project_id, slot, name, source) VALUES (?1, 'B', 'List B', 'test')",
+                [project_id],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO raw_items (list_id, source_row_hash, raw_json)
+                SELECT ?1, source_row_hash, raw_json FROM raw_items",
+                [conn.last_insert_rowid()],
+            )
+            .unwrap();
+        }
+        normalizer
+            .refresh_slots(
+                project_id,
+                &[ListSlot::A, ListSlot::B],
+                RefreshScope::All,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let conn = db.lock();
+        let snapshot = crate::comparison::compute_snapshot(&conn, project_id, None).unwrap();
+        let row = &snapshot.overlap.rows[0];
+        let checked = row.last_checked_at.clone().expect("last_checked_at set");
+        assert_eq!(
+            serde_json::to_value(row).unwrap()["last_checked_at"],
+            checked.as_str()
+        );
+        let feature =
+            crate::comparison::place_feature(row, crate::comparison::ComparisonSegment::Overlap);
+        assert_eq!(feature["properties"]["last_checked_at"], checked.as_str());
+    }
+
+    #[tokio::test]
+    async fn synthetic_fallback_assignments_are_labelled() {
+        let (_dir, db, project_id, normalizer) = normalizer_fixture(
+            &[
+                row("Offline", 8.0, 7.0, None),
+                row("Known", 9.0, 7.0, Some("ChIJknown")),
+            ],
+            SyntheticPlacesClient,
+        );
+        let stats = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(stats.resolved, 2);
+
+        let conn = db.lock();
+        let snapshot = crate::comparison::compute_snapshot(&conn, project_id, None).unwrap();
+        let sources: Vec<_> = snapshot
+            .only_a
+            .rows
+            .iter()
+            .map(|row| (row.name.as_str(), row.resolution_source.as_deref()))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![("Known", Some("provided")), ("Offline", Some("synthetic"))]
+        );
+        let offline = &snapshot.only_a.rows[1];
+        assert!(offline.place_id.starts_with(SYNTHETIC_PLACE_PREFIX));
+        let feature =
+            crate::comparison::place_feature(offline, crate::comparison::ComparisonSegment::OnlyA);
+        assert_eq!(feature["properties"]["resolution_source"], "synthetic");
+    }
+
+    #[tokio::test]
+    async fn offline_normalization_makes_no_api_calls() {
+        let client = TestPlacesClient::new(vec![Ok(PlaceDetails {
+            place_id: "should_not_be_used".into(),
+            name: "Unused".into(),
+            formatted_address: None,
+            location: Some((4.0, 3.0)),
+            types: Vec::new(),
+            confidence: None,
+            photo_name: None,
+            enrichment: PlaceEnrichment::default(),
+        })]);
+        let responses = client.responses.clone();
+        let rows: Vec<_> = (0..2)
+            .map(|index| row(&format!("Row {index}"), 4.0, 3.0 + index as f64, None))
+            .collect();
+        let (_dir, db, project_id, normalizer) = normalizer_fixture(&rows, client);
+        normalizer.set_offline(true);
+
+        let stats = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(stats.places_calls, 0);
+        assert_eq!(stats.resolved, 0);
+        assert_eq!(stats.unresolved, 2);
+        assert_eq!(responses.lock().len(), 1);
+
+        let snapshot = crate::comparison::compute_snapshot(&db.lock(), project_id, None).unwrap();
+        assert_eq!(snapshot.stats.pending_a, 2);
+
+        normalizer.set_offline(false);
+        let online = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(online.places_calls, 2);
+        assert!(responses.lock().is_empty());
+    }
+
+    struct ForbiddenPlacesClient {
+        url: String,
+    }
+
+    #[async_trait]
+    impl PlaceLookup for ForbiddenPlacesClient {
+        async fn lookup_place(&self, _row: &NormalizedRow) -> AppResult<PlaceDetails> {
+            reqwest::get(&self.url).await?.error_for_status()?;
+            unreachable!("the test server always answers 403")
+        }
+    }
+
+    #[tokio::test]
+    async fn disabled_synthetic_fallback_surfaces_http_errors() {
+        use httptest::{matchers::request, responders::status_code, Expectation, Server};
+
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::path("/places"))
+                .times(3)
+                .respond_with(status_code(403)),
+        );
+        let url = server.url("/places").to_string();
+
+        let forbidden = |allow_fallback: bool| {
+            HybridPlacesClient::new(
+                Box::new(ForbiddenPlacesClient { url: url.clone() }),
+                allow_fallback,
+            )
+        };
+        let row = row("Cafe", 6.0, 5.0, None);
+        let (_dir, db, project_id, strict) =
+            normalizer_fixture(std::slice::from_ref(&row), forbidden(false));
+        let err = strict
+            .lookup_with_retry(project_id, &row)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            classify_places_error(&err),
+            PlacesErrorKind::InvalidKey
+        ));
+
+        let stats = strict
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(stats.resolved, 0);
+        assert_eq!(stats.unresolved, 1);
+        let synthetic_ids: i64 = db
+            .lock()
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM places WHERE place_id LIKE 'synthetic_%')
+                      + (SELECT COUNT(*) FROM list_places WHERE place_id LIKE 'synthetic_%')
+                      + (SELECT COUNT(*) FROM normalization_cache WHERE place_id LIKE 'synthetic_%')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(synthetic_ids, 0);
+
+        let (_lenient_dir, _, _, lenient) = normalizer_fixture(&[], forbidden(true));
+        let details = lenient.lookup_with_retry(1, &row).await.unwrap();
+        assert!(details.place_id.starts_with(SYNTHETIC_PLACE_PREFIX));
+    }
+
+    #[test]
+    fn match_confidence_rewards_same_name_and_nearby_candidates() {
+        let row = row("Blue Bottle Coffee", 37.7956, -122.3935, None);
+
+        let exact = match_confidence(&row, Some("Blue Bottle Coffee"), Some((37.7956, -122.3935)));
+        let renamed_far = match_confidence(&row, Some("Zuni Cafe"), Some((37.7735, -122.4216)));
+        assert!(exact > 0.99, "exact match scored {exact}");
+        assert!(
+            renamed_far < 0.4,
+            "renamed, distant match scored {renamed_far}"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let conn = bootstrap(dir.path(), "confidence.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        conn.execute(
+            "INSERT INTO lists (project_id, slot, name, source)
+            SELECT id, 'A', 'List A', 'test' FROM comparison_projects WHERE is_active = 1",
+            [],
+        )
+        .unwrap();
+        let assignment = |confidence| Assignment {
+            source_hash: "hash".into(),
+            details: PlaceDetails {
+                place_id: "blue_bottle".into(),
+                name: "Blue Bottle Coffee".into(),
+                formatted_address: None,
+                location: Some((37.7956, -122.3935)),
+                types: Vec::new(),
+                confidence,
+                photo_name: None,
+                enrichment: PlaceEnrichment::default(),
+            },
+            source: ResolutionSource::Api,
+        };
+        let stored = |conn: &Connection| -> Option<f64> {
+            conn.query_row(
+                "SELECT confidence FROM normalization_cache WHERE source_row_hash = 'hash'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        write_assignment(&conn, 1, &assignment(Some(exact))).unwrap();
+        assert_eq!(stored(&conn), Some(exact));
+        write_assignment(&conn, 1, &assignment(None)).unwrap();
+        assert_eq!(stored(&conn), Some(exact));
+    }
+
+    #[test]
+    fn place_photo_url_uses_stored_photo_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let conn = bootstrap(dir.path(), "photos.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        conn.execute(
+            "INSERT INTO lists (project_id, slot, name, source)
+            SELECT id, 'A', 'List A', 'test' FROM comparison_projects WHERE is_active = 1",
+            [],
+        )
+        .unwrap();
+        for (place_id, photo_name) in [
+            ("with_photo", Some("places/with_photo/photos/abc123")),
+            ("without_photo", None),
+        ] {
+            let assignment = Assignment {
+                source_hash: format!("hash_{place_id}"),
+                details: PlaceDetails {
+                    place_id: place_id.into(),
+                    name: place_id.into(),
+                    formatted_address: None,
+                    location: Some((1.0, 2.0)),
+                    types: Vec::new(),
+                    confidence: None,
+                    photo_name: photo_name.map(str::to_string),
+                    enrichment: PlaceEnrichment::default(),
+                },
+                source: ResolutionSource::Api,
+            };
+            write_assignment(&conn, 1, &assignment).unwrap();
+        }
+
+        let key = SecretString::new("test-key".into());
+        assert_eq!(
+            place_photo_url(&conn, Some(&key), "with_photo", Some(800)).unwrap(),
+            Some(
+                "https://places.googleapis.com/v1/places/with_photo/photos/abc123/media?maxWidthPx=800&key=test-key"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            place_photo_url(&conn, Some(&key), "without_photo", None).unwrap(),
+            None
+        );
+        assert!(place_photo_url(&conn, Some(&key), "missing", None).is_err());
+    }
+
+    #[tokio::test]
+    async fn empty_text_search_falls_back_to_nearby_search() {
+        use httptest::{matchers::request, responders::json_encoded, Expectation, Server};
+
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::path("/places:searchText"))
+                .respond_with(json_encoded(serde_json::json!({}))),
+        );
+        server.expect(
+            Expectation::matching(request::path("/places:searchNearby")).respond_with(
+                json_encoded(serde_json::json!({
+                    "places": [
+                        {
+                            "id": "farther",
+                            "displayName": { "text": "Corner Shop" },
+                            "location": { "latitude": 6.003, "longitude": 5.0 }
+                        },
+                        {
+                            "id": "closest",
+                            "displayName": { "text": "Harbour Kiosk" },
+                            "location": { "latitude": 6.0002, "longitude": 5.0 }
+                        }
+                    ]
+                })),
+            ),
+        );
+
+        let counters = Arc::new(PlacesClientCounters::default());
+        let client = HttpPlacesClient::new(
+            SecretString::new("test-key".into()),
+            &server.url("/").to_string(),
+            reqwest::Client::builder(),
+            Arc::clone(&counters),
+        );
+        let row = row("kiosk by the pier (old)", 6.0, 5.0, None);
+
+        let details = client.lookup_place(&row).await.unwrap();
+        assert_eq!(details.place_id, "closest");
+        assert_eq!(details.name, "Harbour Kiosk");
+        assert_eq!(details.location, Some((6.0002, 5.0)));
+        assert_eq!(counters.snapshot().successes, 2);
+    }
+
+    #[tokio::test]
+    async fn routes_places_requests_through_configured_proxy() {
+        use httptest::matchers::{all_of, contains, request};
+        use httptest::{responders::json_encoded, Expectation, Server};
+
+        // The proxy receives the absolute-form request for the unreachable Places host.
+        let proxy = Server::run();
+        proxy.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/places:searchText"),
+                request::headers(contains(("proxy-authorization", "Basic dXNlcjpzZWNyZXQ="))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "places": [
+                    {
+                        "id": "via-proxy",
+                        "displayName": { "text": "Proxied Cafe" },
+                        "location": { "latitude": 6.0, "longitude": 5.0 }
+                    }
+                ]
+            }))),
+        );
+        let settings = crate::config::ProxySettings {
+            url: proxy.url("/").to_string(),
+            username: Some("user".into()),
+            password: Some(SecretString::new("secret".into())),
+        };
+        let client = HttpPlacesClient::new(
+            SecretString::new("test-key".into()),
+            "http://places.invalid",
+            settings.apply(reqwest::Client::builder()).unwrap(),
+            Arc::new(PlacesClientCounters::default()),
+        );
+        let row = row("Proxied Cafe", 6.0, 5.0, None);
+
+        let details = client.lookup_place(&row).await.unwrap();
+        assert_eq!(details.place_id, "via-proxy");
+    }
+
+    #[tokio::test]
+    async fn persists_rating_and_contact_details_into_snapshots() {
+        use httptest::{matchers::request, responders::json_encoded, Expectation, Server};
+
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::path("/places:searchText")).respond_with(json_encoded(
+                serde_json::json!({
+                    "places": [
+                        {
+                            "id": "rated",
+                            "displayName": { "text": "Rated Bistro" },
+                            "location": { "latitude": 2.0, "longitude": 1.0 },
+                            "rating": 4.6,
+                            "userRatingCount": 812,
+                            "internationalPhoneNumber": "+1 555-0100",
+                            "websiteUri": "https://bistro.example",
+                            "regularOpeningHours": {
+                                "weekdayDescriptions": ["Monday: 9:00 AM – 5:00 PM"]
+                            }
+                        }
+                    ]
+                }),
+            )),
+        );
+
+        let http = HttpPlacesClient::new(
+            SecretString::new("test-key".into()),
+            &server.url("/").to_string(),
+            reqwest::Client::builder(),
+            Arc::new(PlacesClientCounters::default()),
+        );
+        let (_dir, db, project_id, normalizer) =
+            normalizer_fixture(&[row("Rated Bistro", 2.0, 1.0, None)], http);
+        let stats = normalizer
+            .normalize_slot(project_id, ListSlot::A, None, None)
+            .await
+            .unwrap();
+        assert_eq!(stats.resolved, 1);
+
+        let enrichment = normalizer
+            .load_place_by_id("rated")
+            .unwrap()
+            .unwrap()
+            .enrichment;
+        assert_eq!(enrichment.rating, Some(4.6));
+        assert_eq!(enrichment.user_rating_count, Some(812));
+
+        let conn = db.lock();
+        let snapshot = comparison::compute_snapshot(&conn, project_id, None).unwrap();
+        let row = &snapshot.only_a.rows[0];
+        assert_eq!(row.place_id, "rated");
+        assert_eq!(row.enrichment.rating, Some(4.6));
+        assert_eq!(row.enrichment.phone_number.as_deref(), Some("+1 555-0100"));
+        assert_eq!(
+            row.enrichment.website_uri.as_deref(),
+            Some("https://bistro.example")
+        );
+        assert_eq!(
+            row.enrichment.opening_hours,
+            Some(vec!["Monday: 9:00 AM – 5:00 PM".to_string()])
+        );
+    }
+
+    struct GatedPlacesClient {
+        gated_title: String,
+        gate: Arc<tokio::sync::Notify>,
+    }
+
+    #[async_trait]
+    impl PlaceLookup for GatedPlacesClient {
+        async fn lookup_place(&self, row: &NormalizedRow) -> AppResult<PlaceDetails> {
+            if row.title == self.gated_title {
+                self.gate.notified().await;
+            }
+            Ok(details_from_row(row, format!("place_{}", row.title)))
+        }
+    }
+
+    #[tokio::test]
+    async fn different_projects_normalize_concurrently() {
+        let gate = Arc::new(tokio::sync::Notify::new());
+        let (_dir, db, project_a, normalizer) = normalizer_fixture(
+            &[row("Slow", 2.0, 1.0, None)],
+            GatedPlacesClient {
+                gated_title: "Slow".into(),
+                gate: gate.clone(),
+            },
+        );
+        let project_b = {
+            let conn = db.lock();
+            conn.execute(
+                "INSERT INTO comparison_projects (name, slug) VALUES ('Second', 'second')",
+                [],
+            )
+            .unwrap();
+            let project_b = conn.last_insert_rowid();
+            conn.execute(
+                "INSERT INTO lists (project_id, slot, name, source) VALUES (?1, 'A', 'List A', 'test')",
+                [project_b],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO raw_items (list_id, source_row_hash, raw_json) VALUES (?1, 'hash_fast', ?2)",
+                params![
+                    conn.last_insert_rowid(),
+                    serde_json::to_string(&row("Fast", 2.0, 1.0, None)).unwrap()
+                ],
+            )
+            .unwrap();
+            project_b
+        };
+
+        let slow = normalizer.normalize_slot(project_a, ListSlot::A, None, None);
+        let fast = async {
+            let stats = tokio::time::timeout(
+                Duration::from_secs(5),
+                normalizer.normalize_slot(project_b, ListSlot::A, None, None),
+            )
+            .await;
+            gate.notify_one();
+            stats
+        };
+        let (slow, fast) = tokio::join!(slow, fast);
+
+        let fast = fast
+            .expect("project B waited on project A's normalization")
+            .unwrap();
+        assert_eq!(fast.resolved, 1);
+        assert_eq!(slow.unwrap().resolved, 1);
+    }
 }
@@ -1,9 +1,16 @@
+use std::time::Instant;
+
 use rusqlite::{params, Connection, OptionalExtension, Row};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
 
-use crate::comparison::ComparisonStats;
+use crate::comparison::{
+    self, ComparisonPagination, ComparisonSnapshot, ComparisonStats, FuzzyMatchOptions,
+};
 use crate::db;
 use crate::errors::{AppError, AppResult};
+use crate::ingestion::ListSlot;
+use crate::settings::validate_qps;
 
 #[derive(Debug, Serialize, Clone)]
 pub struct ComparisonProjectRecord {
@@ -20,6 +27,9 @@ pub struct ComparisonProjectRecord {
     pub list_b_imported_at: Option<String>,
     pub list_a_drive_file: Option<DriveFileRecord>,
     pub list_b_drive_file: Option<DriveFileRecord>,
+    /// Slows this project's Places lookups while its lists are normalized; the global QPS
+    /// stays the ceiling.
+    pub places_rate_limit_qps: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -32,6 +42,59 @@ pub struct DriveFileRecord {
     pub md5_checksum: Option<String>,
 }
 
+/// One stored row of `comparison_runs`.
+#[derive(Debug, Serialize, Clone)]
+pub struct ComparisonRunRecord {
+    pub id: i64,
+    pub list_a_id: Option<i64>,
+    pub list_b_id: Option<i64>,
+    pub list_a_count: usize,
+    pub list_b_count: usize,
+    pub overlap_count: usize,
+    pub only_a_count: usize,
+    pub only_b_count: usize,
+    pub pending_a: usize,
+    pub pending_b: usize,
+    pub duration_ms: u64,
+    pub started_at: String,
+    pub completed_at: String,
+}
+
+pub const PROJECT_BUNDLE_VERSION: u32 = 1;
+
+/// Portable snapshot of a project: list metadata plus raw rows. Places are rebuilt by
+/// normalization after import, so they are not included.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectBundle {
+    pub version: u32,
+    pub name: String,
+    pub exported_at: String,
+    pub lists: Vec<ListBundle>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ListBundle {
+    pub slot: String,
+    pub name: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    pub source: String,
+    pub drive_file_id: Option<String>,
+    pub drive_file_name: Option<String>,
+    pub drive_file_mime: Option<String>,
+    pub drive_file_size: Option<i64>,
+    pub drive_modified_time: Option<String>,
+    pub drive_file_checksum: Option<String>,
+    pub imported_at: String,
+    pub raw_items: Vec<RawItemBundle>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RawItemBundle {
+    pub source_row_hash: String,
+    pub raw_json: String,
+}
+
 pub fn active_project_id(connection: &Connection) -> AppResult<i64> {
     connection
         .query_row(
@@ -52,6 +115,7 @@ pub fn list_projects(connection: &Connection) -> AppResult<Vec<ComparisonProject
             cp.updated_at,
             cp.is_active,
             COALESCE(cp.last_compared_at, lr.last_compared_at) AS last_compared_at,
+            cp.places_rate_limit_qps,
             la.id AS list_a_id,
             lb.id AS list_b_id,
             la.imported_at AS list_a_imported_at,
@@ -98,6 +162,7 @@ pub fn project_by_id(
                 cp.updated_at,
                 cp.is_active,
                 COALESCE(cp.last_compared_at, lr.last_compared_at) AS last_compared_at,
+                cp.places_rate_limit_qps,
                 la.id AS list_a_id,
                 lb.id AS list_b_id,
                 la.imported_at AS list_a_imported_at,
@@ -180,12 +245,34 @@ pub fn rename_project(
     project_by_id(connection, project_id)
 }
 
+/// Sets or clears (`None`) the project's Places QPS override, bounded like the global limit.
+pub fn set_project_rate_limit(
+    connection: &Connection,
+    project_id: i64,
+    qps: Option<u32>,
+) -> AppResult<ComparisonProjectRecord> {
+    let affected = connection.execute(
+        "UPDATE comparison_projects
+        SET places_rate_limit_qps = ?1, updated_at = DATETIME('now')
+        WHERE id = ?2",
+        params![qps.map(validate_qps).transpose()?, project_id],
+    )?;
+    if affected == 0 {
+        return Err(AppError::Config(format!(
+            "comparison project {project_id} not found"
+        )));
+    }
+    project_by_id(connection, project_id)
+}
+
+/// Activates a project. Only its `updated_at` is bumped, so the others keep the order in
+/// which they were last opened or edited for picking a fallback active project.
 pub fn set_active_project(connection: &Connection, project_id: i64) -> AppResult<()> {
     let affected = connection.execute(
         "UPDATE comparison_projects
         SET is_active = CASE WHEN id = ?1 THEN 1 ELSE 0 END,
-            updated_at = DATETIME('now')
-        WHERE id IN (SELECT id FROM comparison_projects)",
+            updated_at = CASE WHEN id = ?1 THEN DATETIME('now') ELSE updated_at END
+        WHERE EXISTS (SELECT 1 FROM comparison_projects WHERE id = ?1)",
         [project_id],
     )?;
     if affected == 0 {
@@ -196,6 +283,339 @@ pub fn set_active_project(connection: &Connection, project_id: i64) -> AppResult
     Ok(())
 }
 
+/// Repairs the single-active-project invariant after it was broken, e.g. by an interrupted
+/// migration. Keeps the most recently opened or edited active project, or promotes the most
+/// recently opened or edited project when none is active, and returns it.
+pub fn ensure_single_active_project(connection: &Connection) -> AppResult<ComparisonProjectRecord> {
+    let chosen: i64 = connection
+        .query_row(
+            "SELECT id FROM comparison_projects
+            ORDER BY is_active DESC, updated_at DESC, id DESC
+            LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::Config("no comparison projects exist".into()))?;
+    connection.execute(
+        "UPDATE comparison_projects
+        SET is_active = CASE WHEN id = ?1 THEN 1 ELSE 0 END
+        WHERE is_active != CASE WHEN id = ?1 THEN 1 ELSE 0 END",
+        [chosen],
+    )?;
+    project_by_id(connection, chosen)
+}
+
+/// Deletes a project together with its lists, raw items, place assignments, and comparison
+/// runs. When the active project is removed another one is promoted; returns the id of the
+/// project that is active afterwards.
+pub fn delete_project(connection: &mut Connection, project_id: i64) -> AppResult<i64> {
+    let existing = project_by_id(connection, project_id)?;
+    let tx = connection.transaction()?;
+    let remaining: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM comparison_projects WHERE id != ?1",
+        [project_id],
+        |row| row.get(0),
+    )?;
+    if remaining == 0 {
+        return Err(AppError::Config(
+            "cannot delete the last comparison project".into(),
+        ));
+    }
+
+    tx.execute(
+        "DELETE FROM comparison_runs
+        WHERE project_id = ?1
+            OR list_a_id IN (SELECT id FROM lists WHERE project_id = ?1)
+            OR list_b_id IN (SELECT id FROM lists WHERE project_id = ?1)",
+        [project_id],
+    )?;
+    // raw_items and list_places cascade from lists.
+    tx.execute("DELETE FROM lists WHERE project_id = ?1", [project_id])?;
+    tx.execute(
+        "DELETE FROM comparison_projects WHERE id = ?1",
+        [project_id],
+    )?;
+
+    if existing.is_active {
+        tx.execute(
+            "UPDATE comparison_projects
+            SET is_active = 1, updated_at = DATETIME('now')
+            WHERE id = (
+                SELECT id FROM comparison_projects ORDER BY updated_at DESC, id DESC LIMIT 1
+            )",
+            [],
+        )?;
+    }
+    let active_id = active_project_id(&tx)?;
+    tx.commit()?;
+    Ok(active_id)
+}
+
+/// Copies a project's lists and raw items into a new, inactive project. Place assignments are
+/// not copied; callers re-run normalization for the copy.
+pub fn duplicate_project(
+    connection: &mut Connection,
+    project_id: i64,
+) -> AppResult<ComparisonProjectRecord> {
+    let source = project_by_id(connection, project_id)?;
+    let tx = connection.transaction()?;
+    let name = unique_copy_name(&tx, &source.name)?;
+    let slug = unique_slug(&tx, &name)?;
+    tx.execute(
+        "INSERT INTO comparison_projects (name, slug, is_active) VALUES (?1, ?2, 0)",
+        (&name, &slug),
+    )?;
+    let copy_id = tx.last_insert_rowid();
+
+    let list_ids = {
+        let mut stmt = tx.prepare("SELECT id FROM lists WHERE project_id = ?1 ORDER BY slot")?;
+        let ids = stmt
+            .query_map([project_id], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        ids
+    };
+    for list_id in list_ids {
+        tx.execute(
+            "INSERT INTO lists (
+                project_id,
+                slot,
+                name,
+                display_name,
+                source,
+                drive_file_id,
+                drive_file_name,
+                drive_file_mime,
+                drive_file_size,
+                drive_modified_time,
+                drive_file_checksum,
+                imported_at
+            )
+            SELECT
+                ?1,
+                slot,
+                name,
+                display_name,
+                source,
+                drive_file_id,
+                drive_file_name,
+                drive_file_mime,
+                drive_file_size,
+                drive_modified_time,
+                drive_file_checksum,
+                imported_at
+            FROM lists WHERE id = ?2",
+            (copy_id, list_id),
+        )?;
+        let copied_list_id = tx.last_insert_rowid();
+        copy_raw_items(&tx, list_id, copied_list_id)?;
+    }
+    tx.commit()?;
+    project_by_id(connection, copy_id)
+}
+
+pub fn export_project_bundle(connection: &Connection, project_id: i64) -> AppResult<ProjectBundle> {
+    let project = project_by_id(connection, project_id)?;
+    let mut stmt = connection.prepare(
+        "SELECT
+            id,
+            slot,
+            name,
+            display_name,
+            source,
+            drive_file_id,
+            drive_file_name,
+            drive_file_mime,
+            drive_file_size,
+            drive_modified_time,
+            drive_file_checksum,
+            imported_at
+        FROM lists WHERE project_id = ?1 ORDER BY slot",
+    )?;
+    let lists = stmt
+        .query_map([project_id], |row| {
+            Ok((
+                row.get::<_, i64>("id")?,
+                ListBundle {
+                    slot: row.get("slot")?,
+                    name: row.get("name")?,
+                    display_name: row.get("display_name")?,
+                    source: row.get("source")?,
+                    drive_file_id: row.get("drive_file_id")?,
+                    drive_file_name: row.get("drive_file_name")?,
+                    drive_file_mime: row.get("drive_file_mime")?,
+                    drive_file_size: row.get("drive_file_size")?,
+                    drive_modified_time: row.get("drive_modified_time")?,
+                    drive_file_checksum: row.get("drive_file_checksum")?,
+                    imported_at: row.get("imported_at")?,
+                    raw_items: Vec::new(),
+                },
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut items_stmt = connection.prepare(
+        "SELECT source_row_hash, raw_json FROM raw_items WHERE list_id = ?1 ORDER BY id",
+    )?;
+    let mut bundled = Vec::with_capacity(lists.len());
+    for (list_id, mut list) in lists {
+        list.raw_items = items_stmt
+            .query_map([list_id], |row| {
+                Ok(RawItemBundle {
+                    source_row_hash: row.get(0)?,
+                    raw_json: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        bundled.push(list);
+    }
+
+    Ok(ProjectBundle {
+        version: PROJECT_BUNDLE_VERSION,
+        name: project.name,
+        exported_at: db::now_timestamp(),
+        lists: bundled,
+    })
+}
+
+/// Recreates a bundled project as a new, inactive project. Name and slug collisions are
+/// resolved by suffixing.
+pub fn import_project_bundle(
+    connection: &mut Connection,
+    bundle: &ProjectBundle,
+) -> AppResult<ComparisonProjectRecord> {
+    if bundle.version > PROJECT_BUNDLE_VERSION {
+        return Err(AppError::Config(format!(
+            "project bundle version {} is newer than supported version {PROJECT_BUNDLE_VERSION}",
+            bundle.version
+        )));
+    }
+    let trimmed = bundle.name.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::Config("project name cannot be empty".into()));
+    }
+    let slots = validate_bundle_lists(&bundle.lists)?;
+    let tx = connection.transaction()?;
+    let name = if name_exists(&tx, trimmed)? {
+        unique_copy_name(&tx, trimmed)?
+    } else {
+        trimmed.to_string()
+    };
+    let slug = unique_slug(&tx, &name)?;
+    tx.execute(
+        "INSERT INTO comparison_projects (name, slug, is_active) VALUES (?1, ?2, 0)",
+        (&name, &slug),
+    )?;
+    let project_id = tx.last_insert_rowid();
+
+    for (list, slot) in bundle.lists.iter().zip(slots) {
+        tx.execute(
+            "INSERT INTO lists (
+                project_id,
+                slot,
+                name,
+                display_name,
+                source,
+                drive_file_id,
+                drive_file_name,
+                drive_file_mime,
+                drive_file_size,
+                drive_modified_time,
+                drive_file_checksum,
+                imported_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                project_id,
+                slot.as_tag(),
+                list.name.trim(),
+                list.display_name
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty()),
+                list.source,
+                list.drive_file_id,
+                list.drive_file_name,
+                list.drive_file_mime,
+                list.drive_file_size,
+                list.drive_modified_time,
+                list.drive_file_checksum,
+                list.imported_at
+            ],
+        )?;
+        let list_id = tx.last_insert_rowid();
+        let mut insert = tx.prepare(
+            "INSERT OR IGNORE INTO raw_items (list_id, source_row_hash, raw_json)
+            VALUES (?1, ?2, ?3)",
+        )?;
+        for item in &list.raw_items {
+            insert.execute(params![list_id, item.source_row_hash, item.raw_json])?;
+        }
+    }
+    tx.commit()?;
+    project_by_id(connection, project_id)
+}
+
+/// Checks every bundled list before anything is written: each needs a known, unique slot and
+/// a non-blank name, as lists created in the app do.
+fn validate_bundle_lists(lists: &[ListBundle]) -> AppResult<Vec<ListSlot>> {
+    let mut slots = Vec::with_capacity(lists.len());
+    for list in lists {
+        let slot = ListSlot::parse(&list.slot).map_err(|_| {
+            AppError::Config(format!("bundle list has invalid slot: {}", list.slot))
+        })?;
+        if slots.contains(&slot) {
+            return Err(AppError::Config(format!(
+                "bundle has more than one list in slot {}",
+                slot.as_tag()
+            )));
+        }
+        if list.name.trim().is_empty() {
+            return Err(AppError::Config(format!(
+                "bundle list in slot {} has an empty name",
+                slot.as_tag()
+            )));
+        }
+        slots.push(slot);
+    }
+    Ok(slots)
+}
+
+fn copy_raw_items(connection: &Connection, from_list_id: i64, to_list_id: i64) -> AppResult<usize> {
+    let copied = connection.execute(
+        "INSERT INTO raw_items (list_id, source_row_hash, raw_json, created_at)
+        SELECT ?2, source_row_hash, raw_json, created_at FROM raw_items WHERE list_id = ?1",
+        (from_list_id, to_list_id),
+    )?;
+    Ok(copied)
+}
+
+/// Computes a project's snapshot and records it as a comparison run, which also moves the
+/// project's `last_compared_at`. Failing to record the run is logged rather than returned so
+/// the caller still gets its snapshot.
+pub fn run_comparison(
+    connection: &Connection,
+    project_id: i64,
+    pagination: Option<ComparisonPagination>,
+    fuzzy: Option<FuzzyMatchOptions>,
+) -> AppResult<(ComparisonSnapshot, u128)> {
+    let started_at = db::now_timestamp();
+    let timer = Instant::now();
+    let snapshot = comparison::compute_snapshot_with(connection, project_id, pagination, fuzzy)?;
+    let duration_ms = timer.elapsed().as_millis();
+    if let Err(err) = record_comparison_run(
+        connection,
+        project_id,
+        snapshot.lists.list_a_id,
+        snapshot.lists.list_b_id,
+        &snapshot.stats,
+        started_at,
+        duration_ms,
+    ) {
+        warn!(?err, "failed to persist comparison run history");
+    }
+    Ok((snapshot, duration_ms))
+}
+
 pub fn record_comparison_run(
     connection: &Connection,
     project_id: i64,
@@ -204,7 +624,7 @@ pub fn record_comparison_run(
     stats: &ComparisonStats,
     started_at: String,
     duration_ms: u128,
-) -> AppResult<()> {
+) -> AppResult<i64> {
     let completed_at = db::now_timestamp();
     connection.execute(
         "INSERT INTO comparison_runs (
@@ -238,15 +658,110 @@ pub fn record_comparison_run(
             completed_at
         ],
     )?;
+    let run_id = connection.last_insert_rowid();
+    snapshot_run_members(connection, project_id, run_id)?;
     connection.execute(
         "UPDATE comparison_projects
         SET last_compared_at = ?1, updated_at = DATETIME('now')
         WHERE id = ?2",
         (&completed_at, project_id),
     )?;
+    Ok(run_id)
+}
+
+/// Captures the project's list membership against `run_id` when it differs from the stored
+/// snapshot, then drops older snapshots. Runs are recorded on every comparison fetch, but only
+/// the latest membership is read back (by [`crate::comparison::compute_diff`]), so unchanged lists
+/// write nothing.
+fn snapshot_run_members(connection: &Connection, project_id: i64, run_id: i64) -> AppResult<()> {
+    const CURRENT_MEMBERS: &str = "SELECT l.slot, lp.place_id
+        FROM list_places lp
+        JOIN lists l ON l.id = lp.list_id
+        WHERE l.project_id = ?1";
+    if let Some(stored_run) = latest_member_snapshot(connection, project_id)? {
+        let changed: bool = connection.query_row(
+            &format!(
+                "SELECT EXISTS ({CURRENT_MEMBERS}
+                    EXCEPT SELECT slot, place_id FROM comparison_run_members WHERE run_id = ?2)
+                OR EXISTS (SELECT slot, place_id FROM comparison_run_members WHERE run_id = ?2
+                    EXCEPT {CURRENT_MEMBERS})"
+            ),
+            (project_id, stored_run),
+            |row| row.get(0),
+        )?;
+        if !changed {
+            return Ok(());
+        }
+    }
+    connection.execute(
+        &format!(
+            "INSERT INTO comparison_run_members (run_id, slot, place_id)
+            SELECT ?2, slot, place_id FROM ({CURRENT_MEMBERS})"
+        ),
+        (project_id, run_id),
+    )?;
+    connection.execute(
+        "DELETE FROM comparison_run_members
+        WHERE run_id IN (SELECT id FROM comparison_runs WHERE project_id = ?1 AND id <> ?2)",
+        (project_id, run_id),
+    )?;
     Ok(())
 }
 
+/// Returns the run holding the project's stored membership snapshot, if any.
+pub(crate) fn latest_member_snapshot(
+    connection: &Connection,
+    project_id: i64,
+) -> AppResult<Option<i64>> {
+    let run_id = connection.query_row(
+        "SELECT MAX(m.run_id)
+        FROM comparison_run_members m
+        JOIN comparison_runs r ON r.id = m.run_id
+        WHERE r.project_id = ?1",
+        [project_id],
+        |row| row.get(0),
+    )?;
+    Ok(run_id)
+}
+
+/// Returns a project's recorded comparison runs, newest first.
+pub fn comparison_run_history(
+    connection: &Connection,
+    project_id: i64,
+    limit: Option<usize>,
+) -> AppResult<Vec<ComparisonRunRecord>> {
+    let mut stmt = connection.prepare(
+        "SELECT id, list_a_id, list_b_id, list_a_count, list_b_count, overlap_count,
+                only_a_count, only_b_count, pending_a, pending_b, duration_ms,
+                started_at, completed_at
+        FROM comparison_runs
+        WHERE project_id = ?1
+        ORDER BY completed_at DESC, id DESC
+        LIMIT ?2",
+    )?;
+    let limit = limit.map(|value| value as i64).unwrap_or(-1);
+    let rows = stmt
+        .query_map(params![project_id, limit], |row| {
+            Ok(ComparisonRunRecord {
+                id: row.get(0)?,
+                list_a_id: row.get(1)?,
+                list_b_id: row.get(2)?,
+                list_a_count: row.get::<_, i64>(3)? as usize,
+                list_b_count: row.get::<_, i64>(4)? as usize,
+                overlap_count: row.get::<_, i64>(5)? as usize,
+                only_a_count: row.get::<_, i64>(6)? as usize,
+                only_b_count: row.get::<_, i64>(7)? as usize,
+                pending_a: row.get::<_, i64>(8)? as usize,
+                pending_b: row.get::<_, i64>(9)? as usize,
+                duration_ms: row.get::<_, i64>(10)?.max(0) as u64,
+                started_at: row.get(11)?,
+                completed_at: row.get(12)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
 fn unique_slug(connection: &Connection, name: &str) -> AppResult<String> {
     unique_slug_excluding(connection, name, None)
 }
@@ -266,6 +781,29 @@ fn unique_slug_excluding(
     Ok(candidate)
 }
 
+fn unique_copy_name(connection: &Connection, name: &str) -> AppResult<String> {
+    let base = format!("{name} (copy)");
+    let mut candidate = base.clone();
+    let mut counter = 1;
+    while name_exists(connection, &candidate)? {
+        counter += 1;
+        candidate = format!("{name} (copy {counter})");
+    }
+    Ok(candidate)
+}
+
+fn name_exists(connection: &Connection, name: &str) -> AppResult<bool> {
+    connection
+        .query_row(
+            "SELECT 1 FROM comparison_projects WHERE name = ?1 LIMIT 1",
+            [name],
+            |_| Ok::<(), rusqlite::Error>(()),
+        )
+        .optional()
+        .map(|opt| opt.is_some())
+        .map_err(AppError::from)
+}
+
 fn slug_exists(
     connection: &Connection,
     slug: &str,
@@ -319,6 +857,7 @@ fn project_from_row(row: &Row<'_>) -> ComparisonProjectRecord {
         list_b_imported_at: row.get("list_b_imported_at").unwrap_or(None),
         list_a_drive_file,
         list_b_drive_file,
+        places_rate_limit_qps: row.get("places_rate_limit_qps").unwrap_or(None),
     }
 }
 
@@ -345,3 +884,549 @@ fn drive_file_from_row(row: &Row<'_>, alias_prefix: &str) -> Option<DriveFileRec
         md5_checksum: checksum,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::db::bootstrap;
+    use crate::secrets::SecretVault;
+
+    fn seed_list(connection: &Connection, project_id: i64, slot: &str) -> i64 {
+        connection
+            .execute(
+                "INSERT INTO lists (project_id, slot, name) VALUES (?1, ?2, ?3)",
+                params![project_id, slot, format!("List {slot}")],
+            )
+            .unwrap();
+        let list_id = connection.last_insert_rowid();
+        connection
+            .execute(
+                "INSERT INTO raw_items (list_id, source_row_hash, raw_json) VALUES (?1, ?2, '{}')",
+                params![list_id, format!("hash-{list_id}")],
+            )
+            .unwrap();
+        list_id
+    }
+
+    #[test]
+    fn running_a_comparison_records_its_stats() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let conn = bootstrap(dir.path(), "projects.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let project = create_project(&conn, "Coffee", true).unwrap();
+        assert!(project.last_compared_at.is_none());
+        let list_a = seed_list(&conn, project.id, "A");
+        let list_b = seed_list(&conn, project.id, "B");
+        conn.execute_batch(&format!(
+            "INSERT INTO places (place_id, name, lat, lng)
+                VALUES ('shared', 'Shared', 1.0, 1.0), ('a_only', 'A only', 2.0, 2.0);
+            INSERT INTO list_places (list_id, place_id)
+                VALUES ({list_a}, 'shared'), ({list_a}, 'a_only'), ({list_b}, 'shared');"
+        ))
+        .unwrap();
+
+        let (snapshot, duration_ms) = run_comparison(&conn, project.id, None, None).unwrap();
+
+        let recorded: (i64, i64, i64, i64, i64, i64, i64, i64) = conn
+            .query_row(
+                "SELECT list_a_id, list_b_id, list_a_count, list_b_count, overlap_count,
+                        only_a_count, only_b_count, duration_ms
+                FROM comparison_runs WHERE project_id = ?1",
+                [project.id],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                        row.get(5)?,
+                        row.get(6)?,
+                        row.get(7)?,
+                    ))
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            recorded,
+            (
+                list_a,
+                list_b,
+                snapshot.stats.list_a_count as i64,
+                snapshot.stats.list_b_count as i64,
+                snapshot.stats.overlap_count as i64,
+                snapshot.stats.only_a_count as i64,
+                snapshot.stats.only_b_count as i64,
+                duration_ms as i64,
+            )
+        );
+        assert_eq!(snapshot.stats.overlap_count, 1);
+        assert_eq!(snapshot.stats.only_a_count, 1);
+        assert!(project_by_id(&conn, project.id)
+            .unwrap()
+            .last_compared_at
+            .is_some());
+    }
+
+    #[test]
+    fn lists_comparison_runs_newest_first() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let conn = bootstrap(dir.path(), "projects.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let project = create_project(&conn, "History", true).unwrap();
+        let stats = |overlap_count| ComparisonStats {
+            list_a_count: 4,
+            list_b_count: 3,
+            overlap_count,
+            only_a_count: 4 - overlap_count,
+            only_b_count: 3 - overlap_count,
+            pending_a: 0,
+            pending_b: 0,
+        };
+        let first = record_comparison_run(
+            &conn,
+            project.id,
+            None,
+            None,
+            &stats(1),
+            db::now_timestamp(),
+            12,
+        )
+        .unwrap();
+        let second = record_comparison_run(
+            &conn,
+            project.id,
+            None,
+            None,
+            &stats(2),
+            db::now_timestamp(),
+            34,
+        )
+        .unwrap();
+
+        let history = comparison_run_history(&conn, project.id, None).unwrap();
+        assert_eq!(
+            history.iter().map(|run| run.id).collect::<Vec<_>>(),
+            vec![second, first]
+        );
+        assert_eq!(history[0].overlap_count, 2);
+        assert_eq!(history[0].only_a_count, 2);
+        assert_eq!(history[0].duration_ms, 34);
+        assert_eq!(history[1].overlap_count, 1);
+        assert_eq!(history[1].only_b_count, 2);
+        assert_eq!(history[1].duration_ms, 12);
+
+        let latest = comparison_run_history(&conn, project.id, Some(1)).unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].id, second);
+    }
+
+    #[test]
+    fn run_members_are_stored_only_when_membership_changes() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let conn = bootstrap(dir.path(), "projects.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let project = create_project(&conn, "Members", true).unwrap();
+        conn.execute(
+            "INSERT INTO lists (project_id, slot, name, source) VALUES (?1, 'A', 'List A', 'test')",
+            [project.id],
+        )
+        .unwrap();
+        let list_id = conn.last_insert_rowid();
+        conn.execute_batch(&format!(
+            "INSERT INTO places (place_id, name, lat, lng) VALUES ('one', 'One', 1.0, 1.0), ('two', 'Two', 2.0, 2.0);
+            INSERT INTO list_places (list_id, place_id) VALUES ({list_id}, 'one'), ({list_id}, 'two');"
+        ))
+        .unwrap();
+        let stats = ComparisonStats {
+            list_a_count: 2,
+            list_b_count: 0,
+            overlap_count: 0,
+            only_a_count: 2,
+            only_b_count: 0,
+            pending_a: 0,
+            pending_b: 0,
+        };
+        let record = || {
+            record_comparison_run(
+                &conn,
+                project.id,
+                Some(list_id),
+                None,
+                &stats,
+                db::now_timestamp(),
+                1,
+            )
+            .unwrap()
+        };
+        let stored = || -> Vec<(i64, String)> {
+            conn.prepare("SELECT run_id, place_id FROM comparison_run_members ORDER BY place_id")
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        let first = record();
+        record();
+        record();
+        assert_eq!(
+            stored(),
+            vec![(first, "one".to_string()), (first, "two".to_string())]
+        );
+
+        conn.execute(
+            "DELETE FROM list_places WHERE list_id = ?1 AND place_id = 'two'",
+            [list_id],
+        )
+        .unwrap();
+        let changed = record();
+        assert_eq!(stored(), vec![(changed, "one".to_string())]);
+    }
+
+    #[test]
+    fn ensure_single_active_promotes_latest_when_none_active() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let conn = bootstrap(dir.path(), "projects.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let older = create_project(&conn, "Older", false).unwrap();
+        let newer = create_project(&conn, "Newer", false).unwrap();
+        conn.execute_batch(&format!(
+            "UPDATE comparison_projects SET is_active = 0, updated_at = '2024-01-01 00:00:00';
+            UPDATE comparison_projects SET updated_at = '2024-03-01 00:00:00' WHERE id = {};
+            UPDATE comparison_projects SET updated_at = '2024-02-01 00:00:00' WHERE id = {};",
+            newer.id, older.id
+        ))
+        .unwrap();
+        assert!(active_project_id(&conn).is_err());
+
+        let chosen = ensure_single_active_project(&conn).unwrap();
+        assert_eq!(chosen.id, newer.id);
+        assert!(chosen.is_active);
+        assert_eq!(active_project_id(&conn).unwrap(), newer.id);
+    }
+
+    #[test]
+    fn ensure_single_active_promotes_last_opened_over_newer_ids() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let conn = bootstrap(dir.path(), "projects.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let opened = create_project(&conn, "Opened", false).unwrap();
+        let untouched = create_project(&conn, "Untouched", false).unwrap();
+        conn.execute(
+            "UPDATE comparison_projects SET updated_at = '2024-01-01 00:00:00'",
+            [],
+        )
+        .unwrap();
+        set_active_project(&conn, opened.id).unwrap();
+        conn.execute("UPDATE comparison_projects SET is_active = 0", [])
+            .unwrap();
+
+        let chosen = ensure_single_active_project(&conn).unwrap();
+        assert!(untouched.id > opened.id);
+        assert_eq!(chosen.id, opened.id);
+    }
+
+    #[test]
+    fn ensure_single_active_keeps_one_of_several_active() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let conn = bootstrap(dir.path(), "projects.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let first = active_project_id(&conn).unwrap();
+        let second = create_project(&conn, "Second", false).unwrap();
+        let inactive = create_project(&conn, "Inactive", false).unwrap();
+        conn.execute_batch(&format!(
+            "UPDATE comparison_projects SET is_active = 1, updated_at = '2024-01-01 00:00:00'
+                WHERE id IN ({first}, {});
+            UPDATE comparison_projects SET updated_at = '2024-02-01 00:00:00' WHERE id = {first};
+            UPDATE comparison_projects SET is_active = 0, updated_at = '2024-06-01 00:00:00'
+                WHERE id = {};",
+            second.id, inactive.id
+        ))
+        .unwrap();
+
+        let chosen = ensure_single_active_project(&conn).unwrap();
+        assert_eq!(chosen.id, first);
+        let active: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM comparison_projects WHERE is_active = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(active, 1);
+
+        let again = ensure_single_active_project(&conn).unwrap();
+        assert_eq!(again.id, first);
+    }
+
+    #[test]
+    fn deletes_project_with_cascade_and_promotes_another() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let mut conn = bootstrap(dir.path(), "projects.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let original = active_project_id(&conn).unwrap();
+        let doomed = create_project(&conn, "Weekend trip", true).unwrap();
+        let list_a = seed_list(&conn, doomed.id, "A");
+        let list_b = seed_list(&conn, doomed.id, "B");
+        record_comparison_run(
+            &conn,
+            doomed.id,
+            Some(list_a),
+            Some(list_b),
+            &ComparisonStats {
+                list_a_count: 1,
+                list_b_count: 1,
+                overlap_count: 0,
+                only_a_count: 1,
+                only_b_count: 1,
+                pending_a: 0,
+                pending_b: 0,
+            },
+            db::now_timestamp(),
+            5,
+        )
+        .unwrap();
+
+        let active = delete_project(&mut conn, doomed.id).unwrap();
+        assert_eq!(active, original);
+        assert_eq!(active_project_id(&conn).unwrap(), original);
+        assert!(project_by_id(&conn, doomed.id).is_err());
+        let leftovers: (i64, i64, i64) = conn
+            .query_row(
+                "SELECT
+                    (SELECT COUNT(*) FROM lists WHERE project_id = ?1),
+                    (SELECT COUNT(*) FROM raw_items WHERE list_id IN (?2, ?3)),
+                    (SELECT COUNT(*) FROM comparison_runs WHERE project_id = ?1)",
+                params![doomed.id, list_a, list_b],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(leftovers, (0, 0, 0));
+
+        assert!(matches!(
+            delete_project(&mut conn, original),
+            Err(AppError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn deleting_the_active_project_promotes_the_last_opened_one() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let mut conn = bootstrap(dir.path(), "projects.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let opened = create_project(&conn, "Opened earlier", false).unwrap();
+        let newest = create_project(&conn, "Never opened", false).unwrap();
+        let current = create_project(&conn, "Current", false).unwrap();
+        conn.execute(
+            "UPDATE comparison_projects SET updated_at = '2020-01-01 00:00:00'",
+            [],
+        )
+        .unwrap();
+        set_active_project(&conn, opened.id).unwrap();
+        set_active_project(&conn, current.id).unwrap();
+
+        let active = delete_project(&mut conn, current.id).unwrap();
+        assert!(newest.id > opened.id);
+        assert_eq!(active, opened.id);
+        assert!(matches!(
+            set_active_project(&conn, current.id),
+            Err(AppError::Config(_))
+        ));
+        assert_eq!(active_project_id(&conn).unwrap(), opened.id);
+    }
+
+    #[test]
+    fn duplicates_lists_and_raw_items_into_inactive_copy() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let mut conn = bootstrap(dir.path(), "projects.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let source = create_project(&conn, "Food crawl", true).unwrap();
+        let source_list_a = seed_list(&conn, source.id, "A");
+        seed_list(&conn, source.id, "B");
+
+        let copy = duplicate_project(&mut conn, source.id).unwrap();
+        assert_ne!(copy.id, source.id);
+        assert_eq!(copy.name, "Food crawl (copy)");
+        assert_eq!(copy.slug, "food-crawl-copy");
+        assert!(!copy.is_active);
+        assert_eq!(active_project_id(&conn).unwrap(), source.id);
+        let copy_list_a = copy.list_a_id.expect("copied list A");
+        assert_ne!(Some(copy_list_a), source.list_a_id);
+        assert!(copy.list_b_id.is_some());
+
+        let copied_items: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM raw_items ri JOIN lists l ON l.id = ri.list_id
+                WHERE l.project_id = ?1",
+                [copy.id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(copied_items, 2);
+
+        conn.execute("DELETE FROM raw_items WHERE list_id = ?1", [copy_list_a])
+            .unwrap();
+        let source_items: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM raw_items WHERE list_id = ?1",
+                [source_list_a],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(source_items, 1);
+        assert_eq!(
+            duplicate_project(&mut conn, source.id).unwrap().name,
+            "Food crawl (copy 2)"
+        );
+    }
+
+    #[test]
+    fn project_bundle_round_trips_into_fresh_database() {
+        let source_dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let source_conn = bootstrap(source_dir.path(), "source.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let project = create_project(&source_conn, "Default", false).unwrap();
+        let list_a = seed_list(&source_conn, project.id, "A");
+        seed_list(&source_conn, project.id, "B");
+        source_conn
+            .execute(
+                "INSERT INTO raw_items (list_id, source_row_hash, raw_json) VALUES (?1, 'extra', '{}')",
+                [list_a],
+            )
+            .unwrap();
+
+        let bundle = export_project_bundle(&source_conn, project.id).unwrap();
+        let encoded = serde_json::to_string(&bundle).unwrap();
+
+        let target_dir = tempdir().unwrap();
+        let mut target_conn = bootstrap(target_dir.path(), "target.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let decoded: ProjectBundle = serde_json::from_str(&encoded).unwrap();
+        let first = import_project_bundle(&mut target_conn, &decoded).unwrap();
+        let second = import_project_bundle(&mut target_conn, &decoded).unwrap();
+        assert_ne!(first.slug, second.slug);
+        assert!(!first.is_active);
+
+        let imported: i64 = target_conn
+            .query_row(
+                "SELECT COUNT(*) FROM raw_items ri JOIN lists l ON l.id = ri.list_id
+                WHERE l.project_id = ?1",
+                [first.id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(imported, 3);
+        assert!(first.list_a_id.is_some() && first.list_b_id.is_some());
+    }
+
+    #[test]
+    fn project_bundle_with_invalid_lists_is_rejected() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let mut conn = bootstrap(dir.path(), "bundle.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        let list = |slot: &str, name: &str| ListBundle {
+            slot: slot.into(),
+            name: name.into(),
+            display_name: None,
+            source: "drive_kml".into(),
+            drive_file_id: None,
+            drive_file_name: None,
+            drive_file_mime: None,
+            drive_file_size: None,
+            drive_modified_time: None,
+            drive_file_checksum: None,
+            imported_at: "2024-01-01 00:00:00".into(),
+            raw_items: Vec::new(),
+        };
+        let bundle = |lists: Vec<ListBundle>| ProjectBundle {
+            version: PROJECT_BUNDLE_VERSION,
+            name: "Shared".into(),
+            exported_at: "2024-01-01 00:00:00".into(),
+            lists,
+        };
+        let projects_before = list_projects(&conn).unwrap().len();
+
+        for lists in [
+            vec![list("Z", "List Z")],
+            vec![list("A", "List A"), list("a", "Other A")],
+            vec![list("B", "   ")],
+        ] {
+            assert!(matches!(
+                import_project_bundle(&mut conn, &bundle(lists)),
+                Err(AppError::Config(_))
+            ));
+        }
+        assert_eq!(list_projects(&conn).unwrap().len(), projects_before);
+
+        let imported =
+            import_project_bundle(&mut conn, &bundle(vec![list(" c ", " List C ")])).unwrap();
+        let (slot, name): (String, String) = conn
+            .query_row(
+                "SELECT slot, name FROM lists WHERE project_id = ?1",
+                [imported.id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((slot.as_str(), name.as_str()), ("C", "List C"));
+    }
+
+    #[test]
+    fn rename_updates_name_and_regenerates_unique_slug() {
+        let dir = tempdir().unwrap();
+        let vault = SecretVault::in_memory();
+        let conn = bootstrap(dir.path(), "projects.db", &vault)
+            .unwrap()
+            .context
+            .connection;
+        create_project(&conn, "Road Trip", false).unwrap();
+        let project = create_project(&conn, "Scratch", false).unwrap();
+
+        let renamed = rename_project(&conn, project.id, "  Road trip!  ").unwrap();
+        assert_eq!(renamed.id, project.id);
+        assert_eq!(renamed.name, "Road trip!");
+        assert_eq!(renamed.slug, "road-trip-2");
+        assert!(matches!(
+            rename_project(&conn, project.id, "   "),
+            Err(AppError::Config(_))
+        ));
+    }
+}
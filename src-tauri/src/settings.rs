@@ -13,13 +13,64 @@ use crate::config::AppConfig;
 use crate::errors::{AppError, AppResult};
 
 const DEFAULT_MAX_QPS: u32 = 10;
+/// Cache entries never need to live longer than a year; `0` still means "never expire".
+const MAX_CACHE_TTL_HOURS: u64 = 24 * 365;
 const SALT_BYTES: usize = 32;
 
+const MAPTILER_STYLE_BASE: &str = "https://api.maptiler.com/maps";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MapStyle {
+    #[default]
+    Streets,
+    Satellite,
+    Dark,
+    Topo,
+}
+
+impl MapStyle {
+    /// Unknown names fall back to the streets basemap.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "satellite" => MapStyle::Satellite,
+            "dark" => MapStyle::Dark,
+            "topo" => MapStyle::Topo,
+            _ => MapStyle::Streets,
+        }
+    }
+
+    pub fn maptiler_id(&self) -> &'static str {
+        match self {
+            MapStyle::Streets => "streets",
+            MapStyle::Satellite => "satellite",
+            MapStyle::Dark => "streets-v2-dark",
+            MapStyle::Topo => "topo-v2",
+        }
+    }
+
+    pub fn style_url(&self, key: &str) -> String {
+        format!(
+            "{MAPTILER_STYLE_BASE}/{}/style.json?key={key}",
+            self.maptiler_id()
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserSettings {
     pub telemetry_enabled: bool,
     pub places_rate_limit_qps: u32,
     pub telemetry_salt: String,
+    #[serde(default)]
+    pub map_style: MapStyle,
+    /// Hours before a normalization cache entry is stale; `0` never expires. Files written
+    /// before this setting existed pick up the config value on load.
+    #[serde(default)]
+    pub normalization_cache_ttl_hours: Option<u64>,
+    /// Skips Places lookups and fails Google calls fast; imported data stays browsable.
+    #[serde(default)]
+    pub offline: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -27,6 +78,9 @@ pub struct RuntimeSettings {
     pub telemetry_enabled: bool,
     pub places_rate_limit_qps: u32,
     pub telemetry_salt: String,
+    pub map_style: MapStyle,
+    pub normalization_cache_ttl_hours: u64,
+    pub offline: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -34,13 +88,24 @@ pub struct RuntimeSettings {
 pub struct UpdateRuntimeSettingsPayload {
     pub telemetry_enabled: Option<bool>,
     pub places_rate_limit_qps: Option<u32>,
+    #[serde(default)]
+    pub map_style: Option<MapStyle>,
+    #[serde(default)]
+    pub normalization_cache_ttl_hours: Option<u64>,
+    #[serde(default)]
+    pub offline: Option<bool>,
 }
 
 impl UserSettings {
     pub fn load(path: &Path, config: &AppConfig) -> AppResult<Self> {
         match fs::read_to_string(path) {
             Ok(contents) => match serde_json::from_str::<Self>(&contents) {
-                Ok(settings) => Ok(settings),
+                Ok(mut settings) => {
+                    settings
+                        .normalization_cache_ttl_hours
+                        .get_or_insert(config.normalization_cache_ttl_hours);
+                    Ok(settings)
+                }
                 Err(err) => {
                     warn!(
                         target: "settings",
@@ -75,9 +140,26 @@ impl UserSettings {
             telemetry_enabled: self.telemetry_enabled,
             places_rate_limit_qps: self.places_rate_limit_qps,
             telemetry_salt: self.telemetry_salt.clone(),
+            map_style: self.map_style,
+            normalization_cache_ttl_hours: self.cache_ttl_hours(),
+            offline: self.offline,
         }
     }
 
+    pub fn cache_ttl_hours(&self) -> u64 {
+        self.normalization_cache_ttl_hours.unwrap_or_default()
+    }
+
+    /// Restores the config-derived defaults. The telemetry salt is kept unless
+    /// `regenerate_salt` is set, so hashed telemetry stays comparable across the reset.
+    pub fn reset(&mut self, config: &AppConfig, regenerate_salt: bool) {
+        let mut defaults = Self::from_config(config);
+        if !regenerate_salt {
+            defaults.telemetry_salt = std::mem::take(&mut self.telemetry_salt);
+        }
+        *self = defaults;
+    }
+
     pub fn apply_patch(&mut self, payload: &UpdateRuntimeSettingsPayload) {
         if let Some(enabled) = payload.telemetry_enabled {
             self.telemetry_enabled = enabled;
@@ -85,6 +167,15 @@ impl UserSettings {
         if let Some(qps) = payload.places_rate_limit_qps {
             self.places_rate_limit_qps = clamp_qps(qps);
         }
+        if let Some(style) = payload.map_style {
+            self.map_style = style;
+        }
+        if let Some(hours) = payload.normalization_cache_ttl_hours {
+            self.normalization_cache_ttl_hours = Some(hours);
+        }
+        if let Some(offline) = payload.offline {
+            self.offline = offline;
+        }
     }
 
     fn from_config(config: &AppConfig) -> Self {
@@ -92,30 +183,59 @@ impl UserSettings {
             telemetry_enabled: config.telemetry_enabled_by_default,
             places_rate_limit_qps: clamp_qps(config.places_rate_limit_qps),
             telemetry_salt: generate_salt(),
+            map_style: MapStyle::default(),
+            normalization_cache_ttl_hours: Some(config.normalization_cache_ttl_hours),
+            offline: false,
         }
     }
 }
 
 impl RuntimeSettings {
+    pub const CHANGED_EVENT: &'static str = "settings://changed";
+
     pub fn clamp_rate_limit(mut self) -> Self {
         self.places_rate_limit_qps = clamp_qps(self.places_rate_limit_qps);
         self
     }
+
+    /// Hands the applied settings to `emit` under [`Self::CHANGED_EVENT`] and returns them, so the
+    /// event payload and the command result are always the same value.
+    pub fn publish<E>(self, emit: E) -> Self
+    where
+        E: FnOnce(&'static str, &RuntimeSettings),
+    {
+        emit(Self::CHANGED_EVENT, &self);
+        self
+    }
 }
 
 impl UpdateRuntimeSettingsPayload {
-    pub fn sanitized(mut self) -> Self {
+    /// Rejects values that cannot be meant literally and clamps the rest into their bounds:
+    /// Places QPS to `1..=10` (zero is an error) and cache TTL hours to `0..=8760`.
+    pub fn sanitized(mut self) -> AppResult<Self> {
         if let Some(qps) = self.places_rate_limit_qps {
-            self.places_rate_limit_qps = Some(clamp_qps(qps));
+            self.places_rate_limit_qps = Some(validate_qps(qps)?);
         }
-        self
+        if let Some(hours) = self.normalization_cache_ttl_hours {
+            self.normalization_cache_ttl_hours = Some(hours.min(MAX_CACHE_TTL_HOURS));
+        }
+        Ok(self)
     }
 }
 
-fn clamp_qps(value: u32) -> u32 {
+pub(crate) fn clamp_qps(value: u32) -> u32 {
     value.clamp(1, DEFAULT_MAX_QPS)
 }
 
+pub(crate) fn validate_qps(value: u32) -> AppResult<u32> {
+    if value == 0 {
+        return Err(AppError::Config(format!(
+            "places rate limit must be between 1 and {DEFAULT_MAX_QPS} requests per second"
+        )));
+    }
+    Ok(clamp_qps(value))
+}
+
 fn generate_salt() -> String {
     let mut bytes = vec![0_u8; SALT_BYTES];
     OsRng.fill_bytes(&mut bytes);
@@ -156,4 +276,122 @@ mod tests {
         assert_eq!(settings.telemetry_enabled, roundtrip.telemetry_enabled);
         assert_eq!(settings.telemetry_salt, roundtrip.telemetry_salt);
     }
+
+    #[test]
+    fn map_styles_build_maptiler_urls() {
+        for (name, segment) in [
+            ("streets", "/maps/streets/"),
+            ("satellite", "/maps/satellite/"),
+            ("dark", "/maps/streets-v2-dark/"),
+            ("topo", "/maps/topo-v2/"),
+        ] {
+            let url = MapStyle::parse(name).style_url("k");
+            assert!(url.contains(segment), "{name} -> {url}");
+            assert!(url.ends_with("style.json?key=k"));
+        }
+        assert_eq!(MapStyle::parse("watercolor"), MapStyle::Streets);
+    }
+
+    #[test]
+    fn reset_restores_config_defaults() {
+        let dir = tempdir().unwrap();
+        let config = AppConfig::from_env();
+        let path = settings_path(dir.path());
+        let mut settings = UserSettings::load(&path, &config).unwrap();
+        let salt = settings.telemetry_salt.clone();
+        settings.apply_patch(&UpdateRuntimeSettingsPayload {
+            telemetry_enabled: Some(!config.telemetry_enabled_by_default),
+            places_rate_limit_qps: Some(1),
+            map_style: Some(MapStyle::Dark),
+            normalization_cache_ttl_hours: Some(1),
+            offline: Some(true),
+        });
+
+        settings.reset(&config, false);
+        assert_eq!(
+            settings.telemetry_enabled,
+            config.telemetry_enabled_by_default
+        );
+        assert_eq!(
+            settings.places_rate_limit_qps,
+            clamp_qps(config.places_rate_limit_qps)
+        );
+        assert_eq!(settings.map_style, MapStyle::Streets);
+        assert_eq!(
+            settings.cache_ttl_hours(),
+            config.normalization_cache_ttl_hours
+        );
+        assert_eq!(settings.telemetry_salt, salt);
+        assert!(!settings.offline);
+
+        settings.reset(&config, true);
+        assert_ne!(settings.telemetry_salt, salt);
+    }
+
+    fn payload() -> UpdateRuntimeSettingsPayload {
+        UpdateRuntimeSettingsPayload {
+            telemetry_enabled: None,
+            places_rate_limit_qps: None,
+            map_style: None,
+            normalization_cache_ttl_hours: None,
+            offline: None,
+        }
+    }
+
+    #[test]
+    fn sanitized_bounds_places_rate_limit() {
+        let err = UpdateRuntimeSettingsPayload {
+            places_rate_limit_qps: Some(0),
+            ..payload()
+        }
+        .sanitized()
+        .unwrap_err();
+        assert!(
+            matches!(err, AppError::Config(ref message) if message.contains("between 1 and 10"))
+        );
+
+        for (requested, applied) in [(1, 1), (10, 10), (500, DEFAULT_MAX_QPS)] {
+            let sanitized = UpdateRuntimeSettingsPayload {
+                places_rate_limit_qps: Some(requested),
+                ..payload()
+            }
+            .sanitized()
+            .unwrap();
+            assert_eq!(sanitized.places_rate_limit_qps, Some(applied));
+        }
+    }
+
+    #[test]
+    fn sanitized_bounds_cache_ttl() {
+        for (requested, applied) in [
+            (0, 0),
+            (MAX_CACHE_TTL_HOURS, MAX_CACHE_TTL_HOURS),
+            (u64::MAX, MAX_CACHE_TTL_HOURS),
+        ] {
+            let sanitized = UpdateRuntimeSettingsPayload {
+                normalization_cache_ttl_hours: Some(requested),
+                ..payload()
+            }
+            .sanitized()
+            .unwrap();
+            assert_eq!(sanitized.normalization_cache_ttl_hours, Some(applied));
+        }
+    }
+
+    #[test]
+    fn publish_emits_the_returned_settings() {
+        let config = AppConfig::from_env();
+        let settings = UserSettings::from_config(&config).runtime_profile();
+        let mut emitted = None;
+        let returned = settings.clone().publish(|event, payload| {
+            emitted = Some((event, serde_json::to_value(payload).unwrap()));
+        });
+        let (event, payload) = emitted.expect("settings event emitted");
+        assert_eq!(event, "settings://changed");
+        assert_eq!(payload, serde_json::to_value(&returned).unwrap());
+        assert_eq!(
+            returned.places_rate_limit_qps,
+            settings.places_rate_limit_qps
+        );
+    }
 }
@@ -1,12 +1,21 @@
 use std::fs::{self, OpenOptions};
-use std::io::Write;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
 use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use parking_lot::Mutex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::async_runtime::{self, JoinHandle};
 use tracing::warn;
 
 #[cfg(test)]
@@ -23,6 +32,10 @@ pub struct TelemetryClient {
     batch_size: usize,
     max_file_bytes: u64,
     max_file_count: usize,
+    max_age: Option<Duration>,
+    flush_interval: Duration,
+    redacted_keys: Vec<String>,
+    salt: Arc<Mutex<String>>,
     #[cfg(test)]
     fault_injector: Option<Arc<TestFaultInjector>>,
 }
@@ -44,6 +57,11 @@ impl TelemetryClient {
             batch_size: config.telemetry_batch_size,
             max_file_bytes: config.telemetry_buffer_max_bytes,
             max_file_count: config.telemetry_buffer_max_files,
+            max_age: (config.telemetry_buffer_max_age_days > 0)
+                .then(|| Duration::from_secs(config.telemetry_buffer_max_age_days * 86_400)),
+            flush_interval: Duration::from_millis(config.telemetry_flush_interval_ms),
+            redacted_keys: config.telemetry_redacted_keys.clone(),
+            salt: Arc::new(Mutex::new(String::new())),
             #[cfg(test)]
             fault_injector: None,
         };
@@ -51,10 +69,11 @@ impl TelemetryClient {
         Ok(client)
     }
 
-    pub fn record(&self, name: impl Into<String>, payload: serde_json::Value) -> AppResult<()> {
+    pub fn record(&self, name: impl Into<String>, mut payload: Value) -> AppResult<()> {
         if !self.enabled.load(Ordering::Relaxed) {
             return Ok(());
         }
+        redact_telemetry(&mut payload, &self.salt.lock(), &self.redacted_keys);
 
         let mut queue = self.queue.lock();
         queue.push(TelemetryEvent::new(name.into(), payload));
@@ -81,6 +100,62 @@ impl TelemetryClient {
         }
     }
 
+    /// Returns a guard that flushes whatever is still queued when it is dropped.
+    pub fn flush_guard(&self) -> TelemetryFlushGuard {
+        TelemetryFlushGuard {
+            client: self.clone(),
+        }
+    }
+
+    /// Flushes the queue and returns up to `limit` buffered events, newest first. Lines that
+    /// fail to parse (e.g. a write cut short by a crash) are skipped.
+    pub fn read_recent(&self, limit: usize) -> AppResult<Vec<TelemetryEvent>> {
+        self.flush()?;
+        let mut events = Vec::new();
+        for path in self.buffer_files_newest_first()? {
+            if events.len() >= limit {
+                break;
+            }
+            let file = match fs::File::open(&path) {
+                Ok(file) => file,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+            let reader: Box<dyn Read> = if is_gzip_rotation(&path) {
+                Box::new(GzDecoder::new(file))
+            } else {
+                Box::new(file)
+            };
+            let mut parsed: Vec<TelemetryEvent> = BufReader::new(reader)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str(&line).ok())
+                .collect();
+            parsed.reverse();
+            events.extend(parsed.into_iter().take(limit - events.len()));
+        }
+        Ok(events)
+    }
+
+    /// Spawns a task that flushes the queue every `telemetry_flush_interval_ms` so quiet sessions
+    /// still reach disk. Returns `None` when the interval is zero. The task runs on Tauri's async
+    /// runtime because the `setup` hook that builds the client has no Tokio runtime entered.
+    pub fn start_periodic_flush(&self) -> Option<TelemetryFlushTask> {
+        if self.flush_interval.is_zero() {
+            return None;
+        }
+        let client = self.clone();
+        let handle = async_runtime::spawn(async move {
+            let mut ticker = tokio::time::interval(client.flush_interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                client.flush_lossy();
+            }
+        });
+        Some(TelemetryFlushTask { handle })
+    }
+
     pub fn queue_depth(&self) -> usize {
         self.queue.lock().len()
     }
@@ -89,6 +164,10 @@ impl TelemetryClient {
         &self.buffer_path
     }
 
+    pub fn set_salt(&self, salt: &str) {
+        *self.salt.lock() = salt.to_string();
+    }
+
     pub fn set_enabled(&self, enabled: bool) {
         self.enabled.store(enabled, Ordering::SeqCst);
         if !enabled {
@@ -166,9 +245,9 @@ impl TelemetryClient {
         }
 
         let rotated_name = format!(
-            "{}-{}.jsonl",
+            "{}-{}.jsonl.gz",
             self.buffer_stem(),
-            Utc::now().format("%Y%m%d%H%M%S")
+            Utc::now().format("%Y%m%d%H%M%S%6f")
         );
         let rotated_path = self
             .buffer_path
@@ -177,7 +256,7 @@ impl TelemetryClient {
             .join(rotated_name);
 
         if self.buffer_path.exists() {
-            fs::rename(&self.buffer_path, &rotated_path)?;
+            compress_into(&self.buffer_path, &rotated_path)?;
         }
 
         self.prune_rotations()?;
@@ -197,7 +276,7 @@ impl TelemetryClient {
                 entry.ok().and_then(|dir_entry| {
                     let name = dir_entry.file_name();
                     let name = name.to_string_lossy();
-                    if name.starts_with(&prefix) && name.ends_with(".jsonl") {
+                    if name.starts_with(&prefix) && is_rotation_name(&name) {
                         Some((
                             dir_entry.path(),
                             dir_entry.metadata().ok()?.modified().ok()?,
@@ -209,6 +288,20 @@ impl TelemetryClient {
             })
             .collect::<Vec<_>>();
 
+        if let Some(cutoff) = self
+            .max_age
+            .and_then(|max_age| SystemTime::now().checked_sub(max_age))
+        {
+            rotations.retain(|(path, modified)| {
+                if *modified < cutoff {
+                    let _ = fs::remove_file(path);
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
         rotations.sort_by_key(|(_, modified)| *modified);
         let allowed = self.max_file_count.saturating_sub(1);
         if rotations.len() > allowed {
@@ -220,6 +313,27 @@ impl TelemetryClient {
         Ok(())
     }
 
+    fn buffer_files_newest_first(&self) -> AppResult<Vec<PathBuf>> {
+        let parent = self.buffer_path.parent().unwrap_or_else(|| Path::new("."));
+        let prefix = format!("{}-", self.buffer_stem());
+        let mut rotations = fs::read_dir(parent)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                name.starts_with(&prefix) && is_rotation_name(&name)
+            })
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>();
+        // Rotated files carry a sortable timestamp suffix.
+        rotations.sort();
+        rotations.reverse();
+
+        let mut paths = vec![self.buffer_path.clone()];
+        paths.extend(rotations);
+        Ok(paths)
+    }
+
     fn buffer_stem(&self) -> String {
         self.buffer_path
             .file_stem()
@@ -238,7 +352,28 @@ impl TelemetryClient {
     }
 }
 
-#[derive(Debug, Serialize)]
+pub struct TelemetryFlushGuard {
+    client: TelemetryClient,
+}
+
+impl Drop for TelemetryFlushGuard {
+    fn drop(&mut self) {
+        self.client.flush_lossy();
+    }
+}
+
+/// Handle to the periodic flush task; the task stops when this is dropped.
+pub struct TelemetryFlushTask {
+    handle: JoinHandle<()>,
+}
+
+impl Drop for TelemetryFlushTask {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TelemetryEvent {
     pub name: String,
     pub timestamp: DateTime<Utc>,
@@ -255,6 +390,62 @@ impl TelemetryEvent {
     }
 }
 
+fn compress_into(source: &Path, destination: &Path) -> AppResult<()> {
+    let mut input = fs::File::open(source)?;
+    let output = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(destination)?;
+    let mut encoder = GzEncoder::new(output, Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    Ok(())
+}
+
+// Older builds rotated to plain `.jsonl`, so both extensions are still recognised.
+fn is_rotation_name(name: &str) -> bool {
+    name.ends_with(".jsonl") || name.ends_with(".jsonl.gz")
+}
+
+fn is_gzip_rotation(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz")
+}
+
+/// Replaces string values stored under any of `keys` (at any depth) with a salted hash so
+/// events stay correlatable without carrying emails or file names in plaintext.
+pub fn redact_telemetry(payload: &mut Value, salt: &str, keys: &[String]) {
+    match payload {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if keys
+                    .iter()
+                    .any(|candidate| candidate.eq_ignore_ascii_case(key))
+                {
+                    if let Value::String(text) = value {
+                        *text = salted_hash(salt, text);
+                        continue;
+                    }
+                }
+                redact_telemetry(value, salt, keys);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                redact_telemetry(item, salt, keys);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn salted_hash(salt: &str, value: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(value.as_bytes());
+    STANDARD_NO_PAD.encode(hasher.finalize())
+}
+
 fn encode_batch(events: &[TelemetryEvent]) -> AppResult<(Vec<Vec<u8>>, u64)> {
     let mut encoded = Vec::with_capacity(events.len());
     let mut bytes = 0_u64;
@@ -304,6 +495,7 @@ impl TelemetryClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::CipherSettings;
     use serde_json::json;
     use tempfile::tempdir;
 
@@ -317,7 +509,12 @@ mod tests {
             telemetry_batch_size: 1,
             telemetry_buffer_max_bytes: 1024,
             telemetry_buffer_max_files: 3,
+            telemetry_buffer_max_age_days: 30,
             places_rate_limit_qps: 3,
+            allow_synthetic_fallback: false,
+            places_retry_max_attempts: 5,
+            places_retry_base_backoff_ms: 250,
+            places_retry_max_backoff_ms: 16_000,
             normalization_cache_ttl_hours: 72,
             database_file_name: "test.db".into(),
             google_places_api_key: None,
@@ -328,8 +525,19 @@ mod tests {
             google_auth_endpoint: "https://accounts.google.com/o/oauth2/v2/auth".into(),
             google_token_endpoint: "https://oauth2.googleapis.com/token".into(),
             google_userinfo_endpoint: "https://openidconnect.googleapis.com/v1/userinfo".into(),
+            google_revoke_endpoint: "https://oauth2.googleapis.com/revoke".into(),
             google_drive_api_base: "https://www.googleapis.com/drive/v3".into(),
             google_drive_picker_page_size: 25,
+            google_oauth_scopes: Vec::new(),
+            max_import_bytes: 50 * 1024 * 1024,
+            max_import_rows: 50_000,
+            truncate_oversized_imports: false,
+            refresh_progress_every_rows: 50,
+            refresh_progress_interval_ms: 250,
+            place_id_fields: Vec::new(),
+            telemetry_redacted_keys: vec!["email".into(), "file_name".into()],
+            http_proxy: None,
+            cipher: CipherSettings::default(),
         };
 
         let client = TelemetryClient::new(dir.path(), &config).unwrap();
@@ -444,6 +652,185 @@ mod tests {
         assert!(buffer.contains("retry_later"));
     }
 
+    #[test]
+    fn flush_guard_persists_queued_events_on_drop() {
+        let dir = tempdir().unwrap();
+        let mut config = test_config();
+        config.telemetry_batch_size = 10;
+        let client = TelemetryClient::new(dir.path(), &config).unwrap();
+        let guard = client.flush_guard();
+        client.record("app_start", json!({})).unwrap();
+        client.record("export_generated", json!({})).unwrap();
+        assert_eq!(client.queue_depth(), 2);
+        assert!(std::fs::read_to_string(client.buffer_path())
+            .unwrap()
+            .is_empty());
+
+        drop(guard);
+        assert_eq!(client.queue_depth(), 0);
+        let buffer = std::fs::read_to_string(client.buffer_path()).unwrap();
+        assert!(buffer.contains("app_start"));
+        assert!(buffer.contains("export_generated"));
+    }
+
+    #[test]
+    fn hashes_sensitive_fields_with_salt() {
+        let dir = tempdir().unwrap();
+        let mut config = test_config();
+        config.telemetry_batch_size = 1;
+        let client = TelemetryClient::new(dir.path(), &config).unwrap();
+        client.set_salt("install-salt");
+        client
+            .record(
+                "signin_success",
+                json!({ "email": "someone@example.com", "expires_at": null }),
+            )
+            .unwrap();
+
+        let buffer = std::fs::read_to_string(client.buffer_path()).unwrap();
+        assert!(!buffer.contains("someone@example.com"));
+        let event: Value = serde_json::from_str(buffer.lines().next().unwrap()).unwrap();
+        assert_eq!(
+            event["payload"]["email"],
+            salted_hash("install-salt", "someone@example.com")
+        );
+        assert_ne!(
+            salted_hash("install-salt", "someone@example.com"),
+            salted_hash("other-salt", "someone@example.com")
+        );
+    }
+
+    #[test]
+    fn reads_recent_events_newest_first() {
+        let dir = tempdir().unwrap();
+        let config = test_config();
+        let client = TelemetryClient::new(dir.path(), &config).unwrap();
+        client.record("first", json!({})).unwrap();
+        client.record("second", json!({})).unwrap();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(client.buffer_path())
+            .unwrap();
+        file.write_all(b"{\"name\":\"trunc\n").unwrap();
+        client.record("third", json!({})).unwrap();
+
+        let names: Vec<_> = client
+            .read_recent(10)
+            .unwrap()
+            .into_iter()
+            .map(|event| event.name)
+            .collect();
+        assert_eq!(names, vec!["third", "second", "first"]);
+        assert_eq!(client.queue_depth(), 0);
+        assert_eq!(client.read_recent(2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn gzips_rotated_files_and_prunes_by_count() {
+        let dir = tempdir().unwrap();
+        let mut config = test_config();
+        config.telemetry_buffer_max_bytes = 256;
+        config.telemetry_batch_size = 1;
+        config.telemetry_buffer_max_files = 3;
+        let client = TelemetryClient::new(dir.path(), &config).unwrap();
+        let event = json!({ "payload": "0123456789abcdef0123456789abcdef0123456789abcdef" });
+
+        client.record("before_rotation", event.clone()).unwrap();
+        let original = std::fs::read_to_string(client.buffer_path()).unwrap();
+        client.record("after_rotation", event.clone()).unwrap();
+
+        let rotations = || {
+            let mut paths: Vec<_> = std::fs::read_dir(dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| path.to_string_lossy().ends_with(".jsonl.gz"))
+                .collect();
+            paths.sort();
+            paths
+        };
+        let rotated = rotations();
+        assert_eq!(rotated.len(), 1);
+        let mut decompressed = String::new();
+        GzDecoder::new(std::fs::File::open(&rotated[0]).unwrap())
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, original);
+
+        for _ in 0..4 {
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            client.record("filler", event.clone()).unwrap();
+        }
+        assert_eq!(rotations().len(), config.telemetry_buffer_max_files - 1);
+        let names: Vec<_> = client
+            .read_recent(10)
+            .unwrap()
+            .into_iter()
+            .map(|event| event.name)
+            .collect();
+        assert_eq!(names, vec!["filler"; 3]);
+    }
+
+    #[test]
+    fn prunes_rotations_older_than_max_age() {
+        let dir = tempdir().unwrap();
+        let mut config = test_config();
+        config.telemetry_buffer_max_files = 10;
+        config.telemetry_buffer_max_age_days = 7;
+        let client = TelemetryClient::new(dir.path(), &config).unwrap();
+
+        let rotation = |name: &str, age_days: u64| {
+            let path = dir.path().join(name);
+            let file = std::fs::File::create(&path).unwrap();
+            file.set_modified(SystemTime::now() - Duration::from_secs(age_days * 86_400))
+                .unwrap();
+            path
+        };
+        let stale = rotation("telemetry-buffer-20200101000000.jsonl.gz", 30);
+        let legacy = rotation("telemetry-buffer-20200102000000.jsonl", 8);
+        let fresh = rotation("telemetry-buffer-20200103000000.jsonl.gz", 1);
+
+        client.prune_rotations().unwrap();
+        assert!(!stale.exists());
+        assert!(!legacy.exists());
+        assert!(fresh.exists());
+    }
+
+    #[tokio::test]
+    async fn periodic_flush_persists_without_explicit_flush() {
+        let dir = tempdir().unwrap();
+        let mut config = test_config();
+        config.telemetry_batch_size = 10;
+        config.telemetry_flush_interval_ms = 20;
+        let client = TelemetryClient::new(dir.path(), &config).unwrap();
+        let task = client.start_periodic_flush().expect("flush task");
+
+        client.record("quiet_session", json!({})).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let buffer = std::fs::read_to_string(client.buffer_path()).unwrap();
+        assert!(buffer.contains("quiet_session"));
+
+        drop(task);
+        client.record("after_stop", json!({})).unwrap();
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(client.queue_depth(), 1);
+    }
+
+    #[test]
+    fn periodic_flush_starts_outside_a_tokio_runtime() {
+        let dir = tempdir().unwrap();
+        let mut config = test_config();
+        config.telemetry_batch_size = 10;
+        config.telemetry_flush_interval_ms = 20;
+        let client = TelemetryClient::new(dir.path(), &config).unwrap();
+        let task = client.start_periodic_flush().expect("flush task");
+
+        client.record("setup_hook", json!({})).unwrap();
+        std::thread::sleep(Duration::from_millis(150));
+        let buffer = std::fs::read_to_string(client.buffer_path()).unwrap();
+        assert!(buffer.contains("setup_hook"));
+        drop(task);
+    }
+
     fn test_config() -> AppConfig {
         AppConfig {
             telemetry_endpoint: None,
@@ -452,7 +839,12 @@ mod tests {
             telemetry_batch_size: 2,
             telemetry_buffer_max_bytes: 1024,
             telemetry_buffer_max_files: 3,
+            telemetry_buffer_max_age_days: 30,
             places_rate_limit_qps: 3,
+            allow_synthetic_fallback: false,
+            places_retry_max_attempts: 5,
+            places_retry_base_backoff_ms: 250,
+            places_retry_max_backoff_ms: 16_000,
             normalization_cache_ttl_hours: 72,
             database_file_name: "test.db".into(),
             google_places_api_key: None,
@@ -463,8 +855,19 @@ mod tests {
             google_auth_endpoint: "https://accounts.google.com/o/oauth2/v2/auth".into(),
             google_token_endpoint: "https://oauth2.googleapis.com/token".into(),
             google_userinfo_endpoint: "https://openidconnect.googleapis.com/v1/userinfo".into(),
+            google_revoke_endpoint: "https://oauth2.googleapis.com/revoke".into(),
             google_drive_api_base: "https://www.googleapis.com/drive/v3".into(),
             google_drive_picker_page_size: 25,
+            google_oauth_scopes: Vec::new(),
+            max_import_bytes: 50 * 1024 * 1024,
+            max_import_rows: 50_000,
+            truncate_oversized_imports: false,
+            refresh_progress_every_rows: 50,
+            refresh_progress_interval_ms: 250,
+            place_id_fields: Vec::new(),
+            telemetry_redacted_keys: vec!["email".into(), "file_name".into()],
+            http_proxy: None,
+            cipher: CipherSettings::default(),
         }
     }
 }
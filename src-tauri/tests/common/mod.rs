@@ -0,0 +1,74 @@
+#![allow(dead_code)]
+
+use httptest::matchers::{all_of, contains, request, url_decoded};
+use httptest::responders::json_encoded;
+use httptest::{Expectation, Server};
+use serde_json::json;
+use tempfile::tempdir;
+
+use tauri_app_lib::{AppConfig, GoogleServices, SecretVault, TelemetryClient};
+
+pub fn mock_config(server: &Server) -> AppConfig {
+    let mut config = AppConfig::from_env();
+    config.google_oauth_client_id = Some("test-client".into());
+    config.google_oauth_client_secret = Some("test-secret".into());
+    config.google_device_code_endpoint = server.url("/device/code").to_string();
+    config.google_token_endpoint = server.url("/token").to_string();
+    config.google_userinfo_endpoint = server.url("/userinfo").to_string();
+    config.google_revoke_endpoint = server.url("/revoke").to_string();
+    config.google_drive_api_base = server.url("/drive/v3").to_string();
+    config
+}
+
+pub fn expect_device_sign_in(server: &Server) {
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("POST"),
+            request::path("/device/code")
+        ))
+        .respond_with(json_encoded(json!({
+            "device_code": "device-code",
+            "user_code": "USER-CODE",
+            "verification_url": "https://example.com",
+            "expires_in": 1800,
+            "interval": 1
+        }))),
+    );
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("POST"),
+            request::path("/token"),
+            request::body(url_decoded(contains(("device_code", "device-code"))))
+        ))
+        .respond_with(json_encoded(json!({
+            "access_token": "ya29.access",
+            "refresh_token": "ya29.refresh",
+            "expires_in": 3600,
+            "scope": "drive.readonly",
+            "token_type": "Bearer"
+        }))),
+    );
+    server.expect(
+        Expectation::matching(all_of!(request::method("GET"), request::path("/userinfo")))
+            .respond_with(json_encoded(json!({
+                "email": "importer@example.com",
+                "name": "Drive Importer",
+                "picture": null
+            }))),
+    );
+}
+
+pub async fn signed_in_services(server: &Server, vault: &SecretVault) -> GoogleServices {
+    let config = mock_config(server);
+    let temp_dir = tempdir().unwrap();
+    let telemetry = TelemetryClient::new(temp_dir.path(), &config).unwrap();
+    let google = GoogleServices::maybe_new(&config, vault, telemetry)
+        .expect("service creation")
+        .expect("oauth configured");
+    let device_flow = google.start_device_flow().await.expect("device flow");
+    google
+        .complete_device_flow(&device_flow.device_code, device_flow.interval_secs)
+        .await
+        .expect("sign in");
+    google
+}
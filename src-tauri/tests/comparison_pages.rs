@@ -0,0 +1,59 @@
+use tempfile::tempdir;
+
+use tauri_app_lib::{
+    bootstrap, load_segment_page, ComparisonPagination, ComparisonSegment, SecretVault,
+    SegmentFilter,
+};
+
+#[test]
+fn segment_page_two_returns_offset_rows() {
+    let dir = tempdir().unwrap();
+    let vault = SecretVault::in_memory();
+    let conn = bootstrap(dir.path(), "pages.db", &vault)
+        .expect("bootstrap db")
+        .context
+        .connection;
+    let project_id: i64 = conn
+        .query_row(
+            "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .expect("project id");
+    conn.execute(
+        "INSERT INTO lists (id, project_id, slot, name, source) VALUES (1, ?1, 'A', 'List A', 'test')",
+        [project_id],
+    )
+    .expect("list");
+    for (index, name) in ["Alpha", "Bravo", "Charlie", "Delta", "Echo"]
+        .iter()
+        .enumerate()
+    {
+        let place_id = format!("place_{index}");
+        conn.execute(
+            "INSERT INTO places (place_id, name, lat, lng) VALUES (?1, ?2, 1.0, 1.0)",
+            (&place_id, name),
+        )
+        .expect("place");
+        conn.execute(
+            "INSERT INTO list_places (list_id, place_id) VALUES (1, ?1)",
+            [&place_id],
+        )
+        .expect("assignment");
+    }
+
+    let page = load_segment_page(
+        &conn,
+        project_id,
+        ComparisonSegment::OnlyA,
+        ComparisonPagination::new(Some(2), Some(2)),
+        &SegmentFilter::default(),
+    )
+    .expect("segment page");
+
+    assert_eq!(page.total, 5);
+    assert_eq!(page.page, 2);
+    assert_eq!(page.page_size, 2);
+    let names: Vec<_> = page.rows.iter().map(|row| row.name.as_str()).collect();
+    assert_eq!(names, vec!["Charlie", "Delta"]);
+}
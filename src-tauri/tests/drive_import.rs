@@ -1,12 +1,19 @@
-use httptest::matchers::{all_of, request};
-use httptest::responders::{json_encoded, status_code};
+mod common;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use httptest::matchers::{all_of, contains, key, request, url_decoded};
+use httptest::responders::{delay_and_then, json_encoded, status_code};
 use httptest::{Expectation, Server};
 use serde_json::json;
 use tempfile::tempdir;
+use tokio::sync::Notify;
 
 use tauri_app_lib::{
-    bootstrap, enqueue_place_hashes, parse_kml, persist_rows, AppConfig, DriveFileMetadata,
-    GoogleServices, ListSlot, SecretVault, TelemetryClient,
+    bootstrap, enqueue_place_hashes, linked_drive_file, parse_kml, persist_drive_selection,
+    persist_rows, AppConfig, AppError, AppState, DriveFileMetadata, EventSink, GoogleServices,
+    ListSlot, SecretVault, TelemetryClient,
 };
 
 const SAMPLE_KML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -165,6 +172,7 @@ async fn device_flow_and_import_roundtrip() {
         modified_time: Some("2024-01-01T00:00:00Z".into()),
         size: Some(SAMPLE_KML.len() as u64),
         md5_checksum: Some(sample_md5),
+        source: None,
     };
     let summary = persist_rows(
         &mut connection,
@@ -179,3 +187,479 @@ async fn device_flow_and_import_roundtrip() {
     let telemetry = TelemetryClient::new(dir.path(), &config).expect("telemetry");
     enqueue_place_hashes(&telemetry, ListSlot::A, &parsed.rows).expect("hash telemetry");
 }
+
+#[tokio::test]
+async fn streams_large_download_to_temp_file() {
+    let server = Server::run();
+    common::expect_device_sign_in(&server);
+    let body: Vec<u8> = (0..2_000_000_u32)
+        .map(|value| (value % 251) as u8)
+        .collect();
+    let expected_md5 = format!("{:x}", md5::compute(&body));
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("GET"),
+            request::path("/drive/v3/files/big-file")
+        ))
+        .respond_with(status_code(200).body(body.clone())),
+    );
+
+    let vault = SecretVault::in_memory();
+    let google = common::signed_in_services(&server, &vault).await;
+    let mut last_received = 0;
+    let download = google
+        .download_file_to_disk(
+            "big-file",
+            None,
+            Some(body.len() as u64),
+            Some(expected_md5.as_str()),
+            |received, _| last_received = received,
+            None,
+        )
+        .await
+        .expect("download");
+
+    assert_eq!(last_received, body.len() as u64);
+    assert_eq!(download.received_bytes, body.len() as u64);
+    let on_disk = std::fs::read(&download.path).expect("temp file");
+    assert_eq!(format!("{:x}", md5::compute(&on_disk)), expected_md5);
+    assert_eq!(download.checksum_md5, expected_md5);
+
+    let path = download.path.to_path_buf();
+    drop(download);
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn cancelled_download_stops_import_and_keeps_slot() {
+    let server = Server::run();
+    common::expect_device_sign_in(&server);
+    let body: Vec<u8> = (0..2_000_000_u32)
+        .map(|value| (value % 251) as u8)
+        .collect();
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("GET"),
+            request::path("/drive/v3/files/wrong-file")
+        ))
+        .respond_with(status_code(200).body(body.clone())),
+    );
+
+    let vault = SecretVault::in_memory();
+    common::signed_in_services(&server, &vault).await;
+    let dir = tempdir().unwrap();
+    let config = common::mock_config(&server);
+    let database_file_name = config.database_file_name.clone();
+    let project_id: i64 = {
+        let mut connection = bootstrap(dir.path(), &database_file_name, &vault)
+            .expect("bootstrap db")
+            .context
+            .connection;
+        let project_id = connection
+            .query_row(
+                "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .expect("project id");
+        let existing = DriveFileMetadata {
+            id: "right-file".into(),
+            name: "right-file".into(),
+            mime_type: "application/vnd.google-earth.kml+xml".into(),
+            modified_time: None,
+            size: Some(SAMPLE_KML.len() as u64),
+            md5_checksum: None,
+            source: None,
+        };
+        let parsed = parse_kml(SAMPLE_KML.as_bytes()).expect("parse rows");
+        persist_rows(
+            &mut connection,
+            project_id,
+            ListSlot::A,
+            &existing,
+            &parsed.rows,
+        )
+        .expect("persist rows");
+        project_id
+    };
+
+    let download_started = Arc::new(Notify::new());
+    let started = Arc::clone(&download_started);
+    let events: EventSink = Arc::new(move |event, payload| {
+        if event == "import://progress" && payload["stage"] == "download" {
+            started.notify_one();
+        }
+    });
+    let state = AppState::open(dir.path(), config, vault.clone(), events).expect("app state");
+    let (result, signalled) = tokio::join!(
+        state.import_drive_file(
+            Some(project_id),
+            ListSlot::A,
+            "wrong-file".into(),
+            "wrong-file".into(),
+            None,
+            None,
+            Some(body.len() as u64),
+            None,
+        ),
+        async {
+            download_started.notified().await;
+            state.cancel_import(Some(project_id), Some(ListSlot::A))
+        }
+    );
+    assert!(signalled.expect("cancel import"));
+    let err = result.expect_err("cancelled import");
+    assert!(matches!(err, AppError::Cancelled(_)), "{err:?}");
+
+    let connection = bootstrap(dir.path(), &database_file_name, &vault)
+        .expect("reopen db")
+        .context
+        .connection;
+    let linked = linked_drive_file(&connection, project_id, ListSlot::A)
+        .expect("linked file")
+        .expect("slot A stays linked");
+    assert_eq!(linked.id, "right-file");
+    let rows: i64 = connection
+        .query_row(
+            "SELECT COUNT(r.id) FROM lists l
+            JOIN raw_items r ON r.list_id = l.id
+            WHERE l.project_id = ?1 AND l.slot = 'A'",
+            [project_id],
+            |row| row.get(0),
+        )
+        .expect("slot A rows");
+    assert_eq!(rows, 1);
+}
+
+#[tokio::test]
+async fn sign_out_cancels_running_import() {
+    let server = Server::run();
+    common::expect_device_sign_in(&server);
+    server.expect(
+        Expectation::matching(all_of!(request::method("POST"), request::path("/revoke")))
+            .respond_with(status_code(200)),
+    );
+    let body: Vec<u8> = (0..2_000_000_u32)
+        .map(|value| (value % 251) as u8)
+        .collect();
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("GET"),
+            request::path("/drive/v3/files/big-file")
+        ))
+        .respond_with(status_code(200).body(body.clone())),
+    );
+
+    let vault = SecretVault::in_memory();
+    common::signed_in_services(&server, &vault).await;
+    let dir = tempdir().unwrap();
+    let download_started = Arc::new(Notify::new());
+    let started = Arc::clone(&download_started);
+    let events: EventSink = Arc::new(move |event, payload| {
+        if event == "import://progress" && payload["stage"] == "download" {
+            started.notify_one();
+        }
+    });
+    let state =
+        AppState::open(dir.path(), common::mock_config(&server), vault, events).expect("app state");
+    let (result, signed_out) = tokio::join!(
+        state.import_drive_file(
+            None,
+            ListSlot::A,
+            "big-file".into(),
+            "big-file".into(),
+            None,
+            None,
+            Some(body.len() as u64),
+            None,
+        ),
+        async {
+            download_started.notified().await;
+            state.sign_out_google().await
+        }
+    );
+    signed_out.expect("sign out");
+    let err = result.expect_err("import cancelled by sign-out");
+    assert!(matches!(err, AppError::Cancelled(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn download_verifies_md5_checksum() {
+    let server = Server::run();
+    common::expect_device_sign_in(&server);
+    let sample_md5 = format!("{:x}", md5::compute(SAMPLE_KML.as_bytes()));
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("GET"),
+            request::path("/drive/v3/files/checked-file")
+        ))
+        .times(1..)
+        .respond_with(status_code(200).body(SAMPLE_KML)),
+    );
+
+    let vault = SecretVault::in_memory();
+    let google = common::signed_in_services(&server, &vault).await;
+
+    let download = google
+        .download_file("checked-file", None, None, Some(&sample_md5), |_, _| {})
+        .await
+        .expect("matching checksum");
+    assert_eq!(download.checksum_md5, sample_md5);
+
+    let err = google
+        .download_file(
+            "checked-file",
+            None,
+            None,
+            Some("00000000000000000000000000000000"),
+            |_, _| {},
+        )
+        .await
+        .expect_err("mismatched checksum");
+    assert!(matches!(err, AppError::Parse(ref reason) if reason.contains("checksum mismatch")));
+}
+
+#[tokio::test]
+async fn download_without_content_length_reports_metadata_total() {
+    let server = Server::run();
+    common::expect_device_sign_in(&server);
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("GET"),
+            request::path("/drive/v3/files/chunked-file"),
+            request::query(url_decoded(contains(("alt", "media"))))
+        ))
+        .respond_with(
+            status_code(200)
+                .append_header("transfer-encoding", "chunked")
+                .body(SAMPLE_KML),
+        ),
+    );
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("GET"),
+            request::path("/drive/v3/files/chunked-file"),
+            request::query(url_decoded(contains(key("fields"))))
+        ))
+        .respond_with(json_encoded(json!({
+            "id": "chunked-file",
+            "name": "Chunked",
+            "mimeType": "application/vnd.google-earth.kml+xml",
+            "size": SAMPLE_KML.len().to_string()
+        }))),
+    );
+
+    let vault = SecretVault::in_memory();
+    let google = common::signed_in_services(&server, &vault).await;
+    let mut totals = Vec::new();
+    let download = google
+        .download_file("chunked-file", None, None, None, |_, total| {
+            totals.push(total)
+        })
+        .await
+        .expect("download");
+
+    assert_eq!(download.received_bytes, SAMPLE_KML.len() as u64);
+    assert_eq!(download.expected_bytes, Some(SAMPLE_KML.len() as u64));
+    assert!(!totals.is_empty());
+    assert!(totals
+        .iter()
+        .all(|total| *total == Some(SAMPLE_KML.len() as u64)));
+}
+
+#[tokio::test]
+async fn oversized_file_is_rejected_before_download() {
+    let server = Server::run();
+    common::expect_device_sign_in(&server);
+
+    let mut config = common::mock_config(&server);
+    config.max_import_bytes = 1024;
+    let vault = SecretVault::in_memory();
+    let temp_dir = tempdir().unwrap();
+    let telemetry = TelemetryClient::new(temp_dir.path(), &config).unwrap();
+    let google = GoogleServices::maybe_new(&config, &vault, telemetry)
+        .expect("service creation")
+        .expect("oauth configured");
+    let device_flow = google.start_device_flow().await.expect("device flow");
+    google
+        .complete_device_flow(&device_flow.device_code, device_flow.interval_secs)
+        .await
+        .expect("sign in");
+
+    // No expectation is registered for the file body, so any download attempt fails the test.
+    let err = google
+        .download_file("huge-file", None, Some(300 * 1024 * 1024), None, |_, _| {})
+        .await
+        .expect_err("oversized file");
+    match err {
+        AppError::Config(message) => assert!(message.contains("limit is 1024 bytes")),
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn both_slot_downloads_overlap() {
+    let server = Server::run();
+    common::expect_device_sign_in(&server);
+    let delay = Duration::from_millis(400);
+    for file in ["file-a", "file-b"] {
+        server.expect(
+            Expectation::matching(all_of!(
+                request::method("GET"),
+                request::path(format!("/drive/v3/files/{file}"))
+            ))
+            .respond_with(delay_and_then(delay, status_code(200).body(SAMPLE_KML))),
+        );
+    }
+
+    let vault = SecretVault::in_memory();
+    let google = common::signed_in_services(&server, &vault).await;
+    let download = |file_id: &'static str| {
+        let google = &google;
+        async move {
+            let started = Instant::now();
+            let result = google
+                .download_file_to_disk(file_id, None, None, None, |_, _| {}, None)
+                .await
+                .expect("download");
+            (started, Instant::now(), result)
+        }
+    };
+    let began = Instant::now();
+    let ((a_start, a_end, a_file), (b_start, b_end, b_file)) =
+        tokio::join!(download("file-a"), download("file-b"));
+    assert!(
+        a_start < b_end && b_start < a_end,
+        "download windows overlap"
+    );
+    assert!(began.elapsed() < delay * 2);
+
+    let dir = tempdir().unwrap();
+    let mut connection = bootstrap(dir.path(), "both.db", &vault)
+        .expect("bootstrap db")
+        .context
+        .connection;
+    let project_id: i64 = connection
+        .query_row(
+            "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .expect("project id");
+    for (slot, file_id, temp) in [
+        (ListSlot::A, "file-a", a_file),
+        (ListSlot::B, "file-b", b_file),
+    ] {
+        let parsed = parse_kml(&std::fs::read(&temp.path).unwrap()).expect("parse rows");
+        let drive_file = DriveFileMetadata {
+            id: file_id.into(),
+            name: file_id.into(),
+            mime_type: "application/vnd.google-earth.kml+xml".into(),
+            modified_time: None,
+            size: Some(temp.received_bytes),
+            md5_checksum: Some(temp.checksum_md5.clone()),
+            source: None,
+        };
+        persist_rows(&mut connection, project_id, slot, &drive_file, &parsed.rows)
+            .expect("persist rows");
+    }
+    let populated: Vec<String> = connection
+        .prepare(
+            "SELECT l.slot FROM lists l JOIN raw_items r ON r.list_id = l.id
+            WHERE l.project_id = ?1 GROUP BY l.slot ORDER BY l.slot",
+        )
+        .unwrap()
+        .query_map([project_id], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(populated, vec!["A", "B"]);
+}
+
+#[tokio::test]
+async fn refreshes_drive_metadata_without_touching_rows() {
+    let server = Server::run();
+    common::expect_device_sign_in(&server);
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("GET"),
+            request::path("/drive/v3/files/drive-file")
+        ))
+        .respond_with(json_encoded(json!({
+            "id": "drive-file",
+            "name": "Renamed list",
+            "mimeType": "application/vnd.google-earth.kml+xml",
+            "modifiedTime": "2024-05-01T00:00:00Z",
+            "size": "4096",
+            "md5Checksum": "fresh-md5"
+        }))),
+    );
+
+    let vault = SecretVault::in_memory();
+    let google = common::signed_in_services(&server, &vault).await;
+    let dir = tempdir().unwrap();
+    let mut connection = bootstrap(dir.path(), "metadata.db", &vault)
+        .expect("bootstrap db")
+        .context
+        .connection;
+    let project_id: i64 = connection
+        .query_row(
+            "SELECT id FROM comparison_projects WHERE is_active = 1 LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .expect("project id");
+    let original = DriveFileMetadata {
+        id: "drive-file".into(),
+        name: "List A".into(),
+        mime_type: "application/vnd.google-earth.kml+xml".into(),
+        modified_time: Some("2024-01-01T00:00:00Z".into()),
+        size: Some(SAMPLE_KML.len() as u64),
+        md5_checksum: Some("stale-md5".into()),
+        source: None,
+    };
+    let parsed = parse_kml(SAMPLE_KML.as_bytes()).expect("parse rows");
+    persist_rows(
+        &mut connection,
+        project_id,
+        ListSlot::A,
+        &original,
+        &parsed.rows,
+    )
+    .expect("persist rows");
+    let raw_ids = |connection: &rusqlite::Connection| -> Vec<i64> {
+        let mut stmt = connection
+            .prepare("SELECT id FROM raw_items ORDER BY id")
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    };
+    let rows_before = raw_ids(&connection);
+
+    let linked = linked_drive_file(&connection, project_id, ListSlot::A)
+        .expect("stored selection")
+        .expect("slot A linked");
+    let live = google
+        .get_file_metadata(&linked.id)
+        .await
+        .expect("file metadata");
+    persist_drive_selection(&connection, project_id, ListSlot::A, Some(&live))
+        .expect("persist metadata");
+
+    let (name, modified, size, checksum): (String, String, i64, String) = connection
+        .query_row(
+            "SELECT drive_file_name, drive_modified_time, drive_file_size, drive_file_checksum
+            FROM lists WHERE project_id = ?1 AND slot = 'A'",
+            [project_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .expect("slot A");
+    assert_eq!(name, "Renamed list");
+    assert_eq!(modified, "2024-05-01T00:00:00Z");
+    assert_eq!(size, 4096);
+    assert_eq!(checksum, "fresh-md5");
+    assert_eq!(raw_ids(&connection), rows_before);
+    assert!(!rows_before.is_empty());
+}
@@ -0,0 +1,436 @@
+mod common;
+
+use httptest::matchers::{all_of, contains, request, url_decoded};
+use httptest::responders::{json_encoded, status_code};
+use httptest::{Expectation, Server};
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+use tempfile::tempdir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use tauri_app_lib::{GoogleServices, RefreshEvent, RefreshRegistry, SecretVault, TelemetryClient};
+
+use common::{expect_device_sign_in, mock_config, signed_in_services};
+
+#[tokio::test]
+async fn sign_out_revokes_refresh_token() {
+    let server = Server::run();
+    expect_device_sign_in(&server);
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("POST"),
+            request::path("/revoke"),
+            request::body(url_decoded(contains(("token", "ya29.refresh"))))
+        ))
+        .respond_with(status_code(200)),
+    );
+
+    let vault = SecretVault::in_memory();
+    let google = signed_in_services(&server, &vault).await;
+    assert!(vault
+        .has("google-oauth-token:importer@example.com")
+        .unwrap());
+
+    google.sign_out().await.expect("sign out");
+    assert!(!vault
+        .has("google-oauth-token:importer@example.com")
+        .unwrap());
+    assert!(google.list_accounts().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn sign_out_cancels_running_refresh_and_blocks_drive() {
+    let server = Server::run();
+    expect_device_sign_in(&server);
+    server.expect(
+        Expectation::matching(all_of!(request::method("POST"), request::path("/revoke")))
+            .respond_with(status_code(200)),
+    );
+
+    let vault = SecretVault::in_memory();
+    let google = signed_in_services(&server, &vault).await;
+    let refresh_runs = RefreshRegistry::default();
+    let runs = refresh_runs.clone();
+    google.set_sign_out_observer(Arc::new(move || {
+        runs.cancel();
+    }));
+
+    let run = refresh_runs.begin().expect("refresh starts");
+    google.sign_out().await.expect("sign out");
+    assert!(run.is_cancelled());
+    assert!(google.is_signed_out());
+
+    let err = google.list_kml_files(Some(5)).await.unwrap_err();
+    assert!(err.to_string().contains("sign-in required"), "{err}");
+}
+
+#[tokio::test]
+async fn sign_out_succeeds_when_revocation_fails() {
+    let server = Server::run();
+    expect_device_sign_in(&server);
+    server.expect(
+        Expectation::matching(all_of!(request::method("POST"), request::path("/revoke")))
+            .respond_with(status_code(500)),
+    );
+
+    let vault = SecretVault::in_memory();
+    let google = signed_in_services(&server, &vault).await;
+
+    assert!(vault.has("google-oauth-active-account").unwrap());
+
+    google.sign_out().await.expect("sign out");
+    assert!(!vault
+        .has("google-oauth-token:importer@example.com")
+        .unwrap());
+    assert!(!vault.has("google-oauth-active-account").unwrap());
+    assert!(google.list_accounts().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn current_identity_reuses_cached_profile() {
+    let server = Server::run();
+    // The userinfo expectation only allows the single call made during sign-in.
+    expect_device_sign_in(&server);
+
+    let vault = SecretVault::in_memory();
+    let google = signed_in_services(&server, &vault).await;
+
+    let first = google.current_identity(false).await.expect("identity");
+    let second = google.current_identity(false).await.expect("identity");
+    assert_eq!(first.email, "importer@example.com");
+    assert_eq!(second.email, first.email);
+}
+
+#[tokio::test]
+async fn custom_scopes_flow_into_authorization_url() {
+    let server = Server::run();
+    let mut config = mock_config(&server);
+    config.google_oauth_scopes = vec!["drive.file".into(), "email".into()];
+    let temp_dir = tempdir().unwrap();
+    let telemetry = TelemetryClient::new(temp_dir.path(), &config).unwrap();
+    let google = GoogleServices::maybe_new(&config, &SecretVault::in_memory(), telemetry)
+        .expect("service creation")
+        .expect("oauth configured");
+
+    let flow = google.start_loopback_flow().await.expect("loopback flow");
+    let url = reqwest::Url::parse(&flow.authorization_url).unwrap();
+    let scope = url
+        .query_pairs()
+        .find(|(key, _)| key == "scope")
+        .map(|(_, value)| value.to_string());
+    assert_eq!(
+        scope.as_deref(),
+        Some("https://www.googleapis.com/auth/drive.file email")
+    );
+}
+
+#[tokio::test]
+async fn scopes_without_drive_access_are_rejected() {
+    let server = Server::run();
+    let mut config = mock_config(&server);
+    config.google_oauth_scopes = vec!["openid".into(), "email".into()];
+    let temp_dir = tempdir().unwrap();
+    let telemetry = TelemetryClient::new(temp_dir.path(), &config).unwrap();
+
+    assert!(GoogleServices::maybe_new(&config, &SecretVault::in_memory(), telemetry).is_err());
+}
+
+#[tokio::test]
+async fn failed_refresh_notifies_observer_with_sanitized_reason() {
+    let server = Server::run();
+    expect_device_sign_in(&server);
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("POST"),
+            request::path("/token"),
+            request::body(url_decoded(contains(("grant_type", "refresh_token"))))
+        ))
+        .respond_with(status_code(400)),
+    );
+
+    let vault = SecretVault::in_memory();
+    let google = signed_in_services(&server, &vault).await;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    google.set_refresh_observer(Arc::new(move |event: RefreshEvent| {
+        sink.lock().unwrap().push(event);
+    }));
+
+    assert!(google.force_refresh().await.is_err());
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+        RefreshEvent::Failed { reason, .. } => {
+            assert!(reason.contains("failed to refresh google token"));
+            assert!(!reason.contains("ya29"));
+        }
+        other => panic!("unexpected refresh event: {other:?}"),
+    }
+    assert_eq!(events[0].event_name(), "auth://refresh_failed");
+}
+
+#[tokio::test]
+async fn successful_drive_call_clears_refresh_failure() {
+    let server = Server::run();
+    expect_device_sign_in(&server);
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("POST"),
+            request::path("/token"),
+            request::body(url_decoded(contains(("grant_type", "refresh_token"))))
+        ))
+        .respond_with(status_code(400)),
+    );
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("GET"),
+            request::path("/drive/v3/files")
+        ))
+        .respond_with(json_encoded(json!({ "files": [] }))),
+    );
+
+    let vault = SecretVault::in_memory();
+    let google = signed_in_services(&server, &vault).await;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    google.set_refresh_observer(Arc::new(move |event: RefreshEvent| {
+        sink.lock().unwrap().push(event.event_name());
+    }));
+
+    assert!(google.force_refresh().await.is_err());
+    assert!(google.last_refresh_failure().is_some());
+
+    google.list_kml_files(Some(5)).await.expect("list files");
+    assert_eq!(google.last_refresh_failure(), None);
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["auth://refresh_failed", "auth://refresh_recovered"]
+    );
+
+    let config = mock_config(&server);
+    let temp_dir = tempdir().unwrap();
+    let telemetry = TelemetryClient::new(temp_dir.path(), &config).unwrap();
+    let restored = GoogleServices::maybe_new(&config, &vault, telemetry)
+        .expect("service creation")
+        .expect("oauth configured");
+    assert_eq!(restored.last_refresh_failure(), None);
+}
+
+#[tokio::test]
+async fn offline_mode_fails_drive_calls_without_network() {
+    let server = Server::run();
+    expect_device_sign_in(&server);
+
+    let vault = SecretVault::in_memory();
+    let google = signed_in_services(&server, &vault).await;
+    google.set_offline(true);
+
+    let err = google.list_kml_files(Some(5)).await.unwrap_err();
+    assert!(err.to_string().contains("offline"), "{err}");
+    assert!(google.current_identity(false).await.is_err());
+    assert_eq!(google.last_refresh_failure(), None);
+}
+
+fn expect_account(server: &Server, device_code: &str, access_token: &str, email: &str) {
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("POST"),
+            request::path("/token"),
+            request::body(url_decoded(contains((
+                "device_code",
+                device_code.to_string()
+            ))))
+        ))
+        .respond_with(json_encoded(json!({
+            "access_token": access_token,
+            "refresh_token": format!("{access_token}.refresh"),
+            "expires_in": 3600,
+            "scope": "drive.readonly",
+            "token_type": "Bearer"
+        }))),
+    );
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("GET"),
+            request::path("/userinfo"),
+            request::headers(contains((
+                "authorization",
+                format!("Bearer {access_token}")
+            )))
+        ))
+        .respond_with(json_encoded(json!({
+            "email": email,
+            "name": null,
+            "picture": null
+        }))),
+    );
+}
+
+#[tokio::test]
+async fn stores_multiple_accounts_and_switches_between_them() {
+    let server = Server::run();
+    expect_account(
+        &server,
+        "device-personal",
+        "ya29.personal",
+        "me@example.com",
+    );
+    expect_account(&server, "device-work", "ya29.work", "me@work.example.com");
+
+    let config = mock_config(&server);
+    let vault = SecretVault::in_memory();
+    let temp_dir = tempdir().unwrap();
+    let telemetry = TelemetryClient::new(temp_dir.path(), &config).unwrap();
+    let google = GoogleServices::maybe_new(&config, &vault, telemetry)
+        .expect("service creation")
+        .expect("oauth configured");
+
+    google
+        .complete_device_flow("device-personal", 1)
+        .await
+        .expect("personal sign in");
+    google
+        .complete_device_flow("device-work", 1)
+        .await
+        .expect("work sign in");
+
+    assert!(vault.has("google-oauth-token:me@example.com").unwrap());
+    assert!(vault.has("google-oauth-token:me@work.example.com").unwrap());
+    let accounts = google.list_accounts().unwrap();
+    assert_eq!(accounts.len(), 2);
+    assert!(accounts
+        .iter()
+        .any(|account| account.email == "me@work.example.com" && account.active));
+    assert_eq!(
+        google.ensure_token().await.unwrap().access_token,
+        "ya29.work"
+    );
+
+    let identity = google
+        .switch_account("me@example.com")
+        .await
+        .expect("switch account");
+    assert_eq!(identity.email, "me@example.com");
+    assert_eq!(
+        google.ensure_token().await.unwrap().access_token,
+        "ya29.personal"
+    );
+    assert!(google.switch_account("stranger@example.com").await.is_err());
+}
+
+async fn send_loopback_request(redirect_url: &str, chunks: &[&str]) -> String {
+    let url = reqwest::Url::parse(redirect_url).unwrap();
+    let mut stream = TcpStream::connect((url.host_str().unwrap(), url.port().unwrap()))
+        .await
+        .unwrap();
+    for chunk in chunks {
+        stream.write_all(chunk.as_bytes()).await.unwrap();
+        stream.flush().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+fn expect_code_exchange(server: &Server, code: &str) {
+    server.expect(
+        Expectation::matching(all_of!(
+            request::method("POST"),
+            request::path("/token"),
+            request::body(url_decoded(contains(("code", code.to_string()))))
+        ))
+        .respond_with(json_encoded(json!({
+            "access_token": "ya29.loopback",
+            "refresh_token": "ya29.loopback.refresh",
+            "expires_in": 3600,
+            "scope": "drive.readonly",
+            "token_type": "Bearer"
+        }))),
+    );
+    server.expect(
+        Expectation::matching(all_of!(request::method("GET"), request::path("/userinfo")))
+            .respond_with(json_encoded(json!({
+                "email": "loopback@example.com",
+                "name": null,
+                "picture": null
+            }))),
+    );
+}
+
+#[tokio::test]
+async fn loopback_flow_ignores_favicon_requests() {
+    let server = Server::run();
+    expect_code_exchange(&server, "auth-code");
+
+    let config = mock_config(&server);
+    let temp_dir = tempdir().unwrap();
+    let telemetry = TelemetryClient::new(temp_dir.path(), &config).unwrap();
+    let google = GoogleServices::maybe_new(&config, &SecretVault::in_memory(), telemetry)
+        .expect("service creation")
+        .expect("oauth configured");
+
+    let flow = google.start_loopback_flow().await.expect("loopback flow");
+    let state = reqwest::Url::parse(&flow.authorization_url)
+        .unwrap()
+        .query_pairs()
+        .find(|(key, _)| key == "state")
+        .map(|(_, value)| value.to_string())
+        .unwrap();
+
+    let favicon = send_loopback_request(
+        &flow.redirect_url,
+        &["GET /favicon.ico HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n"],
+    )
+    .await;
+    assert!(favicon.starts_with("HTTP/1.1 404"), "{favicon}");
+
+    let callback = format!(
+        "GET /auth/callback?code=auth-code&state={state} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n"
+    );
+    let approved = send_loopback_request(&flow.redirect_url, &[&callback]).await;
+    assert!(approved.starts_with("HTTP/1.1 200"), "{approved}");
+
+    let identity = google
+        .complete_loopback_flow(Some(5))
+        .await
+        .expect("loopback sign in");
+    assert_eq!(identity.email, "loopback@example.com");
+}
+
+#[tokio::test]
+async fn loopback_flow_reads_request_line_split_across_packets() {
+    let server = Server::run();
+    let code = format!("4/{}", "x".repeat(6000));
+    expect_code_exchange(&server, &code);
+
+    let config = mock_config(&server);
+    let temp_dir = tempdir().unwrap();
+    let telemetry = TelemetryClient::new(temp_dir.path(), &config).unwrap();
+    let google = GoogleServices::maybe_new(&config, &SecretVault::in_memory(), telemetry)
+        .expect("service creation")
+        .expect("oauth configured");
+
+    let flow = google.start_loopback_flow().await.expect("loopback flow");
+    let state = reqwest::Url::parse(&flow.authorization_url)
+        .unwrap()
+        .query_pairs()
+        .find(|(key, _)| key == "state")
+        .map(|(_, value)| value.to_string())
+        .unwrap();
+
+    let first = format!("GET /auth/callback?code={}", code.replace('/', "%2F"));
+    let second = format!("&state={state} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n");
+    let approved = send_loopback_request(&flow.redirect_url, &[&first, &second]).await;
+    assert!(approved.starts_with("HTTP/1.1 200"), "{approved}");
+
+    let identity = google
+        .complete_loopback_flow(Some(5))
+        .await
+        .expect("loopback sign in");
+    assert_eq!(identity.email, "loopback@example.com");
+}
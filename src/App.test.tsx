@@ -17,6 +17,9 @@ vi.mock("@tauri-apps/api/core", () => ({
             telemetry_enabled: true,
             places_rate_limit_qps: 3,
             telemetry_salt: "salt",
+            map_style: "streets",
+            normalization_cache_ttl_hours: 72,
+            offline: false,
           },
           config: {
             telemetry_endpoint: null,
@@ -25,12 +28,14 @@ vi.mock("@tauri-apps/api/core", () => ({
             telemetry_batch_size: 10,
             telemetry_buffer_max_bytes: 1024,
             telemetry_buffer_max_files: 3,
+            telemetry_buffer_max_age_days: 30,
             places_rate_limit_qps: 3,
             database_file_name: "test.db",
             has_google_places_key: false,
             has_maptiler_key: false,
             drive_import_enabled: true,
             drive_picker_page_size: 5,
+            max_import_bytes: 1024,
           },
         });
       case "compare_lists":
@@ -4,7 +4,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { save } from "@tauri-apps/plugin-dialog";
 import { openUrl } from "@tauri-apps/plugin-opener";
-import type { FoundationHealth, RuntimeSettings } from "./types/foundation";
+import type { CommandError, FoundationHealth, RuntimeSettings } from "./types/foundation";
 import type {
   ComparisonProjectRecord,
   ComparisonSegmentKey,
@@ -62,6 +62,7 @@ type RefreshProgressPayload = {
   resolved: number;
   pending: number;
   rate_limit_qps: number;
+  resumed_from?: number | null;
   message: string;
 };
 
@@ -124,6 +125,8 @@ type NormalizationStats = {
     network_errors: number;
     other_errors: number;
   };
+  rate_limit_qps: number;
+  resumed_from?: number | null;
 };
 
 const checklistTemplate: ChecklistItem[] = [
@@ -583,6 +586,24 @@ function App() {
     };
   }, [driveEnabled, identity]);
 
+  useEffect(() => {
+    if (!driveEnabled) {
+      return;
+    }
+    const failed = listen<{ reason: string }>("auth://refresh_failed", (event) => {
+      if (event.payload?.reason) {
+        setSignInError(event.payload.reason);
+      }
+    });
+    const recovered = listen("auth://refresh_recovered", () => {
+      setSignInError(null);
+    });
+    return () => {
+      void failed.then((unlisten) => unlisten());
+      void recovered.then((unlisten) => unlisten());
+    };
+  }, [driveEnabled]);
+
   useEffect(() => {
     let mounted = true;
     const subscription = listen<ImportProgressPayload>("import://progress", (event) => {
@@ -669,6 +690,18 @@ function App() {
     };
   }, []);
 
+  useEffect(() => {
+    const subscription = listen<RuntimeSettings>("settings://changed", (event) => {
+      setRuntimeSettings(event.payload);
+      setPendingRateLimit(event.payload.places_rate_limit_qps);
+      telemetry.setEnabled(event.payload.telemetry_enabled);
+      telemetry.setInstallSalt(event.payload.telemetry_salt);
+    });
+    return () => {
+      void subscription.then((unlisten) => unlisten());
+    };
+  }, []);
+
   useEffect(() => {
     if (!foundationHealth) {
       setRuntimeSettings(null);
@@ -2488,9 +2521,21 @@ function normalizeError(error: unknown): string {
   if (typeof error === "string") {
     return error;
   }
+  if (isCommandError(error)) {
+    return error.message;
+  }
   return "Unexpected error";
 }
 
+function isCommandError(error: unknown): error is CommandError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as CommandError).kind === "string" &&
+    typeof (error as CommandError).message === "string"
+  );
+}
+
 async function hashIdentifier(value: string): Promise<string> {
   try {
     if (window.crypto?.subtle) {
@@ -21,6 +21,9 @@ const baseHealth: FoundationHealth = {
     telemetry_enabled: true,
     places_rate_limit_qps: 3,
     telemetry_salt: "salt",
+    map_style: "streets",
+    normalization_cache_ttl_hours: 72,
+    offline: false,
   },
   config: {
     telemetry_endpoint: null,
@@ -29,6 +32,7 @@ const baseHealth: FoundationHealth = {
     telemetry_batch_size: 5,
     telemetry_buffer_max_bytes: 1000,
     telemetry_buffer_max_files: 2,
+    telemetry_buffer_max_age_days: 30,
     places_rate_limit_qps: 3,
     normalization_cache_ttl_hours: 72,
     database_file_name: "test.db",
@@ -36,6 +40,7 @@ const baseHealth: FoundationHealth = {
     has_maptiler_key: false,
     drive_import_enabled: true,
     drive_picker_page_size: 10,
+    max_import_bytes: 1024,
   },
 };
 
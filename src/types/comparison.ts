@@ -20,6 +20,31 @@ export type PlaceComparisonRow = {
   lng: number;
   types: string[];
   lists: ListSlot[];
+  fuzzy_match?: string;
+  last_checked_at?: string | null;
+  resolution_source?: "api" | "cache" | "places_table" | "provided" | "synthetic" | null;
+};
+
+export type PendingRow = {
+  source_row_hash: string;
+  title?: string | null;
+  lat?: number | null;
+  lng?: number | null;
+};
+
+export type PendingRows = {
+  slot: ListSlot;
+  rows: PendingRow[];
+};
+
+export type NearestPlace = PlaceComparisonRow & {
+  distance_m: number;
+};
+
+export type LooseOnlyReport = {
+  radius_m: number;
+  loose_only_a: PlaceComparisonRow[];
+  loose_only_b: PlaceComparisonRow[];
 };
 
 export type ComparisonSegmentPage = {
@@ -27,6 +52,15 @@ export type ComparisonSegmentPage = {
   total: number;
   page: number;
   page_size: number;
+  total_pages: number;
+  has_next: boolean;
+  has_prev: boolean;
+  next_cursor?: SegmentCursor | null;
+};
+
+export type SegmentCursor = {
+  name: string;
+  place_id: string;
 };
 
 export type ComparisonProjectInfo = {
@@ -64,9 +98,13 @@ export type ComparisonProjectRecord = {
   list_b_imported_at?: string | null;
   list_a_drive_file?: DriveFileMetadata | null;
   list_b_drive_file?: DriveFileMetadata | null;
+  places_rate_limit_qps?: number | null;
 };
 
+export type MapStyle = "streets" | "satellite" | "dark" | "topo";
+
 export type MapStyleDescriptor = {
+  style?: MapStyle;
   style_url?: string | null;
 };
 
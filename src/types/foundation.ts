@@ -1,3 +1,5 @@
+import type { MapStyle } from "./comparison";
+
 export type PublicAppConfig = {
   telemetry_endpoint: string | null;
   telemetry_enabled_by_default: boolean;
@@ -5,6 +7,7 @@ export type PublicAppConfig = {
   telemetry_batch_size: number;
   telemetry_buffer_max_bytes: number;
   telemetry_buffer_max_files: number;
+  telemetry_buffer_max_age_days: number;
   places_rate_limit_qps: number;
   normalization_cache_ttl_hours: number;
   database_file_name: string;
@@ -12,12 +15,16 @@ export type PublicAppConfig = {
   has_maptiler_key: boolean;
   drive_import_enabled: boolean;
   drive_picker_page_size: number;
+  max_import_bytes: number;
 };
 
 export type RuntimeSettings = {
   telemetry_enabled: boolean;
   places_rate_limit_qps: number;
   telemetry_salt: string;
+  map_style: MapStyle;
+  normalization_cache_ttl_hours: number;
+  offline: boolean;
 };
 
 export type FoundationHealth = {
@@ -30,3 +37,20 @@ export type FoundationHealth = {
   config: PublicAppConfig;
   settings: RuntimeSettings;
 };
+
+export type CommandErrorKind =
+  | "network"
+  | "rate_limited"
+  | "parse"
+  | "storage"
+  | "database"
+  | "config"
+  | "keychain"
+  | "cancelled"
+  | "internal";
+
+export type CommandError = {
+  kind: CommandErrorKind;
+  message: string;
+  details: string[];
+};